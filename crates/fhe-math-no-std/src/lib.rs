@@ -0,0 +1,49 @@
+#![crate_name = "fhe_math_no_std"]
+#![crate_type = "lib"]
+#![no_std]
+#![warn(missing_docs, unused_imports)]
+
+//! Uses of the `zq`, `ntt` and `rns` modules of `fhe-math` in a `#![no_std]`
+//! crate, which only relies on `alloc`. The integration tests run them.
+//!
+//! Build this crate on its own, so that the `std` feature of `fhe-math` is not
+//! enabled by the other crates of the workspace, and for a target without
+//! `std`, e.g. with
+//! `cargo build -p fhe-math-no-std --no-default-features --target x86_64-unknown-none`.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use fhe_math::{ntt::NttOperator, rns::RnsContext, zq::Modulus, Result};
+use ndarray::aview1;
+use num_bigint::BigUint;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Reduce the values modulo `p`.
+pub fn reduce(p: u64, values: &[u64]) -> Result<Vec<u64>> {
+    let q = Modulus::new(p)?;
+    Ok(values.iter().map(|a| q.reduce(*a)).collect())
+}
+
+/// Sample a random vector of `size` elements modulo `p` from `seed`, and
+/// check that a forward then a backward NTT gives it back. Returns `None` if
+/// `p` does not support an NTT of this size.
+pub fn ntt_round_trip(p: u64, size: usize, seed: [u8; 32]) -> Result<Option<bool>> {
+    let q = Modulus::new(p)?;
+    let Some(op) = NttOperator::new(&q, size) else {
+        return Ok(None);
+    };
+    let a = q.random_vec(size, &mut ChaCha8Rng::from_seed(seed));
+    let mut b = a.clone();
+    op.forward(&mut b);
+    op.backward(&mut b);
+    Ok(Some(a == b))
+}
+
+/// Project `value` on the RNS basis formed by `moduli` and lift it back.
+pub fn rns_round_trip(moduli: &[u64], value: &BigUint) -> Result<BigUint> {
+    let rns = RnsContext::new(moduli)?;
+    let rests = rns.project(value);
+    Ok(rns.lift(aview1(&rests)))
+}
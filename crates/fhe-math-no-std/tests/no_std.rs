@@ -0,0 +1,43 @@
+use fhe_math::Error;
+use fhe_math_no_std::{ntt_round_trip, reduce, rns_round_trip};
+use num_bigint::BigUint;
+
+#[test]
+fn reduction() -> Result<(), Error> {
+    assert_eq!(
+        reduce(1153, &[0, 1152, 1153, 1154, u64::MAX])?,
+        [0, 1152, 0, 1, u64::MAX % 1153]
+    );
+    assert_eq!(reduce(0, &[]), Err(Error::InvalidModulus(0)));
+    Ok(())
+}
+
+#[test]
+fn ntt() -> Result<(), Error> {
+    for p in [1153, 4611686018326724609] {
+        for seed in 0..4 {
+            assert_eq!(ntt_round_trip(p, 16, [seed; 32])?, Some(true));
+        }
+    }
+    assert_eq!(ntt_round_trip(1153, 4096, [0; 32])?, None);
+    Ok(())
+}
+
+#[test]
+fn rns() -> Result<(), Error> {
+    let moduli = [1153, 4611686018326724609, 4611686018309947393];
+    let product = moduli
+        .iter()
+        .map(|p| BigUint::from(*p))
+        .product::<BigUint>();
+    for value in [
+        BigUint::from(0u64),
+        BigUint::from(1u64),
+        &product - 1u64,
+        &product >> 7,
+    ] {
+        assert_eq!(rns_round_trip(&moduli, &value)?, value);
+    }
+    assert_eq!(rns_round_trip(&[], &product), Err(Error::EmptyModuli));
+    Ok(())
+}
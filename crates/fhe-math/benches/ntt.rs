@@ -8,12 +8,15 @@ pub fn ntt_benchmark(c: &mut Criterion) {
     group.sample_size(50);
     let mut rng = thread_rng();
 
-    for vector_size in [1024usize, 4096].iter() {
+    for vector_size in [1024usize, 4096, 8192, 16384, 32768].iter() {
         for p in [4611686018326724609u64, 40961u64] {
             let p_nbits = 64 - p.leading_zeros();
             let q = Modulus::new(p).unwrap();
             let mut a = q.random_vec(*vector_size, &mut rng);
-            let op = NttOperator::new(&Arc::new(q), *vector_size).unwrap();
+            let Some(op) = NttOperator::new(&Arc::new(q), *vector_size) else {
+                // The modulus does not support the NTT of this size.
+                continue;
+            };
 
             group.bench_function(
                 BenchmarkId::new("forward", format!("{vector_size}/{p_nbits}")),
@@ -51,6 +51,28 @@ pub fn rns_benchmark(c: &mut Criterion) {
         },
     );
 
+    let rns = RnsContext::new(&q).unwrap();
+    let values = (0..4096)
+        .map(|_| {
+            let rests = q.iter().map(|qi| rng.next_u64() % *qi).collect::<Vec<_>>();
+            rns.lift((&rests).into())
+        })
+        .collect::<Vec<_>>();
+
+    group.bench_function(
+        BenchmarkId::new("project", format!("{}/4096", q.len())),
+        |b| {
+            b.iter(|| values.iter().map(|v| rns.project(v)).collect::<Vec<_>>());
+        },
+    );
+
+    group.bench_function(
+        BenchmarkId::new("project_many", format!("{}/4096", q.len())),
+        |b| {
+            b.iter(|| rns.project_many(&values));
+        },
+    );
+
     group.finish();
 }
 
@@ -1,7 +1,9 @@
 use criterion::measurement::WallTime;
 use criterion::{criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
-use fhe_math::rq::*;
+use fhe_math::rq::{traits::TryConvertFrom, *};
+use fhe_traits::Serialize;
 use itertools::{izip, Itertools};
+use num_bigint::BigUint;
 use rand::thread_rng;
 use std::{
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
@@ -173,6 +175,202 @@ pub fn rq_dot_product(c: &mut Criterion) {
     }
 }
 
+pub fn rq_mul_accumulate(c: &mut Criterion) {
+    let mut group = create_group(c, "rq_mul_accumulate".to_string());
+    let mut rng = thread_rng();
+    let ctx = Arc::new(Context::new(&MODULI[..4], 4096).unwrap());
+    let p_vec = (0..8)
+        .map(|_| Poly::random(&ctx, Representation::Ntt, &mut rng))
+        .collect_vec();
+    let q_vec = (0..8)
+        .map(|_| Poly::random(&ctx, Representation::NttShoup, &mut rng))
+        .collect_vec();
+
+    group.bench_function(
+        BenchmarkId::from_parameter(format!("8/4096/{}", ctx.modulus().bits())),
+        |b| {
+            b.iter(|| mul_accumulate(&p_vec, &q_vec));
+        },
+    );
+}
+
+pub fn rq_scale(c: &mut Criterion) {
+    let mut group = create_group(c, "rq_scale".to_string());
+    let mut rng = thread_rng();
+    for degree in DEGREE {
+        let from = Arc::new(Context::new(&MODULI[..2], *degree).unwrap());
+        let to = Arc::new(Context::new(&MODULI[2..], *degree).unwrap());
+        let scaler = scaler::Scaler::new(
+            &from,
+            &to,
+            fhe_math::rns::ScalingFactor::new(&3u64.into(), &1001u64.into()),
+        )
+        .unwrap();
+        let p = Poly::random(&from, Representation::PowerBasis, &mut rng);
+        let mut out = Poly::zero(&to, Representation::PowerBasis);
+
+        group.bench_function(BenchmarkId::new("scale", degree), |b| {
+            b.iter(|| p.scale(&scaler));
+        });
+
+        group.bench_function(BenchmarkId::new("scale_into", degree), |b| {
+            b.iter(|| p.scale_into(&scaler, &mut out));
+        });
+    }
+
+    let degree = 8192;
+    let from = Arc::new(Context::new(&MODULI[..2], degree).unwrap());
+    let to = Arc::new(Context::new(&MODULI[2..], degree).unwrap());
+    let scaler = scaler::Scaler::new(
+        &from,
+        &to,
+        fhe_math::rns::ScalingFactor::new(&3u64.into(), &1001u64.into()),
+    )
+    .unwrap();
+    for size in [2, 4] {
+        let polys = (0..size)
+            .map(|_| Poly::random(&from, Representation::PowerBasis, &mut rng))
+            .collect_vec();
+
+        group.bench_function(
+            BenchmarkId::new("scale_each", format!("{size}/{degree}")),
+            |b| {
+                b.iter(|| polys.iter().map(|p| p.scale(&scaler)).collect_vec());
+            },
+        );
+
+        group.bench_function(
+            BenchmarkId::new("scale_batch", format!("{size}/{degree}")),
+            |b| {
+                b.iter(|| scaler.scale_batch(&polys));
+            },
+        );
+    }
+}
+
+pub fn rq_convert_biguint(c: &mut Criterion) {
+    let mut group = create_group(c, "rq_convert_biguint".to_string());
+    let mut rng = thread_rng();
+    let degree = 8192;
+    let ctx = Arc::new(Context::new(MODULI, degree).unwrap());
+    let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+    let values = Vec::<BigUint>::from(&p);
+
+    group.bench_function(
+        BenchmarkId::from_parameter(format!("{}/{}", degree, ctx.modulus().bits())),
+        |b| {
+            b.iter(|| {
+                Poly::try_convert_from(values.as_slice(), &ctx, false, Representation::PowerBasis)
+            });
+        },
+    );
+}
+
+pub fn rq_residues(c: &mut Criterion) {
+    let mut group = create_group(c, "rq_residues".to_string());
+    let mut rng = thread_rng();
+    for degree in [4096, 16384, 32768] {
+        for num_moduli in [3, 12] {
+            let ctx = ContextBuilder::new()
+                .degree(degree)
+                .generate_moduli(&vec![60; num_moduli])
+                .build()
+                .unwrap();
+            let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+            let q = Poly::random(&ctx, Representation::Ntt, &mut rng);
+
+            // With the `rayon` feature, compare the sequential and the parallel
+            // iterations over the residues.
+            #[cfg(feature = "rayon")]
+            let thresholds = [("seq", usize::MAX), ("par", 0)];
+            #[cfg(not(feature = "rayon"))]
+            let thresholds = [("seq", usize::MAX)];
+
+            for (name, _threshold) in thresholds {
+                #[cfg(feature = "rayon")]
+                set_parallel_threshold(_threshold);
+                let parameter = format!("{name}/{degree}/{num_moduli}");
+
+                group.bench_function(BenchmarkId::new("ntt", &parameter), |b| {
+                    b.iter(|| {
+                        p.change_representation(Representation::PowerBasis);
+                        p.change_representation(Representation::Ntt);
+                    });
+                });
+
+                group.bench_function(BenchmarkId::new("mul_assign", &parameter), |b| {
+                    b.iter(|| p *= &q);
+                });
+
+                group.bench_function(BenchmarkId::new("shoup", &parameter), |b| {
+                    b.iter(|| {
+                        p.change_representation(Representation::NttShoup);
+                        p.change_representation(Representation::Ntt);
+                    });
+                });
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    set_parallel_threshold(DEFAULT_PARALLEL_THRESHOLD);
+}
+
+pub fn rq_change_representation_batch(c: &mut Criterion) {
+    let mut group = create_group(c, "rq_change_representation_batch".to_string());
+    let mut rng = thread_rng();
+    let degree = 8192;
+    let ctx = Arc::new(Context::new(&MODULI[..3], degree).unwrap());
+    let mut polys = (0..64)
+        .map(|_| Poly::random(&ctx, Representation::PowerBasis, &mut rng))
+        .collect_vec();
+
+    group.bench_function(BenchmarkId::new("per_poly", degree), |b| {
+        b.iter(|| {
+            for to in [Representation::Ntt, Representation::PowerBasis] {
+                polys
+                    .iter_mut()
+                    .for_each(|p| p.change_representation(to.clone()));
+            }
+        });
+    });
+
+    group.bench_function(BenchmarkId::new("batch", degree), |b| {
+        b.iter(|| {
+            for to in [Representation::Ntt, Representation::PowerBasis] {
+                change_representation_batch(&mut polys, to).unwrap();
+            }
+        });
+    });
+
+    group.finish();
+}
+
+pub fn rq_serialize(c: &mut Criterion) {
+    let mut group = create_group(c, "rq_serialize".to_string());
+    let mut rng = thread_rng();
+    let ctx = Arc::new(Context::new(MODULI, 8192).unwrap());
+    for representation in [Representation::PowerBasis, Representation::Ntt] {
+        let p = Poly::random(&ctx, representation.clone(), &mut rng);
+        let parameter = format!("{representation:?}");
+
+        group.bench_function(BenchmarkId::new("to_bytes", &parameter), |b| {
+            b.iter(|| p.to_bytes());
+        });
+
+        // Serializing into a buffer with enough capacity does not allocate.
+        let mut out = Vec::with_capacity(p.to_bytes().len());
+        group.bench_function(BenchmarkId::new("serialize_into", &parameter), |b| {
+            b.iter(|| {
+                out.clear();
+                p.serialize_into(&mut out);
+            });
+        });
+    }
+
+    group.finish();
+}
+
 pub fn rq_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("rq");
     group.warm_up_time(Duration::from_millis(100));
@@ -236,6 +434,21 @@ pub fn rq_benchmark(c: &mut Criterion) {
                 },
             );
 
+            group.bench_function(
+                BenchmarkId::new(
+                    "change_representation/PowerBasis_to_NttShoup",
+                    format!("{}/{}", degree, ctx.modulus().bits()),
+                ),
+                |b| {
+                    b.iter(|| {
+                        unsafe {
+                            p.override_representation(Representation::PowerBasis);
+                        }
+                        p.change_representation(Representation::NttShoup)
+                    });
+                },
+            );
+
             p.change_representation(Representation::Ntt);
             q.change_representation(Representation::Ntt);
 
@@ -280,6 +493,19 @@ pub fn rq_benchmark(c: &mut Criterion) {
                         });
                     },
                 );
+
+                group.bench_function(
+                    BenchmarkId::new(
+                        "change_representation/PowerBasis_to_NttShoup_vt",
+                        format!("{}/{}", degree, ctx.modulus().bits()),
+                    ),
+                    |b| {
+                        b.iter(|| {
+                            p.override_representation(Representation::PowerBasis);
+                            p.change_representation(Representation::NttShoup)
+                        });
+                    },
+                );
             }
         }
     }
@@ -287,5 +513,16 @@ pub fn rq_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(rq, rq_op_benchmark, rq_dot_product, rq_benchmark);
+criterion_group!(
+    rq,
+    rq_op_benchmark,
+    rq_dot_product,
+    rq_mul_accumulate,
+    rq_scale,
+    rq_convert_biguint,
+    rq_residues,
+    rq_change_representation_batch,
+    rq_serialize,
+    rq_benchmark
+);
 criterion_main!(rq);
@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use fhe_math::rq::*;
+use std::{sync::Arc, time::Duration};
+
+static MODULI: &[u64; 4] = &[
+    562949954093057,
+    4611686018326724609,
+    4611686018309947393,
+    4611686018282684417,
+];
+
+static DEGREE: &[usize] = &[1024, 4096, 8192];
+
+pub fn rq_mul_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rq_mul");
+    group.warm_up_time(Duration::from_millis(100));
+    group.measurement_time(Duration::from_secs(1));
+
+    for degree in DEGREE {
+        for nmoduli in 1..=MODULI.len() {
+            let ctx = Arc::new(Context::new(&MODULI[..nmoduli], *degree).unwrap());
+            let (p, mut q) = Poly::random_pair(&ctx, Representation::Ntt);
+
+            group.bench_function(
+                BenchmarkId::new("mul", format!("{}/{}", degree, ctx.modulus().bits())),
+                |b| {
+                    b.iter(|| &p * &q);
+                },
+            );
+
+            unsafe { q.allow_variable_time_computations() }
+            group.bench_function(
+                BenchmarkId::new("mul_vt", format!("{}/{}", degree, ctx.modulus().bits())),
+                |b| {
+                    b.iter(|| &p * &q);
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(rq_mul, rq_mul_benchmark);
+criterion_main!(rq_mul);
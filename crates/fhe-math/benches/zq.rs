@@ -9,7 +9,7 @@ pub fn zq_benchmark(c: &mut Criterion) {
     let p = 4611686018326724609;
     let mut rng = thread_rng();
 
-    for vector_size in [1024usize, 4096].iter() {
+    for vector_size in [1024usize, 4096, 8192, 16384, 32768].iter() {
         let q = Modulus::new(p).unwrap();
         let mut a = q.random_vec(*vector_size, &mut rng);
         let c = q.random_vec(*vector_size, &mut rng);
@@ -32,6 +32,8 @@ pub fn zq_benchmark(c: &mut Criterion) {
             b.iter(|| q.neg_vec(&mut a));
         });
 
+        // mul_vec is not vectorized: this measures the scalar code, which was
+        // faster than an AVX2 kernel; see the comment in `Modulus::mul_vec`.
         group.bench_function(BenchmarkId::new("mul_vec", vector_size), |b| {
             b.iter(|| q.mul_vec(&mut a, &c));
         });
@@ -40,6 +42,14 @@ pub fn zq_benchmark(c: &mut Criterion) {
             b.iter(|| q.mul_vec_vt(&mut a, &c));
         });
 
+        let mut a_mont = a.clone();
+        let mut c_mont = c.clone();
+        q.to_montgomery_vec(&mut a_mont);
+        q.to_montgomery_vec(&mut c_mont);
+        group.bench_function(BenchmarkId::new("mont_mul_vec", vector_size), |b| {
+            b.iter(|| q.mont_mul_vec(&mut a_mont, &c_mont));
+        });
+
         group.bench_function(BenchmarkId::new("mul_shoup_vec", vector_size), |b| {
             b.iter(|| q.mul_shoup_vec(&mut a, &c, &c_shoup));
         });
@@ -1,46 +1,274 @@
-use thiserror::Error;
+use crate::{ntt::NttVariant, rns::Rounding};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
 
+#[cfg(feature = "std")]
 use crate::rq::Representation;
 
 /// The Result type for this library.
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Enum encapsulation all the possible errors from this library.
-#[derive(Debug, Error, PartialEq, Eq)]
+///
+/// The variants which hold a representation of a polynomial are only
+/// available with the `std` feature, so the enum is non-exhaustive.
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
     /// Indicates an invalid modulus
-    #[error("Invalid modulus: modulus {0} should be between 2 and (1 << 62) - 1.")]
     InvalidModulus(u64),
 
     /// Indicates an error in the serialization / deserialization.
-    #[error("{0}")]
     Serialization(String),
 
     /// Indicates that there is no more contexts to switch to.
-    #[error("This is the last context.")]
     NoMoreContext,
 
     /// Indicates that the provided context is invalid.
-    #[error("Invalid context provided.")]
     InvalidContext,
 
     /// Indicates an incorrect representation.
-    #[error("Incorrect representation: got {0:?}, expected {1:?}.")]
+    #[cfg(feature = "std")]
     IncorrectRepresentation(Representation, Representation),
 
     /// Indicates that the seed size is incorrect.
-    #[error("Invalid seed: got {0} bytes, expected {1} bytes.")]
     InvalidSeedSize(usize, usize),
 
+    /// Indicates that the degree is invalid.
+    InvalidDegree(usize),
+
+    /// Indicates that a modulus does not support the NTT of the requested
+    /// degree.
+    UnsupportedNtt {
+        /// The modulus.
+        modulus: u64,
+        /// The degree of the NTT.
+        degree: usize,
+    },
+
+    /// Indicates that a string does not name a representation.
+    UnknownRepresentation(String),
+
+    /// Indicates that the representation is required but not specified.
+    MissingRepresentation,
+
+    /// Indicates an incorrect number of values.
+    InvalidLength(usize, usize),
+
+    /// Indicates that there are more values than the polynomial can hold.
+    TooManyValues(usize),
+
+    /// Indicates that an array does not have the expected shape.
+    InvalidShape(Vec<usize>, Vec<usize>),
+
+    /// Indicates invalid parameters for sampling a polynomial.
+    Sampling(String),
+
+    /// Indicates that the representation of a polynomial cannot be changed.
+    #[cfg(feature = "std")]
+    InvalidTransition(Representation, Representation),
+
+    /// Indicates that a polynomial has lazy coefficients, which the operation
+    /// does not support.
+    LazyCoefficients,
+
+    /// Indicates that an input which must hold at least one value is empty.
+    EmptyInput,
+
+    /// Indicates that a polynomial is not invertible.
+    NotInvertible,
+
+    /// Indicates that a coefficient is out of the range that can be encoded.
+    CoefficientOutOfRange {
+        /// The bound on the absolute value of the coefficients.
+        bound: u64,
+    },
+
+    /// Indicates that a rounding mode is not supported in constant time for a
+    /// scaling factor.
+    UnsupportedRounding(Rounding),
+
+    /// Indicates that a list of moduli is empty.
+    EmptyModuli,
+
+    /// Indicates that moduli are not pairwise coprime.
+    NonCoprimeModuli,
+
+    /// Indicates that there are not enough primes to reach a target modulus.
+    NotEnoughPrimes,
+
+    /// Indicates an invalid number of moduli.
+    InvalidNumberOfModuli {
+        /// The number of moduli.
+        got: usize,
+        /// The maximum number of moduli.
+        max: usize,
+    },
+
+    /// Indicates that there are not enough rests.
+    NotEnoughRests {
+        /// The number of rests.
+        got: usize,
+        /// The minimum number of rests.
+        expected: usize,
+    },
+
+    /// Indicates that a modulus index is out of range.
+    InvalidModulusIndex {
+        /// The index.
+        index: usize,
+        /// The number of moduli.
+        len: usize,
+    },
+
+    /// Indicates that a modulus index is repeated.
+    DuplicateModulusIndex(usize),
+
+    /// Indicates that a coefficient does not encode a bit.
+    InvalidBit {
+        /// The index of the coefficient.
+        index: usize,
+    },
+
+    /// Indicates that a convolution is not supported.
+    UnsupportedConvolution(NttVariant),
+
+    /// Indicates that neither the moduli nor their sizes are specified.
+    MissingModuli,
+
+    /// Indicates that both the moduli and their sizes are specified.
+    ConflictingModuli,
+
+    /// Indicates that the size of a modulus to generate is invalid.
+    InvalidModulusSize(usize),
+
+    /// Indicates that there are not enough primes of a size supporting the NTT
+    /// of a degree.
+    NotEnoughNttPrimes {
+        /// The size of the primes, in bits.
+        size: usize,
+        /// The degree of the NTT.
+        degree: usize,
+    },
+
     /// Indicates a default error
     /// TODO: To delete when transition is over
-    #[error("{0}")]
     Default(String),
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidModulus(p) => write!(
+                f,
+                "Invalid modulus: modulus {p} should be between 2 and (1 << 62) - 1."
+            ),
+            Error::Serialization(s) | Error::Sampling(s) | Error::Default(s) => f.write_str(s),
+            Error::NoMoreContext => f.write_str("This is the last context."),
+            Error::InvalidContext => f.write_str("Invalid context provided."),
+            #[cfg(feature = "std")]
+            Error::IncorrectRepresentation(got, expected) => write!(
+                f,
+                "Incorrect representation: got {got:?}, expected {expected:?}."
+            ),
+            Error::InvalidSeedSize(got, expected) => write!(
+                f,
+                "Invalid seed: got {got} bytes, expected {expected} bytes."
+            ),
+            Error::InvalidDegree(degree) => write!(
+                f,
+                "Invalid degree: {degree} is not a power of 2 larger than 8"
+            ),
+            Error::UnsupportedNtt { modulus, degree } => write!(
+                f,
+                "Unsupported NTT: modulus {modulus} does not support the NTT of degree {degree}."
+            ),
+            Error::UnknownRepresentation(s) => write!(
+                f,
+                "Unknown representation \"{s}\": expected one of \"power-basis\", \"ntt\", or \"ntt-shoup\"."
+            ),
+            Error::MissingRepresentation => {
+                f.write_str("The representation needs to be specified.")
+            }
+            Error::InvalidLength(got, expected) => write!(
+                f,
+                "Invalid length: got {got} values, expected {expected}."
+            ),
+            Error::TooManyValues(max) => write!(f, "Too many values: expected at most {max}."),
+            Error::InvalidShape(got, expected) => write!(
+                f,
+                "Invalid shape: got {got:?}, expected {expected:?}."
+            ),
+            #[cfg(feature = "std")]
+            Error::InvalidTransition(from, to) => write!(
+                f,
+                "Invalid representation transition from {from:?} to {to:?}."
+            ),
+            Error::LazyCoefficients => {
+                f.write_str("The polynomials must not have lazy coefficients.")
+            }
+            Error::EmptyInput => f.write_str("The input is empty."),
+            Error::NotInvertible => f.write_str("The polynomial is not invertible."),
+            Error::CoefficientOutOfRange { bound } => write!(
+                f,
+                "Coefficient out of range: the coefficients should be in [-{bound}, {bound}]."
+            ),
+            Error::UnsupportedRounding(rounding) => write!(
+                f,
+                "The rounding mode {rounding:?} is not supported for this scaling factor."
+            ),
+            Error::EmptyModuli => f.write_str("The list of moduli is empty."),
+            Error::NonCoprimeModuli => f.write_str("The moduli are not coprime."),
+            Error::NotEnoughPrimes => {
+                f.write_str("Not enough primes to reach the target modulus.")
+            }
+            Error::InvalidNumberOfModuli { got, max } => write!(
+                f,
+                "Invalid number of moduli: expected between 1 and {max}, got {got}."
+            ),
+            Error::NotEnoughRests { got, expected } => write!(
+                f,
+                "Not enough rests: expected at least {expected}, got {got}."
+            ),
+            Error::InvalidModulusIndex { index, len } => write!(
+                f,
+                "Invalid modulus index: {index} is out of range for {len} moduli."
+            ),
+            Error::DuplicateModulusIndex(index) => {
+                write!(f, "The modulus index {index} is repeated.")
+            }
+            Error::InvalidBit { index } => write!(
+                f,
+                "Invalid bit: the coefficient {index} is neither 0 nor 1."
+            ),
+            Error::UnsupportedConvolution(variant) => write!(
+                f,
+                "Unsupported convolution: got {variant:?}, only the negacyclic convolution is supported."
+            ),
+            Error::MissingModuli => {
+                f.write_str("The moduli or their sizes need to be specified.")
+            }
+            Error::ConflictingModuli => {
+                f.write_str("Only one of the moduli and their sizes can be specified.")
+            }
+            Error::InvalidModulusSize(size) => write!(
+                f,
+                "Invalid modulus size: {size} should be between 10 and 62."
+            ),
+            Error::NotEnoughNttPrimes { size, degree } => write!(
+                f,
+                "Not enough {size}-bit primes supporting the NTT of degree {degree}."
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
-    use crate::{rq::Representation, Error};
+    use crate::{ntt::NttVariant, rns::Rounding, Error};
 
     #[test]
     fn error_strings() {
@@ -57,14 +285,135 @@ mod tests {
             Error::InvalidContext.to_string(),
             "Invalid context provided."
         );
+        assert_eq!(
+            Error::InvalidSeedSize(0, 1).to_string(),
+            "Invalid seed: got 0 bytes, expected 1 bytes."
+        );
+        assert_eq!(
+            Error::InvalidDegree(10).to_string(),
+            "Invalid degree: 10 is not a power of 2 larger than 8"
+        );
+        assert_eq!(
+            Error::UnsupportedNtt {
+                modulus: 17,
+                degree: 16
+            }
+            .to_string(),
+            "Unsupported NTT: modulus 17 does not support the NTT of degree 16."
+        );
+        assert_eq!(
+            Error::UnknownRepresentation("test".to_string()).to_string(),
+            "Unknown representation \"test\": expected one of \"power-basis\", \"ntt\", or \"ntt-shoup\"."
+        );
+        assert_eq!(
+            Error::MissingRepresentation.to_string(),
+            "The representation needs to be specified."
+        );
+        assert_eq!(
+            Error::InvalidLength(1, 2).to_string(),
+            "Invalid length: got 1 values, expected 2."
+        );
+        assert_eq!(
+            Error::TooManyValues(2).to_string(),
+            "Too many values: expected at most 2."
+        );
+        assert_eq!(
+            Error::InvalidShape(vec![1, 2], vec![3, 4]).to_string(),
+            "Invalid shape: got [1, 2], expected [3, 4]."
+        );
+        assert_eq!(Error::Sampling("test".to_string()).to_string(), "test");
+        assert_eq!(
+            Error::LazyCoefficients.to_string(),
+            "The polynomials must not have lazy coefficients."
+        );
+        assert_eq!(Error::EmptyInput.to_string(), "The input is empty.");
+        assert_eq!(
+            Error::NotInvertible.to_string(),
+            "The polynomial is not invertible."
+        );
+        assert_eq!(
+            Error::CoefficientOutOfRange { bound: 7 }.to_string(),
+            "Coefficient out of range: the coefficients should be in [-7, 7]."
+        );
+        assert_eq!(
+            Error::UnsupportedRounding(Rounding::Floor).to_string(),
+            "The rounding mode Floor is not supported for this scaling factor."
+        );
+        assert_eq!(
+            Error::EmptyModuli.to_string(),
+            "The list of moduli is empty."
+        );
+        assert_eq!(
+            Error::NonCoprimeModuli.to_string(),
+            "The moduli are not coprime."
+        );
+        assert_eq!(
+            Error::NotEnoughPrimes.to_string(),
+            "Not enough primes to reach the target modulus."
+        );
+        assert_eq!(
+            Error::InvalidNumberOfModuli { got: 0, max: 2 }.to_string(),
+            "Invalid number of moduli: expected between 1 and 2, got 0."
+        );
+        assert_eq!(
+            Error::NotEnoughRests {
+                got: 1,
+                expected: 2
+            }
+            .to_string(),
+            "Not enough rests: expected at least 2, got 1."
+        );
+        assert_eq!(
+            Error::InvalidModulusIndex { index: 5, len: 5 }.to_string(),
+            "Invalid modulus index: 5 is out of range for 5 moduli."
+        );
+        assert_eq!(
+            Error::DuplicateModulusIndex(1).to_string(),
+            "The modulus index 1 is repeated."
+        );
+        assert_eq!(
+            Error::InvalidBit { index: 2 }.to_string(),
+            "Invalid bit: the coefficient 2 is neither 0 nor 1."
+        );
+        assert_eq!(
+            Error::UnsupportedConvolution(NttVariant::Cyclic).to_string(),
+            "Unsupported convolution: got Cyclic, only the negacyclic convolution is supported."
+        );
+        assert_eq!(
+            Error::MissingModuli.to_string(),
+            "The moduli or their sizes need to be specified."
+        );
+        assert_eq!(
+            Error::ConflictingModuli.to_string(),
+            "Only one of the moduli and their sizes can be specified."
+        );
+        assert_eq!(
+            Error::InvalidModulusSize(63).to_string(),
+            "Invalid modulus size: 63 should be between 10 and 62."
+        );
+        assert_eq!(
+            Error::NotEnoughNttPrimes {
+                size: 11,
+                degree: 64
+            }
+            .to_string(),
+            "Not enough 11-bit primes supporting the NTT of degree 64."
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn representation_error_strings() {
+        use crate::rq::Representation;
+
         assert_eq!(
             Error::IncorrectRepresentation(Representation::Ntt, Representation::NttShoup)
                 .to_string(),
             "Incorrect representation: got Ntt, expected NttShoup."
         );
         assert_eq!(
-            Error::InvalidSeedSize(0, 1).to_string(),
-            "Invalid seed: got 0 bytes, expected 1 bytes."
+            Error::InvalidTransition(Representation::Ntt, Representation::PowerBasis).to_string(),
+            "Invalid representation transition from Ntt to PowerBasis."
         );
     }
 }
@@ -1,19 +1,31 @@
 #![crate_name = "fhe_math"]
 #![crate_type = "lib"]
 #![warn(missing_docs, unused_imports)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 //! Mathematical utilities for the fhe.rs library.
+//!
+//! Without the default `std` feature, the crate is `no_std` with `alloc`: the
+//! [`zq`], [`ntt`], and [`rns`] modules are available, while the polynomials
+//! of the `rq` module require `std`.
+
+extern crate alloc;
 
 mod errors;
+#[cfg(feature = "std")]
 mod proto;
 
 pub mod ntt;
 pub mod rns;
+#[cfg(feature = "std")]
 pub mod rq;
 pub mod zq;
 
 pub use errors::{Error, Result};
 
+#[cfg(all(test, feature = "std"))]
+mod test_allocator;
+
 #[cfg(test)]
 #[macro_use]
 extern crate proptest;
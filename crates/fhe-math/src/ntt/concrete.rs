@@ -1,16 +1,51 @@
 use concrete_ntt::prime64::Plan;
 
-use crate::zq::Modulus;
+use crate::{zq::Modulus, Error};
 
-use super::native;
+use super::{native, NttVariant};
 
 /// Number-Theoretic Transform operator.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "NttParameters", into = "NttParameters")]
 pub struct NttOperator {
+    p: Modulus,
+    size: usize,
     concrete_operator: Option<Plan>,
     native_operator: native::NttOperator,
 }
 
+// The operator is serialized as its parameters, and recomputed when it is
+// deserialized.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct NttParameters {
+    p: Modulus,
+    size: usize,
+    variant: NttVariant,
+}
+
+impl From<NttOperator> for NttParameters {
+    fn from(op: NttOperator) -> Self {
+        Self {
+            variant: op.variant(),
+            p: op.p,
+            size: op.size,
+        }
+    }
+}
+
+impl TryFrom<NttParameters> for NttOperator {
+    type Error = Error;
+
+    fn try_from(parameters: NttParameters) -> Result<Self, Self::Error> {
+        Self::new_with_variant(&parameters.p, parameters.size, parameters.variant).ok_or(
+            Error::UnsupportedNtt {
+                modulus: parameters.p.p,
+                degree: parameters.size,
+            },
+        )
+    }
+}
+
 impl PartialEq for NttOperator {
     fn eq(&self, other: &Self) -> bool {
         self.native_operator == other.native_operator
@@ -26,14 +61,35 @@ impl NttOperator {
     /// Returns None if the modulus does not support the NTT for this specific
     /// size.
     pub fn new(p: &Modulus, size: usize) -> Option<Self> {
-        let native_operator = native::NttOperator::new(p, size)?;
-        let concrete_operator = Plan::try_new(size, p.p);
+        Self::new_with_variant(p, size, NttVariant::Negacyclic)
+    }
+
+    /// Create an NTT operator of the specified variant given a modulus for a
+    /// specific size. Only the negacyclic NTT is computed by the `concrete-ntt`
+    /// crate, the cyclic NTT always uses the native implementation.
+    ///
+    /// Aborts if the size is not a power of 2 that is >= 8 in debug mode.
+    /// Returns None if the modulus does not support the (negacyclic) NTT for
+    /// this specific size.
+    pub fn new_with_variant(p: &Modulus, size: usize, variant: NttVariant) -> Option<Self> {
+        let native_operator = native::NttOperator::new_with_variant(p, size, variant)?;
+        let concrete_operator = match variant {
+            NttVariant::Negacyclic => Plan::try_new(size, p.p),
+            NttVariant::Cyclic => None,
+        };
         Some(Self {
+            p: p.clone(),
+            size,
             concrete_operator,
             native_operator,
         })
     }
 
+    /// Returns the variant of the NTT computed by this operator.
+    pub const fn variant(&self) -> NttVariant {
+        self.native_operator.variant()
+    }
+
     /// Compute the forward NTT in place.
     /// Aborts if a is not of the size handled by the operator.
     pub fn forward(&self, a: &mut [u64]) {
@@ -55,6 +111,34 @@ impl NttOperator {
         }
     }
 
+    /// Compute the forward NTT in place in a lazy fashion.
+    /// The input coefficients must be in the interval [0, 4 * p), and the
+    /// output coefficients are in the interval [0, 2 * p).
+    /// Aborts if a is not of the size handled by the operator.
+    pub fn forward_lazy(&self, a: &mut [u64]) {
+        if let Some(ref concrete_operator) = self.concrete_operator {
+            // The `concrete-ntt` crate requires reduced inputs.
+            self.p.reduce_vec(a);
+            concrete_operator.fwd(a);
+        } else {
+            self.native_operator.forward_lazy(a);
+        }
+    }
+
+    /// Compute the backward NTT in place in a lazy fashion.
+    /// The input coefficients must be in the interval [0, 2 * p), and the
+    /// output coefficients are in the interval [0, 2 * p).
+    /// Aborts if a is not of the size handled by the operator.
+    pub fn backward_lazy(&self, a: &mut [u64]) {
+        if let Some(ref concrete_operator) = self.concrete_operator {
+            self.p.reduce_vec(a);
+            concrete_operator.inv(a);
+            concrete_operator.normalize(a);
+        } else {
+            self.native_operator.backward_lazy(a);
+        }
+    }
+
     /// Compute the forward NTT in place in variable time in a lazily fashion.
     /// This means that the output coefficients may be up to 4 times the
     /// modulus.
@@ -66,6 +150,7 @@ impl NttOperator {
     pub(crate) unsafe fn forward_vt_lazy(&self, a_ptr: *mut u64) {
         if let Some(ref concrete_operator) = self.concrete_operator {
             let a = std::slice::from_raw_parts_mut(a_ptr, concrete_operator.ntt_size());
+            self.p.reduce_vec(a);
             concrete_operator.fwd(a);
         } else {
             self.native_operator.forward_vt_lazy(a_ptr);
@@ -12,6 +12,16 @@ pub use concrete::NttOperator;
 #[cfg(not(any(feature = "concrete-ntt", feature = "concrete-ntt-nightly")))]
 pub use native::NttOperator;
 
+/// Variants of the Number-Theoretic Transform.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum NttVariant {
+    /// Negacyclic NTT, over ZZ_q\[x\]/(x^n + 1) as in R_q.
+    #[default]
+    Negacyclic,
+    /// Cyclic NTT, over ZZ_q\[x\]/(x^n - 1).
+    Cyclic,
+}
+
 /// Returns whether a modulus p is prime and supports the Number Theoretic
 /// Transform of size n.
 ///
@@ -24,9 +34,10 @@ pub(crate) fn supports_ntt(p: u64, n: usize) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use itertools::{izip, Itertools};
     use rand::thread_rng;
 
-    use super::{supports_ntt, NttOperator};
+    use super::{supports_ntt, NttOperator, NttVariant};
     use crate::zq::Modulus;
 
     #[test]
@@ -110,4 +121,142 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn forward_backward_lazy() {
+        let ntests = 100;
+        let mut rng = thread_rng();
+
+        for size in [32, 1024] {
+            for p in [1153, 4611686018326724609] {
+                let q = Modulus::new(p).unwrap();
+
+                if supports_ntt(p, size) {
+                    let op = NttOperator::new(&q, size).unwrap();
+
+                    for _ in 0..ntests {
+                        let a = q.random_vec(size, &mut rng);
+                        let mut b = a.clone();
+                        let mut b_lazy = a.clone();
+
+                        op.forward(&mut b);
+                        op.forward_lazy(&mut b_lazy);
+                        assert!(b_lazy.iter().all(|bi| *bi < 2 * p));
+                        let mut b_reduced = b_lazy.clone();
+                        q.reduce_vec(&mut b_reduced);
+                        assert_eq!(b, b_reduced);
+
+                        // The lazy backward NTT accepts the lazy outputs.
+                        op.backward_lazy(&mut b_lazy);
+                        assert!(b_lazy.iter().all(|bi| *bi < 2 * p));
+                        q.reduce_vec(&mut b_lazy);
+                        assert_eq!(a, b_lazy);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lazy_unreduced_inputs() {
+        let ntests = 100;
+        let mut rng = thread_rng();
+
+        for size in [32, 1024] {
+            for p in [1153, 4611686018326724609] {
+                let q = Modulus::new(p).unwrap();
+
+                if supports_ntt(p, size) {
+                    let op = NttOperator::new(&q, size).unwrap();
+
+                    for _ in 0..ntests {
+                        let a = q.random_vec(size, &mut rng);
+                        let mut expected = a.clone();
+                        op.forward(&mut expected);
+
+                        // Inputs in [0, 4 * p) for the lazy forward NTT, and in
+                        // [0, 2 * p) for the other lazy NTTs.
+                        let shifts = q.random_vec(size, &mut rng);
+                        let unreduced = |k: u64| {
+                            izip!(&a, &shifts)
+                                .map(|(ai, si)| ai + (si % k) * p)
+                                .collect_vec()
+                        };
+
+                        let mut b = unreduced(4);
+                        op.forward_lazy(&mut b);
+                        assert!(b.iter().all(|bi| *bi < 2 * p));
+                        q.reduce_vec(&mut b);
+                        assert_eq!(b, expected);
+
+                        let mut b = unreduced(2);
+                        unsafe { op.forward_vt_lazy(b.as_mut_ptr()) }
+                        assert!(b.iter().all(|bi| *bi < 4 * p));
+                        q.reduce_vec(&mut b);
+                        assert_eq!(b, expected);
+
+                        let mut b = izip!(&expected, &shifts)
+                            .map(|(bi, si)| bi + (si % 2) * p)
+                            .collect_vec();
+                        op.backward_lazy(&mut b);
+                        assert!(b.iter().all(|bi| *bi < 2 * p));
+                        q.reduce_vec(&mut b);
+                        assert_eq!(b, a);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cyclic() {
+        let ntests = 20;
+        let mut rng = thread_rng();
+
+        for size in [8, 32, 1024] {
+            for p in [1153, 4611686018326724609] {
+                let q = Modulus::new(p).unwrap();
+
+                if supports_ntt(p, size) {
+                    let op = NttOperator::new_with_variant(&q, size, NttVariant::Cyclic).unwrap();
+                    assert_eq!(op.variant(), NttVariant::Cyclic);
+                    assert_eq!(
+                        NttOperator::new(&q, size).unwrap().variant(),
+                        NttVariant::Negacyclic
+                    );
+
+                    for _ in 0..ntests {
+                        let a = q.random_vec(size, &mut rng);
+                        let b = q.random_vec(size, &mut rng);
+
+                        // Forward and backward round-trip.
+                        let mut a_ntt = a.clone();
+                        op.forward(&mut a_ntt);
+                        let mut a_ntt_vt = a.clone();
+                        unsafe { op.forward_vt(a_ntt_vt.as_mut_ptr()) }
+                        assert_eq!(a_ntt, a_ntt_vt);
+                        let mut c = a_ntt.clone();
+                        op.backward(&mut c);
+                        assert_eq!(c, a);
+                        unsafe { op.backward_vt(a_ntt_vt.as_mut_ptr()) }
+                        assert_eq!(a_ntt_vt, a);
+
+                        // Cyclic convolution.
+                        let mut expected = vec![0u64; size];
+                        for (i, ai) in a.iter().enumerate() {
+                            for (j, bj) in b.iter().enumerate() {
+                                let k = (i + j) % size;
+                                expected[k] = q.add(expected[k], q.mul(*ai, *bj));
+                            }
+                        }
+                        let mut b_ntt = b.clone();
+                        op.forward(&mut b_ntt);
+                        q.mul_vec(&mut a_ntt, &b_ntt);
+                        op.backward(&mut a_ntt);
+                        assert_eq!(a_ntt, expected);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,8 +1,10 @@
-use crate::zq::Modulus;
+use super::NttVariant;
+use crate::zq::{simd, Modulus};
+use alloc::boxed::Box;
+use core::iter::successors;
 use itertools::Itertools;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use std::iter::successors;
 
 /// Number-Theoretic Transform operator.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
@@ -10,6 +12,7 @@ pub struct NttOperator {
     p: Modulus,
     p_twice: u64,
     size: usize,
+    variant: NttVariant,
     omegas: Box<[u64]>,
     omegas_shoup: Box<[u64]>,
     zetas_inv: Box<[u64]>,
@@ -19,12 +22,26 @@ pub struct NttOperator {
 }
 
 impl NttOperator {
-    /// Create an NTT operator given a modulus for a specific size.
+    /// Create a negacyclic NTT operator given a modulus for a specific size.
     ///
     /// Aborts if the size is not a power of 2 that is >= 8 in debug mode.
     /// Returns None if the modulus does not support the NTT for this specific
     /// size.
+    #[cfg_attr(
+        any(feature = "concrete-ntt", feature = "concrete-ntt-nightly"),
+        allow(dead_code)
+    )]
     pub fn new(p: &Modulus, size: usize) -> Option<Self> {
+        Self::new_with_variant(p, size, NttVariant::Negacyclic)
+    }
+
+    /// Create an NTT operator of the specified variant given a modulus for a
+    /// specific size.
+    ///
+    /// Aborts if the size is not a power of 2 that is >= 8 in debug mode.
+    /// Returns None if the modulus does not support the (negacyclic) NTT for
+    /// this specific size.
+    pub fn new_with_variant(p: &Modulus, size: usize, variant: NttVariant) -> Option<Self> {
         if !super::supports_ntt(p.p, size) {
             None
         } else {
@@ -40,13 +57,37 @@ impl NttOperator {
                 .take(size)
                 .collect_vec();
 
-            let mut omegas = Vec::with_capacity(size);
-            let mut zetas_inv = Vec::with_capacity(size);
-            for i in 0..size {
-                let j = i.reverse_bits() >> (size.leading_zeros() + 1);
-                omegas.push(powers[j]);
-                zetas_inv.push(powers_inv[j]);
-            }
+            // The i-th twiddle factor of the forward NTT is omega^exponents[i]. In the
+            // cyclic case, the twist by the powers of omega is removed, so that
+            // the exponents are even, i.e., powers of the n-th root omega^2.
+            let exponents = (0..size)
+                .map(|i| {
+                    let j = i.reverse_bits() >> (size.leading_zeros() + 1);
+                    match variant {
+                        NttVariant::Negacyclic => j,
+                        NttVariant::Cyclic if i == 0 => 0,
+                        NttVariant::Cyclic => j - (size >> (i.ilog2() + 1)),
+                    }
+                })
+                .collect_vec();
+
+            let omegas = exponents.iter().map(|e| powers[*e]).collect_vec();
+            // The i-th twiddle factor of the backward NTT is the inverse of the
+            // twiddle factor of the corresponding butterfly in the forward NTT:
+            // the level with m butterfly groups uses the indices [n - 2m, n - m)
+            // in the backward NTT, and [m, 2m) in the forward NTT.
+            let zetas_inv = (0..size)
+                .map(|i| {
+                    if i == size - 1 {
+                        return 1;
+                    }
+                    let m = (size - i).next_power_of_two() >> 1;
+                    match exponents[i + 3 * m - size] {
+                        0 => 1,
+                        e => powers_inv[e - 1],
+                    }
+                })
+                .collect_vec();
 
             let omegas_shoup = p.shoup_vec(&omegas);
             let zetas_inv_shoup = p.shoup_vec(&zetas_inv);
@@ -55,6 +96,7 @@ impl NttOperator {
                 p: p.clone(),
                 p_twice: p.p * 2,
                 size,
+                variant,
                 omegas: omegas.into_boxed_slice(),
                 omegas_shoup: omegas_shoup.into_boxed_slice(),
                 zetas_inv: zetas_inv.into_boxed_slice(),
@@ -65,9 +107,28 @@ impl NttOperator {
         }
     }
 
+    /// Returns the variant of the NTT computed by this operator.
+    pub const fn variant(&self) -> NttVariant {
+        self.variant
+    }
+
     /// Compute the forward NTT in place.
     /// Aborts if a is not of the size handled by the operator.
     pub fn forward(&self, a: &mut [u64]) {
+        self.forward_levels(a, false)
+    }
+
+    /// Compute the forward NTT in place in a lazy fashion.
+    /// The input coefficients must be in the interval [0, 4 * p), and the
+    /// output coefficients are in the interval [0, 2 * p).
+    /// Aborts if a is not of the size handled by the operator.
+    pub fn forward_lazy(&self, a: &mut [u64]) {
+        self.forward_levels(a, true)
+    }
+
+    /// Compute the levels of the forward NTT in place. The last level reduces
+    /// the output to [0, 2 * p) when lazy is set, and to [0, p) otherwise.
+    fn forward_levels(&self, a: &mut [u64], lazy: bool) {
         debug_assert_eq!(a.len(), self.size);
 
         let n = self.size;
@@ -77,30 +138,39 @@ impl NttOperator {
         let mut m = 1;
         let mut k = 1;
         while l > 0 {
-            for i in 0..m {
-                unsafe {
-                    let omega = *self.omegas.get_unchecked(k);
-                    let omega_shoup = *self.omegas_shoup.get_unchecked(k);
-                    k += 1;
-
-                    let s = 2 * i * l;
-                    match l {
-                        1 => {
-                            // The last level should reduce the output
-                            let uj = &mut *a_ptr.add(s);
-                            let ujl = &mut *a_ptr.add(s + l);
-                            self.butterfly(uj, ujl, omega, omega_shoup);
-                            *uj = self.reduce3(*uj);
-                            *ujl = self.reduce3(*ujl);
-                        }
-                        _ => {
-                            for j in s..(s + l) {
-                                self.butterfly(
-                                    &mut *a_ptr.add(j),
-                                    &mut *a_ptr.add(j + l),
-                                    omega,
-                                    omega_shoup,
-                                );
+            if unsafe { self.forward_level_simd(a_ptr, l, k, m) } {
+                k += m;
+            } else {
+                for i in 0..m {
+                    unsafe {
+                        let omega = *self.omegas.get_unchecked(k);
+                        let omega_shoup = *self.omegas_shoup.get_unchecked(k);
+                        k += 1;
+
+                        let s = 2 * i * l;
+                        match l {
+                            1 => {
+                                // The last level should reduce the output
+                                let uj = &mut *a_ptr.add(s);
+                                let ujl = &mut *a_ptr.add(s + l);
+                                self.butterfly(uj, ujl, omega, omega_shoup);
+                                if lazy {
+                                    *uj = Modulus::reduce1(*uj, self.p_twice);
+                                    *ujl = Modulus::reduce1(*ujl, self.p_twice);
+                                } else {
+                                    *uj = self.reduce3(*uj);
+                                    *ujl = self.reduce3(*ujl);
+                                }
+                            }
+                            _ => {
+                                for j in s..(s + l) {
+                                    self.butterfly(
+                                        &mut *a_ptr.add(j),
+                                        &mut *a_ptr.add(j + l),
+                                        omega,
+                                        omega_shoup,
+                                    );
+                                }
                             }
                         }
                     }
@@ -114,6 +184,27 @@ impl NttOperator {
     /// Compute the backward NTT in place.
     /// Aborts if a is not of the size handled by the operator.
     pub fn backward(&self, a: &mut [u64]) {
+        self.backward_levels(a);
+        a.iter_mut()
+            .for_each(|ai| *ai = self.p.mul_shoup(*ai, self.size_inv, self.size_inv_shoup));
+    }
+
+    /// Compute the backward NTT in place in a lazy fashion.
+    /// The input coefficients must be in the interval [0, 2 * p), and the
+    /// output coefficients are in the interval [0, 2 * p).
+    /// Aborts if a is not of the size handled by the operator.
+    pub fn backward_lazy(&self, a: &mut [u64]) {
+        self.backward_levels(a);
+        a.iter_mut().for_each(|ai| {
+            *ai = self
+                .p
+                .lazy_mul_shoup(*ai, self.size_inv, self.size_inv_shoup)
+        });
+    }
+
+    /// Compute the levels of the backward NTT in place, without the final
+    /// multiplication by the inverse of the size.
+    fn backward_levels(&self, a: &mut [u64]) {
         debug_assert_eq!(a.len(), self.size);
 
         let a_ptr = a.as_mut_ptr();
@@ -122,30 +213,34 @@ impl NttOperator {
         let mut m = self.size >> 1;
         let mut l = 1;
         while m > 0 {
-            for i in 0..m {
-                let s = 2 * i * l;
-                unsafe {
-                    let zeta_inv = *self.zetas_inv.get_unchecked(k);
-                    let zeta_inv_shoup = *self.zetas_inv_shoup.get_unchecked(k);
-                    k += 1;
-                    match l {
-                        1 => {
-                            self.inv_butterfly(
-                                &mut *a_ptr.add(s),
-                                &mut *a_ptr.add(s + l),
-                                zeta_inv,
-                                zeta_inv_shoup,
-                            );
-                        }
-                        _ => {
-                            for j in s..(s + l) {
+            if unsafe { self.backward_level_simd(a_ptr, l, k, m) } {
+                k += m;
+            } else {
+                for i in 0..m {
+                    let s = 2 * i * l;
+                    unsafe {
+                        let zeta_inv = *self.zetas_inv.get_unchecked(k);
+                        let zeta_inv_shoup = *self.zetas_inv_shoup.get_unchecked(k);
+                        k += 1;
+                        match l {
+                            1 => {
                                 self.inv_butterfly(
-                                    &mut *a_ptr.add(j),
-                                    &mut *a_ptr.add(j + l),
+                                    &mut *a_ptr.add(s),
+                                    &mut *a_ptr.add(s + l),
                                     zeta_inv,
                                     zeta_inv_shoup,
                                 );
                             }
+                            _ => {
+                                for j in s..(s + l) {
+                                    self.inv_butterfly(
+                                        &mut *a_ptr.add(j),
+                                        &mut *a_ptr.add(j + l),
+                                        zeta_inv,
+                                        zeta_inv_shoup,
+                                    );
+                                }
+                            }
                         }
                     }
                 }
@@ -153,9 +248,6 @@ impl NttOperator {
             l <<= 1;
             m >>= 1;
         }
-
-        a.iter_mut()
-            .for_each(|ai| *ai = self.p.mul_shoup(*ai, self.size_inv, self.size_inv_shoup));
     }
 
     /// Compute the forward NTT in place in variable time in a lazily fashion.
@@ -171,30 +263,34 @@ impl NttOperator {
         let mut m = 1;
         let mut k = 1;
         while l > 0 {
-            for i in 0..m {
-                let omega = *self.omegas.get_unchecked(k);
-                let omega_shoup = *self.omegas_shoup.get_unchecked(k);
-                k += 1;
-
-                let s = 2 * i * l;
-                match l {
-                    1 => {
-                        self.butterfly_vt(
-                            &mut *a_ptr.add(s),
-                            &mut *a_ptr.add(s + l),
-                            omega,
-                            omega_shoup,
-                        );
-                    }
-                    _ => {
-                        for j in s..(s + l) {
+            if unsafe { self.forward_level_simd(a_ptr, l, k, m) } {
+                k += m;
+            } else {
+                for i in 0..m {
+                    let omega = *self.omegas.get_unchecked(k);
+                    let omega_shoup = *self.omegas_shoup.get_unchecked(k);
+                    k += 1;
+
+                    let s = 2 * i * l;
+                    match l {
+                        1 => {
                             self.butterfly_vt(
-                                &mut *a_ptr.add(j),
-                                &mut *a_ptr.add(j + l),
+                                &mut *a_ptr.add(s),
+                                &mut *a_ptr.add(s + l),
                                 omega,
                                 omega_shoup,
                             );
                         }
+                        _ => {
+                            for j in s..(s + l) {
+                                self.butterfly_vt(
+                                    &mut *a_ptr.add(j),
+                                    &mut *a_ptr.add(j + l),
+                                    omega,
+                                    omega_shoup,
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -227,29 +323,33 @@ impl NttOperator {
         let mut m = self.size >> 1;
         let mut l = 1;
         while m > 0 {
-            for i in 0..m {
-                let s = 2 * i * l;
-                let zeta_inv = *self.zetas_inv.get_unchecked(k);
-                let zeta_inv_shoup = *self.zetas_inv_shoup.get_unchecked(k);
-                k += 1;
-                match l {
-                    1 => {
-                        self.inv_butterfly_vt(
-                            &mut *a_ptr.add(s),
-                            &mut *a_ptr.add(s + l),
-                            zeta_inv,
-                            zeta_inv_shoup,
-                        );
-                    }
-                    _ => {
-                        for j in s..(s + l) {
+            if unsafe { self.backward_level_simd(a_ptr, l, k, m) } {
+                k += m;
+            } else {
+                for i in 0..m {
+                    let s = 2 * i * l;
+                    let zeta_inv = *self.zetas_inv.get_unchecked(k);
+                    let zeta_inv_shoup = *self.zetas_inv_shoup.get_unchecked(k);
+                    k += 1;
+                    match l {
+                        1 => {
                             self.inv_butterfly_vt(
-                                &mut *a_ptr.add(j),
-                                &mut *a_ptr.add(j + l),
+                                &mut *a_ptr.add(s),
+                                &mut *a_ptr.add(s + l),
                                 zeta_inv,
                                 zeta_inv_shoup,
                             );
                         }
+                        _ => {
+                            for j in s..(s + l) {
+                                self.inv_butterfly_vt(
+                                    &mut *a_ptr.add(j),
+                                    &mut *a_ptr.add(j + l),
+                                    zeta_inv,
+                                    zeta_inv_shoup,
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -264,6 +364,38 @@ impl NttOperator {
         }
     }
 
+    /// Compute a level of the forward NTT with the vectorized kernels.
+    /// Returns false if the level was not computed.
+    ///
+    /// # Safety
+    /// This function assumes that a_ptr points to at least `size` elements.
+    unsafe fn forward_level_simd(&self, a_ptr: *mut u64, l: usize, k: usize, m: usize) -> bool {
+        let a = core::slice::from_raw_parts_mut(a_ptr, self.size);
+        simd::ntt_forward_level(
+            self.p.p,
+            a,
+            l,
+            &self.omegas[k..k + m],
+            &self.omegas_shoup[k..k + m],
+        ) > 0
+    }
+
+    /// Compute a level of the backward NTT with the vectorized kernels.
+    /// Returns false if the level was not computed.
+    ///
+    /// # Safety
+    /// This function assumes that a_ptr points to at least `size` elements.
+    unsafe fn backward_level_simd(&self, a_ptr: *mut u64, l: usize, k: usize, m: usize) -> bool {
+        let a = core::slice::from_raw_parts_mut(a_ptr, self.size);
+        simd::ntt_backward_level(
+            self.p.p,
+            a,
+            l,
+            &self.zetas_inv[k..k + m],
+            &self.zetas_inv_shoup[k..k + m],
+        ) > 0
+    }
+
     /// Reduce a modulo p.
     ///
     /// Aborts if a >= 4 * p.
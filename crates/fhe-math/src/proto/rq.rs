@@ -11,6 +11,8 @@ pub struct Rq {
     pub coefficients: ::prost::alloc::vec::Vec<u8>,
     #[prost(bool, tag = "4")]
     pub allow_variable_time: bool,
+    #[prost(bool, tag = "5")]
+    pub byte_aligned: bool,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -0,0 +1,301 @@
+#![warn(missing_docs, unused_imports)]
+
+//! Fast RNS basis conversion, following Section 2.2 of <https://eprint.iacr.org/2018/117.pdf>.
+
+use super::RnsContext;
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use ethnum::{u256, U256};
+use itertools::{izip, Itertools};
+use ndarray::{ArrayView1, ArrayViewMut1};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+
+/// Converter between two RNS contexts.
+/// This is a helper struct to perform fast RNS basis conversions from the
+/// moduli q_1, ..., q_k of a context `from` of product q, to the moduli p_1,
+/// ..., p_m of a context `to`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RnsConverter {
+    from: Arc<RnsContext>,
+    to: Arc<RnsContext>,
+
+    // (q / q_i) mod p_j, indexed by j then by i.
+    q_star_mod_p: Box<[Box<[u64]>]>,
+    // q mod p_j.
+    q_mod_p: Box<[u64]>,
+    // 2^127 / q_i, rounded down.
+    theta: Box<[u128]>,
+}
+
+impl RnsConverter {
+    /// Create a RNS converter from the context `from` to the context `to`.
+    pub fn new(from: &Arc<RnsContext>, to: &Arc<RnsContext>) -> Self {
+        let q_star_mod_p = to
+            .moduli_u64
+            .iter()
+            .map(|pj| {
+                from.q_star
+                    .iter()
+                    .map(|q_star_i| (q_star_i % pj).to_u64().unwrap())
+                    .collect_vec()
+                    .into_boxed_slice()
+            })
+            .collect_vec()
+            .into_boxed_slice();
+        let q_mod_p = to
+            .moduli_u64
+            .iter()
+            .map(|pj| (&from.product % pj).to_u64().unwrap())
+            .collect_vec()
+            .into_boxed_slice();
+        let theta = from
+            .moduli_u64
+            .iter()
+            .map(|qi| ((BigUint::one() << 127usize) / qi).to_u128().unwrap())
+            .collect_vec()
+            .into_boxed_slice();
+
+        Self {
+            from: from.clone(),
+            to: to.clone(),
+            q_star_mod_p,
+            q_mod_p,
+            theta,
+        }
+    }
+
+    /// Output the RNS representation in the context `to` of the rests, which
+    /// are interpreted as an integer in [-q/2, q/2).
+    ///
+    /// The conversion is exact except for inputs at a distance smaller than
+    /// about k * q / 2^65 of q/2 or -q/2, where k is the number of moduli of
+    /// `from`; these inputs may be lifted to the other end of the interval.
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode.
+    pub fn convert_new(&self, rests: ArrayView1<u64>) -> Vec<u64> {
+        let mut out = vec![0; self.to.moduli.len()];
+        self.convert(rests, (&mut out).into());
+        out
+    }
+
+    /// Compute the RNS representation in the context `to` of the rests, which
+    /// are interpreted as an integer in [-q/2, q/2), and store the result in
+    /// `out`; see [`RnsConverter::convert_new`].
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode, or if the size of out is not the number of moduli of `to`.
+    pub fn convert(&self, rests: ArrayView1<u64>, out: ArrayViewMut1<u64>) {
+        let y = self.y(rests);
+
+        // The correction term v = round(sum_i y_i / q_i), so that
+        // sum_i y_i * (q / q_i) - v * q is the centered lift of the rests.
+        let mut sum_theta = u256::ZERO;
+        for (yi, thetai) in izip!(&y, self.theta.iter()) {
+            sum_theta += U256::from(*yi) * U256::from(*thetai);
+        }
+        let v = (sum_theta >> 126u32).as_u64().div_ceil(2);
+
+        self.sum_and_correct(&y, v, out)
+    }
+
+    /// Output the RNS representation in the context `to` of x + a * q, where x
+    /// is the lift of the rests in [0, q), and a is an integer in [0, k), where
+    /// k is the number of moduli of `from`; in particular a = 0 when x = 0.
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode.
+    pub fn convert_approximate_new(&self, rests: ArrayView1<u64>) -> Vec<u64> {
+        let mut out = vec![0; self.to.moduli.len()];
+        self.convert_approximate(rests, (&mut out).into());
+        out
+    }
+
+    /// Compute the RNS representation in the context `to` of x + a * q, where
+    /// x is the lift of the rests in [0, q), and a is an integer in [0, k), and
+    /// store the result in `out`; see
+    /// [`RnsConverter::convert_approximate_new`].
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode, or if the size of out is not the number of moduli of `to`.
+    pub fn convert_approximate(&self, rests: ArrayView1<u64>, out: ArrayViewMut1<u64>) {
+        let y = self.y(rests);
+        self.sum_and_correct(&y, 0, out)
+    }
+
+    // Computes y_i = rests_i * (q / q_i)^(-1) mod q_i.
+    fn y(&self, rests: ArrayView1<u64>) -> Vec<u64> {
+        debug_assert_eq!(rests.len(), self.from.moduli.len());
+        izip!(
+            rests.iter(),
+            self.from.moduli.iter(),
+            self.from.q_tilde.iter(),
+            self.from.q_tilde_shoup.iter()
+        )
+        .map(|(ri, qi, q_tilde_i, q_tilde_shoup_i)| qi.mul_shoup(*ri, *q_tilde_i, *q_tilde_shoup_i))
+        .collect_vec()
+    }
+
+    // Computes sum_i y_i * (q / q_i) - v * q modulo each p_j.
+    fn sum_and_correct(&self, y: &[u64], v: u64, mut out: ArrayViewMut1<u64>) {
+        debug_assert_eq!(out.len(), self.to.moduli.len());
+        for (out_j, pj, q_star_mod_pj, q_mod_pj) in izip!(
+            out.iter_mut(),
+            self.to.moduli.iter(),
+            self.q_star_mod_p.iter(),
+            self.q_mod_p.iter()
+        ) {
+            // Each product is < 2^124, so we reduce the sum every 15 products.
+            let mut sum = 0u128;
+            for (i, (yi, q_star_i)) in izip!(y, q_star_mod_pj.iter()).enumerate() {
+                sum += (*yi as u128) * (*q_star_i as u128);
+                if i % 15 == 14 {
+                    sum = pj.reduce_u128(sum) as u128
+                }
+            }
+            let correction = pj.mul(pj.reduce(v), *q_mod_pj);
+            *out_j = pj.sub(pj.reduce_u128(sum), correction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RnsConverter;
+    use crate::rns::RnsContext;
+    use itertools::Itertools;
+    use ndarray::ArrayView1;
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::Zero;
+    use rand::{thread_rng, RngCore};
+    use std::{error::Error, sync::Arc};
+
+    static Q: &[u64; 3] = &[4, 4611686018326724609, 1153];
+    static P: &[u64; 4] = &[
+        4611686018309947393,
+        4611686018282684417,
+        4611686018257518593,
+        4611686018232352769,
+    ];
+
+    // Projects the integer x in the context ctx.
+    fn project(ctx: &RnsContext, x: &BigInt) -> Vec<u64> {
+        let modulus = BigInt::from(ctx.modulus().clone());
+        let x = ((x % &modulus) + &modulus) % &modulus;
+        ctx.project(&x.to_biguint().unwrap())
+    }
+
+    // Inputs close to 0 and to q, inputs around q / 2 (but far enough from it
+    // for the exact conversion), and random inputs.
+    fn inputs(q: &RnsContext) -> Vec<BigUint> {
+        let mut rng = thread_rng();
+        let modulus = q.modulus();
+        let margin = modulus >> 56;
+        let mut inputs = vec![];
+        for i in 0..10u64 {
+            inputs.push(BigUint::from(i));
+            inputs.push(modulus - 1u64 - i);
+            inputs.push((modulus >> 1) - &margin - i);
+            inputs.push((modulus >> 1) + &margin + i);
+        }
+        for _ in 0..1000 {
+            let x = (BigUint::from(rng.next_u64()) << 128)
+                + (BigUint::from(rng.next_u64()) << 64)
+                + rng.next_u64();
+            inputs.push(x % modulus);
+        }
+        inputs
+    }
+
+    #[test]
+    fn convert() -> Result<(), Box<dyn Error>> {
+        let q = Arc::new(RnsContext::new(Q)?);
+        let p = Arc::new(RnsContext::new(P)?);
+        let converter = RnsConverter::new(&q, &p);
+
+        for x in inputs(&q) {
+            let rests = q.project(&x);
+            let x_centered = q.lift_centered(ArrayView1::from(&rests));
+            assert_eq!(
+                converter.convert_new(ArrayView1::from(&rests)),
+                project(&p, &x_centered)
+            );
+        }
+
+        // Converting to the same context is the identity.
+        let converter = RnsConverter::new(&q, &q);
+        for x in inputs(&q) {
+            let rests = q.project(&x);
+            assert_eq!(converter.convert_new(ArrayView1::from(&rests)), rests);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_approximate() -> Result<(), Box<dyn Error>> {
+        let q = Arc::new(RnsContext::new(Q)?);
+        let p = Arc::new(RnsContext::new(P)?);
+        let converter = RnsConverter::new(&q, &p);
+
+        // The output is x + a * q with 0 <= a < Q.len(); since the modulus of p
+        // is larger than Q.len() * q, the value of a can be recovered by lifting.
+        assert!(p.modulus() > &(q.modulus() * Q.len()));
+        let mut a_values = vec![];
+        for x in inputs(&q) {
+            let rests = q.project(&x);
+            let out = converter.convert_approximate_new(ArrayView1::from(&rests));
+            let lifted = p.lift(ArrayView1::from(&out));
+            assert!(lifted >= x);
+            let difference = lifted - &x;
+            assert!((&difference % q.modulus()).is_zero());
+            let a = (difference / q.modulus()).to_u64_digits();
+            assert!(a.len() <= 1);
+            let a = a.first().copied().unwrap_or_default();
+            assert!(a < Q.len() as u64);
+            if x.is_zero() {
+                assert_eq!(a, 0);
+            }
+            a_values.push(a);
+        }
+        // Inputs close to q are typically off by a multiple of q.
+        assert!(a_values.iter().any(|a| *a > 0));
+
+        // With a single modulus, the approximate conversion is exact.
+        let q = Arc::new(RnsContext::new(&Q[1..2])?);
+        let converter = RnsConverter::new(&q, &p);
+        for x in inputs(&q) {
+            let rests = q.project(&x);
+            assert_eq!(
+                converter.convert_approximate_new(ArrayView1::from(&rests)),
+                p.project(&x)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn many_moduli() -> Result<(), Box<dyn Error>> {
+        // More than 15 moduli, to exercise the intermediate reductions.
+        let moduli = crate::zq::primes::generate_ntt_primes(62, 16, 20).unwrap();
+        let q = Arc::new(RnsContext::new(&moduli[..18])?);
+        let p = Arc::new(RnsContext::new(&moduli[18..])?);
+        let converter = RnsConverter::new(&q, &p);
+
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let rests = moduli[..18]
+                .iter()
+                .map(|qi| rng.next_u64() % qi)
+                .collect_vec();
+            let x_centered = q.lift_centered(ArrayView1::from(&rests));
+            assert_eq!(
+                converter.convert_new(ArrayView1::from(&rests)),
+                project(&p, &x_centered)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -2,17 +2,25 @@
 
 //! Residue-Number System operations.
 
-use crate::{zq::Modulus, Error, Result};
+use crate::{
+    zq::{primes::generate_prime, Modulus},
+    Error, Result,
+};
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::{cmp::Ordering, fmt::Debug};
 use itertools::izip;
-use ndarray::ArrayView1;
-use num_bigint::BigUint;
+use ndarray::{s, Array2, ArrayView1, ArrayView2, ArrayViewMut1, Axis};
+use num_bigint::{BigInt, BigUint, Sign};
 use num_bigint_dig::{BigInt as BigIntDig, BigUint as BigUintDig, ExtendedGcd, ModInverse};
 use num_traits::{cast::ToPrimitive, One, Zero};
-use std::{cmp::Ordering, fmt::Debug};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
+mod converter;
 mod scaler;
 
-pub use scaler::{RnsScaler, ScalingFactor};
+pub use converter::RnsConverter;
+pub use scaler::{RnsScaler, Rounding, ScalingFactor};
 
 /// Context for a Residue Number System.
 #[derive(Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -23,11 +31,53 @@ pub struct RnsContext {
     q_tilde_shoup: Vec<u64>,
     q_star: Vec<BigUint>,
     garner: Vec<BigUint>,
+    inv_moduli: Vec<Vec<u64>>,
+    half_mixed_radix: Vec<u64>,
     product: BigUint,
+    #[serde(skip)]
+    prefixes: PrefixContexts,
 }
 
+/// Lazily computed RNS contexts over the prefixes of the moduli. This cache is
+/// not serialized, and is ignored when comparing contexts. Without the `std`
+/// feature, the contexts are not cached.
+#[derive(Default, Clone)]
+struct PrefixContexts(#[cfg(feature = "std")] Box<[OnceLock<Arc<RnsContext>>]>);
+
+impl PrefixContexts {
+    fn new(_num_moduli: usize) -> Self {
+        Self(
+            #[cfg(feature = "std")]
+            (0.._num_moduli).map(|_| OnceLock::new()).collect(),
+        )
+    }
+
+    /// Returns the context over the first `num_moduli` moduli, computed by
+    /// `new` if it is not cached.
+    fn get_or_init<F>(&self, num_moduli: usize, new: F) -> Arc<RnsContext>
+    where
+        F: FnOnce() -> Arc<RnsContext>,
+    {
+        #[cfg(feature = "std")]
+        if let Some(cell) = self.0.get(num_moduli - 1) {
+            return cell.get_or_init(new).clone();
+        }
+        // The cache is not restored by deserialization.
+        let _ = num_moduli;
+        new()
+    }
+}
+
+impl PartialEq for PrefixContexts {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for PrefixContexts {}
+
 impl Debug for RnsContext {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("RnsContext")
             .field("moduli_u64", &self.moduli_u64)
             // .field("moduli", &self.moduli)
@@ -46,7 +96,7 @@ impl RnsContext {
     /// Returns an error if the list is empty, or if the moduli are no coprime.
     pub fn new(moduli_u64: &[u64]) -> Result<Self> {
         if moduli_u64.is_empty() {
-            Err(Error::Default("The list of moduli is empty".to_string()))
+            Err(Error::EmptyModuli)
         } else {
             let mut product = BigUint::one();
             let mut product_dig = BigUintDig::one();
@@ -58,7 +108,7 @@ impl RnsContext {
                         let (d, _, _) = BigUintDig::from(moduli_u64[i])
                             .extended_gcd(&BigUintDig::from(moduli_u64[j]));
                         if d.cmp(&BigIntDig::from(1)) != Ordering::Equal {
-                            return Err(Error::Default("The moduli are not coprime".to_string()));
+                            return Err(Error::NonCoprimeModuli);
                         }
                     }
                 }
@@ -95,18 +145,73 @@ impl RnsContext {
                 );
             }
 
+            // inv_moduli[i][j] = q_j^(-1) % q_i, for j != i, and 0 for j = i
+            let inv_moduli = (0..moduli_u64.len())
+                .map(|i| {
+                    (0..moduli_u64.len())
+                        .map(|j| {
+                            if i == j {
+                                0
+                            } else {
+                                BigUintDig::from(moduli_u64[j])
+                                    .mod_inverse(&BigUintDig::from(moduli_u64[i]))
+                                    .unwrap()
+                                    .to_u64()
+                                    .unwrap()
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+
+            // Mixed-radix digits of floor(product / 2)
+            let mut half: BigUint = &product >> 1;
+            let mut half_mixed_radix = Vec::with_capacity(moduli_u64.len());
+            for modulus in moduli_u64 {
+                half_mixed_radix.push((&half % modulus).to_u64().unwrap());
+                half /= *modulus;
+            }
+
             Ok(Self {
-                moduli_u64: moduli_u64.to_owned(),
+                moduli_u64: moduli_u64.to_vec(),
                 moduli,
                 q_tilde,
                 q_tilde_shoup,
                 q_star,
                 garner,
+                inv_moduli,
+                half_mixed_radix,
                 product,
+                prefixes: PrefixContexts::new(moduli_u64.len()),
             })
         }
     }
 
+    /// Create a RNS context whose modulus is at least `min_modulus`, from
+    /// distinct 62-bit primes supporting the NTT of size `degree`. The primes
+    /// are generated greedily, from the largest one, until their product is
+    /// larger than or equal to `min_modulus`.
+    ///
+    /// Returns the RNS context and the list of moduli, or an error if the
+    /// degree is not a power of two larger or equal to 8.
+    pub fn new_targeting(min_modulus: &BigUint, degree: usize) -> Result<(Self, Vec<u64>)> {
+        if !degree.is_power_of_two() || degree < 8 {
+            return Err(Error::InvalidDegree(degree));
+        }
+
+        let mut moduli = vec![];
+        let mut product = BigUint::one();
+        let mut upper_bound = 1u64 << 62;
+        while moduli.is_empty() || &product < min_modulus {
+            upper_bound =
+                generate_prime(62, 2 * degree as u64, upper_bound).ok_or(Error::NotEnoughPrimes)?;
+            moduli.push(upper_bound);
+            product *= upper_bound;
+        }
+
+        Ok((Self::new(&moduli)?, moduli))
+    }
+
     /// Returns the product of the moduli used when creating the RNS context.
     pub const fn modulus(&self) -> &BigUint {
         &self.product
@@ -114,9 +219,68 @@ impl RnsContext {
 
     /// Project a BigUint into its rests.
     pub fn project(&self, a: &BigUint) -> Vec<u64> {
-        let mut rests = Vec::with_capacity(self.moduli_u64.len());
-        for modulus in &self.moduli_u64 {
-            rests.push((a % modulus).to_u64().unwrap())
+        let mut rests = vec![0; self.moduli.len()];
+        self.project_into(a, &mut rests);
+        rests
+    }
+
+    /// Project a BigUint into its rests, which are written in `out` without
+    /// allocating.
+    ///
+    /// Aborts if `out` does not have one entry per modulus.
+    pub fn project_into(&self, a: &BigUint, out: &mut [u64]) {
+        assert_eq!(out.len(), self.moduli.len());
+        // The value may be secret, so it is reduced digit by digit instead of
+        // computing intermediate big integers that are not zeroized.
+        izip!(out.iter_mut(), self.moduli.iter()).for_each(|(r, qi)| {
+            *r = a.iter_u64_digits().rev().fold(0u64, |acc, d| {
+                qi.reduce_u128(((acc as u128) << 64) | (d as u128))
+            })
+        })
+    }
+
+    /// Project many BigUint into their rests, and output them as a matrix
+    /// whose j-th column contains the rests of the j-th value; this is
+    /// equivalent to calling [`RnsContext::project`] on each value.
+    ///
+    /// The values are reduced digit by digit in base 2^64 using the modular
+    /// reduction of the moduli, instead of using big integer divisions. When
+    /// the `rayon` feature is enabled, the values are projected in parallel;
+    /// each column only depends on its value, so the output is the same.
+    pub fn project_many(&self, values: &[BigUint]) -> Array2<u64> {
+        let mut rests = Array2::zeros((self.moduli.len(), values.len()));
+        let project_into = |mut column: ArrayViewMut1<u64>, v: &BigUint| {
+            izip!(column.iter_mut(), self.moduli.iter()).for_each(|(r, qi)| {
+                *r = v.iter_u64_digits().rev().fold(0u64, |acc, d| {
+                    qi.reduce_u128(((acc as u128) << 64) | (d as u128))
+                })
+            })
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use ndarray::parallel::prelude::*;
+            rests
+                .axis_iter_mut(Axis(1))
+                .into_par_iter()
+                .zip(values.par_iter())
+                .for_each(|(column, v)| project_into(column, v));
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        izip!(rests.axis_iter_mut(Axis(1)), values).for_each(|(column, v)| project_into(column, v));
+
+        rests
+    }
+
+    /// Project a BigInt into its rests, each in [0, q_i).
+    ///
+    /// A negative integer -a is projected into the negation of the rests of a,
+    /// so that the projection is consistent with `project` for any magnitude.
+    pub fn project_signed(&self, a: &BigInt) -> Vec<u64> {
+        let mut rests = self.project(a.magnitude());
+        if a.sign() == Sign::Minus {
+            izip!(rests.iter_mut(), &self.moduli).for_each(|(ri, qi)| *ri = qi.neg(*ri));
         }
         rests
     }
@@ -132,6 +296,197 @@ impl RnsContext {
         result % &self.product
     }
 
+    /// Returns the RNS context over the first `num_moduli` moduli. The context
+    /// is computed on the first call, and cached.
+    ///
+    /// Returns an error if `num_moduli` is 0 or larger than the number of
+    /// moduli.
+    pub fn sub_context(&self, num_moduli: usize) -> Result<Arc<RnsContext>> {
+        if num_moduli == 0 || num_moduli > self.moduli_u64.len() {
+            return Err(Error::InvalidNumberOfModuli {
+                got: num_moduli,
+                max: self.moduli_u64.len(),
+            });
+        }
+        // The moduli of a prefix are coprime, so the creation cannot fail.
+        let new = || Arc::new(Self::new(&self.moduli_u64[..num_moduli]).unwrap());
+        Ok(self.prefixes.get_or_init(num_moduli, new))
+    }
+
+    /// Lift the first `num_moduli` rests into a BigUint, i.e., compute the
+    /// value of the rests modulo the product of the first `num_moduli` moduli.
+    ///
+    /// Returns an error if `num_moduli` is 0, or larger than the number of
+    /// moduli or than the number of rests.
+    pub fn lift_partial(&self, rests: ArrayView1<u64>, num_moduli: usize) -> Result<BigUint> {
+        if num_moduli > rests.len() {
+            return Err(Error::NotEnoughRests {
+                got: rests.len(),
+                expected: num_moduli,
+            });
+        }
+        Ok(self
+            .sub_context(num_moduli)?
+            .lift(rests.slice(s![..num_moduli])))
+    }
+
+    /// Lift rests into a BigInt in the centered interval (-q/2, q/2], where q
+    /// is the product of the moduli.
+    ///
+    /// When q is even, the value q/2 is lifted to the positive q/2.
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode.
+    pub fn lift_centered(&self, rests: ArrayView1<u64>) -> BigInt {
+        let digits = self.to_mixed_radix(rests);
+        let lifted = izip!(digits.iter(), self.moduli_u64.iter())
+            .rev()
+            .fold(BigUint::zero(), |acc, (d_i, q_i)| acc * *q_i + *d_i);
+        if self.mixed_radix_greater_than_half(&digits) {
+            BigInt::from(lifted) - BigInt::from(self.product.clone())
+        } else {
+            BigInt::from(lifted)
+        }
+    }
+
+    /// Switch values from this context to the context `to` with a fast RNS
+    /// basis conversion. The rests are given with one row per modulus and one
+    /// column per value, and the output has one row per modulus of `to`.
+    ///
+    /// When `approximate` is false, the values are lifted in [-q/2, q/2); see
+    /// [`RnsConverter::convert`]. Otherwise, a value x in [0, q) is switched
+    /// to x + a * q for an integer a in [0, k), where k is the number of moduli
+    /// of this context; see [`RnsConverter::convert_approximate`].
+    ///
+    /// Returns an error if the number of rows is not the number of moduli.
+    pub fn switch_basis(
+        self: &Arc<Self>,
+        to: &Arc<RnsContext>,
+        rests: ArrayView2<u64>,
+        approximate: bool,
+    ) -> Result<Array2<u64>> {
+        if rests.nrows() != self.moduli.len() {
+            return Err(Error::InvalidLength(rests.nrows(), self.moduli.len()));
+        }
+        let converter = RnsConverter::new(self, to);
+        let mut out = Array2::zeros((to.moduli.len(), rests.ncols()));
+        izip!(out.axis_iter_mut(Axis(1)), rests.axis_iter(Axis(1))).for_each(|(out, rests)| {
+            if approximate {
+                converter.convert_approximate(rests, out)
+            } else {
+                converter.convert(rests, out)
+            }
+        });
+        Ok(out)
+    }
+
+    /// Compute the mixed-radix (Garner) digits of the lift x in [0, q) of the
+    /// rests, i.e., the digits d_i in [0, q_i) such that
+    /// x = d_0 + d_1 * q_0 + d_2 * q_0 * q_1 + ... + d_(k-1) * q_0 * ... *
+    /// q_(k-2).
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode.
+    pub fn to_mixed_radix(&self, rests: ArrayView1<u64>) -> Vec<u64> {
+        debug_assert_eq!(rests.len(), self.moduli.len());
+        let mut digits: Vec<u64> = Vec::with_capacity(self.moduli.len());
+        for (r_i, q_i, inv_i) in izip!(rests.iter(), &self.moduli, &self.inv_moduli) {
+            let mut d_i = q_i.reduce(*r_i);
+            for (d_j, inv_ij) in izip!(&digits, &inv_i[..digits.len()]) {
+                d_i = q_i.mul(q_i.sub(d_i, q_i.reduce(*d_j)), *inv_ij);
+            }
+            digits.push(d_i);
+        }
+        digits
+    }
+
+    /// Divide the lift x in [0, q) of the rests by the modulus q_i of index
+    /// `index`, when q_i divides x exactly, i.e., when the mixed-radix digit
+    /// of x in a basis starting with q_i, which is the i-th rest, is zero.
+    ///
+    /// Returns the rests of x / q_i modulo the moduli other than q_i, in the
+    /// same order, or None if q_i does not divide x or if the index is out of
+    /// range.
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode.
+    pub fn divide_exact(&self, rests: ArrayView1<u64>, index: usize) -> Option<Vec<u64>> {
+        debug_assert_eq!(rests.len(), self.moduli.len());
+        if index >= self.moduli.len() || self.moduli[index].reduce(rests[index]) != 0 {
+            return None;
+        }
+        Some(
+            izip!(rests.iter(), &self.moduli, &self.inv_moduli)
+                .enumerate()
+                .filter(|(j, _)| *j != index)
+                .map(|(_, (r_j, q_j, inv_j))| q_j.mul(q_j.reduce(*r_j), inv_j[index]))
+                .collect(),
+        )
+    }
+
+    /// Divide many values by the modulus q_i of index `index`, when q_i divides
+    /// all of them exactly. The rests are given with one row per modulus and
+    /// one column per value, as in [`RnsContext::switch_basis`]; since the
+    /// division is linear, this also divides the NTT of the values.
+    ///
+    /// Returns the rests of the quotients, with one row per modulus other than
+    /// q_i, in the same order, or None if q_i does not divide all the values
+    /// or if the index is out of range; this is equivalent to calling
+    /// [`RnsContext::divide_exact`] on each column. The divisibility is checked
+    /// in constant time, so only whether all the values are divisible leaks.
+    ///
+    /// Returns an error if the number of rows is not the number of moduli.
+    pub fn divide_exact_many(
+        &self,
+        rests: ArrayView2<u64>,
+        index: usize,
+    ) -> Result<Option<Array2<u64>>> {
+        if rests.nrows() != self.moduli.len() {
+            return Err(Error::InvalidLength(rests.nrows(), self.moduli.len()));
+        }
+        if index >= self.moduli.len() {
+            return Ok(None);
+        }
+
+        // The rests modulo q_i are accumulated without branching.
+        let q_i = &self.moduli[index];
+        if rests
+            .row(index)
+            .iter()
+            .fold(0, |acc, r| acc | q_i.reduce(*r))
+            != 0
+        {
+            return Ok(None);
+        }
+
+        let others = (0..self.moduli.len())
+            .filter(|j| *j != index)
+            .collect::<Vec<_>>();
+        let mut quotients = rests.select(Axis(0), &others);
+        izip!(quotients.outer_iter_mut(), &others).for_each(|(mut row, j)| {
+            let row = row.as_slice_mut().unwrap();
+            self.moduli[*j].reduce_vec(row);
+            self.moduli[*j].scalar_mul_vec(row, self.inv_moduli[*j][index]);
+        });
+        Ok(Some(quotients))
+    }
+
+    /// Returns whether the lift x in [0, q) of the rests is larger than q/2,
+    /// i.e., whether the rests are lifted to a negative value by
+    /// [`RnsContext::lift_centered`]. This does not allocate a BigUint.
+    ///
+    /// Aborts if the number of rests is different than the number of moduli in
+    /// debug mode.
+    pub fn is_greater_than_half(&self, rests: ArrayView1<u64>) -> bool {
+        self.mixed_radix_greater_than_half(&self.to_mixed_radix(rests))
+    }
+
+    // Compares mixed-radix digits with those of floor(q / 2), starting from the
+    // most significant digit.
+    fn mixed_radix_greater_than_half(&self, digits: &[u64]) -> bool {
+        digits.iter().rev().cmp(self.half_mixed_radix.iter().rev()) == Ordering::Greater
+    }
+
     /// Getter for the i-th garner coefficient.
     pub fn get_garner(&self, i: usize) -> Option<&BigUint> {
         self.garner.get(i)
@@ -141,11 +496,14 @@ impl RnsContext {
 #[cfg(test)]
 mod tests {
 
-    use std::error::Error;
+    use std::{error::Error, sync::Arc};
 
     use super::RnsContext;
-    use ndarray::ArrayView1;
-    use num_bigint::BigUint;
+    use crate::ntt::supports_ntt;
+    use itertools::{izip, Itertools};
+    use ndarray::{s, ArrayView1, Axis};
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::{One, Signed, Zero};
     use rand::RngCore;
 
     #[test]
@@ -156,13 +514,39 @@ mod tests {
 
         let e = RnsContext::new(&[]);
         assert!(e.is_err());
-        assert_eq!(e.unwrap_err().to_string(), "The list of moduli is empty");
+        assert_eq!(e.unwrap_err().to_string(), "The list of moduli is empty.");
         let e = RnsContext::new(&[2, 4]);
         assert!(e.is_err());
-        assert_eq!(e.unwrap_err().to_string(), "The moduli are not coprime");
+        assert_eq!(e.unwrap_err().to_string(), "The moduli are not coprime.");
         let e = RnsContext::new(&[2, 3, 5, 30]);
         assert!(e.is_err());
-        assert_eq!(e.unwrap_err().to_string(), "The moduli are not coprime");
+        assert_eq!(e.unwrap_err().to_string(), "The moduli are not coprime.");
+    }
+
+    #[test]
+    fn new_targeting() -> Result<(), Box<dyn Error>> {
+        for target_bits in [0, 1, 61, 62, 63, 200, 1000] {
+            for degree in [8, 4096] {
+                let target = BigUint::one() << target_bits;
+                let (rns, moduli) = RnsContext::new_targeting(&target, degree)?;
+                assert!(rns.modulus() >= &target);
+                assert_eq!(
+                    rns.modulus(),
+                    &moduli
+                        .iter()
+                        .map(|qi| BigUint::from(*qi))
+                        .product::<BigUint>()
+                );
+                assert!(moduli.iter().all_unique());
+                assert!(moduli.iter().all(|qi| supports_ntt(*qi, degree)));
+                assert_eq!(moduli.len(), target_bits / 62 + 1);
+            }
+        }
+
+        assert!(RnsContext::new_targeting(&BigUint::one(), 0).is_err());
+        assert!(RnsContext::new_targeting(&BigUint::one(), 1000).is_err());
+
+        Ok(())
     }
 
     #[test]
@@ -232,4 +616,353 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn project_many() -> Result<(), Box<dyn Error>> {
+        let mut rng = rand::thread_rng();
+        for moduli in [
+            &[4u64, 15, 1153] as &[u64],
+            &[
+                4611686018326724609,
+                4611686018309947393,
+                4611686018282684417,
+            ],
+        ] {
+            let rns = RnsContext::new(moduli)?;
+            let mut values = vec![BigUint::zero(), BigUint::one(), rns.modulus() - 1u64];
+            for _ in 0..100 {
+                // Values up to 2^256, i.e., larger than the modulus.
+                let v = (0..4).fold(BigUint::zero(), |acc, _| (acc << 64) + rng.next_u64());
+                values.push(v);
+            }
+
+            let rests = rns.project_many(&values);
+            assert_eq!(rests.shape(), [moduli.len(), values.len()]);
+            for (column, v) in izip!(rests.columns(), &values) {
+                assert_eq!(column.to_vec(), rns.project(v));
+            }
+        }
+
+        let rns = RnsContext::new(&[4, 15, 1153])?;
+        assert_eq!(rns.project_many(&[]).shape(), [3, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_signed() -> Result<(), Box<dyn Error>> {
+        let rns = RnsContext::new(&[4, 15, 1153])?;
+        let product = BigInt::from(4u64 * 15 * 1153);
+
+        assert_eq!(rns.project_signed(&BigInt::from(-1)), &[3u64, 14, 1152]);
+        assert_eq!(rns.project_signed(&BigInt::from(0)), &[0u64, 0, 0]);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let a = BigInt::from(rng.next_u64() >> 48) - BigInt::from(1u64 << 15);
+
+            // The projection of non-negative integers matches the unsigned one.
+            if let Some(a_unsigned) = a.to_biguint() {
+                assert_eq!(rns.project_signed(&a), rns.project(&a_unsigned));
+            }
+
+            // The projection wraps consistently modulo the product.
+            assert_eq!(rns.project_signed(&a), rns.project_signed(&(&a + &product)));
+            assert_eq!(
+                rns.project_signed(&a),
+                rns.project_signed(&(&a - 3 * &product))
+            );
+
+            // The projection of small integers round-trips with the centered lift.
+            let rests = rns.project_signed(&a);
+            assert_eq!(rns.lift_centered(ArrayView1::from(&rests)), a);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn lift_centered() -> Result<(), Box<dyn Error>> {
+        let rns = RnsContext::new(&[4, 15, 1153])?;
+        let product = 4u64 * 15 * 1153;
+
+        // Small negative numbers are lifted to negative values.
+        for i in 1..100u64 {
+            let rests = rns.project(&BigUint::from(product - i));
+            let lifted = rns.lift_centered(ArrayView1::from(&rests));
+            assert_eq!(lifted, BigInt::from(-(i as i64)));
+            assert!(lifted.is_negative());
+
+            // The absolute value projects to the negated rests.
+            let abs_rests = rns.project(lifted.magnitude());
+            assert_eq!(abs_rests, rns.project(&BigUint::from(i)));
+            assert_eq!(
+                rns.lift_centered(ArrayView1::from(&abs_rests)),
+                BigInt::from(i)
+            );
+        }
+
+        // Small non-negative numbers are unchanged.
+        for i in 0..100u64 {
+            let rests = rns.project(&BigUint::from(i));
+            assert_eq!(rns.lift_centered(ArrayView1::from(&rests)), BigInt::from(i));
+        }
+
+        // The boundaries of the interval (-q/2, q/2] for an even product.
+        let half = product / 2;
+        let rests = rns.project(&BigUint::from(half));
+        assert_eq!(
+            rns.lift_centered(ArrayView1::from(&rests)),
+            BigInt::from(half)
+        );
+        let rests = rns.project(&BigUint::from(half + 1));
+        assert_eq!(
+            rns.lift_centered(ArrayView1::from(&rests)),
+            -BigInt::from(half - 1)
+        );
+
+        // The boundaries of the interval (-q/2, q/2] for an odd product.
+        let rns = RnsContext::new(&[15, 1153])?;
+        let product = 15u64 * 1153;
+        let rests = rns.project(&BigUint::from(product / 2));
+        assert_eq!(
+            rns.lift_centered(ArrayView1::from(&rests)),
+            BigInt::from(product / 2)
+        );
+        let rests = rns.project(&BigUint::from(product / 2 + 1));
+        assert_eq!(
+            rns.lift_centered(ArrayView1::from(&rests)),
+            -BigInt::from(product / 2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn mixed_radix() -> Result<(), Box<dyn Error>> {
+        let mut rng = rand::thread_rng();
+        for moduli in [
+            vec![2],
+            vec![4, 15, 1153],
+            vec![15, 1153],
+            vec![4611686018326724609, 4611686018309947393, 1153],
+        ] {
+            let rns = RnsContext::new(&moduli)?;
+            let q = rns.modulus();
+            let half = q >> 1;
+
+            let mut inputs = vec![BigUint::zero(), q - 1u64];
+            for i in 0..10u64 {
+                if half >= BigUint::from(i) {
+                    inputs.push(&half - i);
+                }
+                inputs.push((&half + i) % q);
+            }
+            for _ in 0..100 {
+                let x = (BigUint::from(rng.next_u64()) << 128)
+                    + (BigUint::from(rng.next_u64()) << 64)
+                    + rng.next_u64();
+                inputs.push(x % q);
+            }
+
+            for x in inputs {
+                let rests = rns.project(&x);
+
+                // The digits are reduced and reconstruct the value.
+                let digits = rns.to_mixed_radix(ArrayView1::from(&rests));
+                assert_eq!(digits.len(), moduli.len());
+                let mut radix = BigUint::one();
+                let mut value = BigUint::zero();
+                for (d_i, q_i) in digits.iter().zip(&moduli) {
+                    assert!(d_i < q_i);
+                    value += &radix * *d_i;
+                    radix *= *q_i;
+                }
+                assert_eq!(value, x);
+
+                // The sign predicate matches the BigUint comparison.
+                assert_eq!(
+                    rns.is_greater_than_half(ArrayView1::from(&rests)),
+                    &x << 1 > *q
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn lift_partial() -> Result<(), Box<dyn Error>> {
+        let mut rng = rand::thread_rng();
+        let moduli = [4, 15, 1153, 4611686018326724609, 4611686018309947393];
+        let rns = RnsContext::new(&moduli)?;
+
+        for j in 1..=moduli.len() {
+            let prefix = RnsContext::new(&moduli[..j])?;
+            let sub = rns.sub_context(j)?;
+            assert_eq!(*sub, prefix);
+            // The prefix contexts are only cached with std.
+            #[cfg(feature = "std")]
+            assert!(std::sync::Arc::ptr_eq(&sub, &rns.sub_context(j)?));
+
+            for _ in 0..100 {
+                let x = (BigUint::from(rng.next_u64()) << 192)
+                    + (BigUint::from(rng.next_u64()) << 128)
+                    + (BigUint::from(rng.next_u64()) << 64)
+                    + rng.next_u64();
+                let rests = rns.project(&(x % rns.modulus()));
+                assert_eq!(
+                    rns.lift_partial(ArrayView1::from(&rests), j)?,
+                    prefix.lift(ArrayView1::from(&rests[..j]))
+                );
+                assert_eq!(
+                    rns.lift_partial(ArrayView1::from(&rests[..j]), j)?,
+                    prefix.lift(ArrayView1::from(&rests[..j]))
+                );
+            }
+        }
+
+        let rests = rns.project(&BigUint::one());
+        assert!(rns.sub_context(0).is_err());
+        assert!(rns.sub_context(moduli.len() + 1).is_err());
+        assert!(rns.lift_partial(ArrayView1::from(&rests), 0).is_err());
+        assert!(rns
+            .lift_partial(ArrayView1::from(&rests), moduli.len() + 1)
+            .is_err());
+        assert!(rns.lift_partial(ArrayView1::from(&rests[..2]), 3).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn divide_exact() -> Result<(), Box<dyn Error>> {
+        let mut rng = rand::thread_rng();
+        let moduli = [4, 15, 1153, 4611686018326724609, 4611686018309947393];
+        let rns = RnsContext::new(&moduli)?;
+
+        for (i, qi) in moduli.iter().enumerate() {
+            let others = moduli
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, qj)| *qj)
+                .collect_vec();
+            let rns_others = RnsContext::new(&others)?;
+
+            for _ in 0..100 {
+                let k = ((BigUint::from(rng.next_u64()) << 192)
+                    + (BigUint::from(rng.next_u64()) << 128)
+                    + (BigUint::from(rng.next_u64()) << 64)
+                    + rng.next_u64())
+                    % rns_others.modulus();
+
+                // Multiples of qi are divided exactly.
+                let rests = rns.project(&(&k * *qi));
+                assert_eq!(
+                    rns.divide_exact(ArrayView1::from(&rests), i),
+                    Some(rns_others.project(&k))
+                );
+
+                // Non-multiples of qi are rejected.
+                let r = 1 + rng.next_u64() % (qi - 1);
+                let rests = rns.project(&((&k * *qi + r) % rns.modulus()));
+                assert_eq!(rns.divide_exact(ArrayView1::from(&rests), i), None);
+            }
+        }
+
+        let rests = rns.project(&BigUint::zero());
+        assert_eq!(
+            rns.divide_exact(ArrayView1::from(&rests), 0),
+            Some(vec![0; moduli.len() - 1])
+        );
+        assert_eq!(
+            rns.divide_exact(ArrayView1::from(&rests), moduli.len()),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn divide_exact_many() -> Result<(), Box<dyn Error>> {
+        let mut rng = rand::thread_rng();
+        let moduli = [4, 15, 1153, 4611686018326724609, 4611686018309947393];
+        let rns = RnsContext::new(&moduli)?;
+
+        for i in 0..moduli.len() {
+            // The values are divided as with `divide_exact`.
+            let values = (0..10)
+                .map(|_| BigUint::from(rng.next_u64()) * moduli[i] % rns.modulus())
+                .collect_vec();
+            let rests = rns.project_many(&values);
+            let quotients = rns.divide_exact_many(rests.view(), i)?.unwrap();
+            assert_eq!(quotients.nrows(), moduli.len() - 1);
+            for (r, q) in izip!(rests.axis_iter(Axis(1)), quotients.axis_iter(Axis(1))) {
+                assert_eq!(rns.divide_exact(r, i), Some(q.to_vec()));
+            }
+
+            // A single value which is not a multiple of qi rejects the batch.
+            let mut values = values;
+            values[3] += 1u64;
+            let rests = rns.project_many(&values);
+            assert_eq!(rns.divide_exact_many(rests.view(), i)?, None);
+        }
+
+        let rests = rns.project_many(&[BigUint::zero()]);
+        assert_eq!(rns.divide_exact_many(rests.view(), moduli.len())?, None);
+        assert_eq!(
+            rns.divide_exact_many(rests.slice(s![1.., ..]), 0),
+            Err(crate::Error::InvalidLength(moduli.len() - 1, moduli.len()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn switch_basis() -> Result<(), Box<dyn Error>> {
+        let from = Arc::new(RnsContext::new(&[4611686018326724609, 1153])?);
+        let to = Arc::new(RnsContext::new(&[
+            4611686018309947393,
+            4611686018282684417,
+            4611686018257518593,
+        ])?);
+        let q = from.modulus();
+        let half = BigInt::from(q >> 1);
+
+        let mut rng = rand::thread_rng();
+        let mut values = (0..10u64)
+            .flat_map(|i| [BigUint::from(i), q - 1u64 - i])
+            .collect_vec();
+        values.extend((0..20).map(|_| BigUint::from(rng.next_u64()) % q));
+        let rests = from.project_many(&values);
+
+        let exact = from.switch_basis(&to, rests.view(), false)?;
+        let approximate = from.switch_basis(&to, rests.view(), true)?;
+        assert_eq!(exact.dim(), (3, values.len()));
+        assert_eq!(approximate.dim(), (3, values.len()));
+        for (v, e, a) in izip!(
+            &values,
+            exact.axis_iter(Axis(1)),
+            approximate.axis_iter(Axis(1))
+        ) {
+            // The exact conversion lifts the values in [-q/2, q/2).
+            let mut centered = BigInt::from(v.clone());
+            if centered >= half {
+                centered -= BigInt::from(q.clone());
+            }
+            assert_eq!(to.lift_centered(e), centered);
+
+            // The approximate conversion adds a small multiple of q.
+            let a = to.lift(a) - v;
+            assert!((&a % q).is_zero());
+            assert!(a / q < BigUint::from(2u64));
+        }
+
+        assert_eq!(
+            from.switch_basis(&to, to.project_many(&values).view(), true),
+            Err(crate::Error::InvalidLength(3, 2))
+        );
+
+        Ok(())
+    }
 }
@@ -3,12 +3,14 @@
 //! RNS scaler inspired from Remark 3.2 of <https://eprint.iacr.org/2021/204.pdf>.
 
 use super::RnsContext;
-use ethnum::{u256, U256};
+use crate::{Error, Result};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::cmp::min;
+use ethnum::{u256, I256, U256};
 use itertools::{izip, Itertools};
 use ndarray::{ArrayView1, ArrayViewMut1};
-use num_bigint::BigUint;
-use num_traits::{One, ToPrimitive, Zero};
-use std::{cmp::min, sync::Arc};
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Signed, ToPrimitive, Zero};
 
 /// Scaling factor when performing a RNS scaling.
 #[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -39,6 +41,71 @@ impl ScalingFactor {
     }
 }
 
+/// Rounding mode when performing a RNS scaling.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Rounding {
+    /// Round down, i.e., towards negative infinity.
+    Floor,
+    /// Round to the nearest integer; ties are rounded half away from zero.
+    #[default]
+    Nearest,
+    /// Round up, i.e., towards positive infinity.
+    Ceiling,
+}
+
+/// Fractional parts of the scaled omega and gamma, scaled by 2^127 and rounded
+/// so that their inner product with the rests is either an upper bound or a
+/// lower bound of the exact fractional part of the scaled value.
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct Theta {
+    gamma_lo: u64,
+    gamma_hi: u64,
+    gamma_sign: bool,
+
+    omega_lo: Box<[u64]>,
+    omega_hi: Box<[u64]>,
+    omega_sign: Box<[bool]>,
+}
+
+impl Theta {
+    // Selects the rounding of the fractional parts so that sum_i rests_i *
+    // theta_omega_i - v * theta_gamma is an upper bound of its exact value when
+    // `up` is set, and a lower bound otherwise.
+    fn new(gamma: &ScaledTheta, omega: &[ScaledTheta], up: bool) -> Self {
+        let (gamma_lo, gamma_hi) = gamma.rounded(!up);
+        let (omega_lo, omega_hi): (Vec<u64>, Vec<u64>) =
+            omega.iter().map(|theta| theta.rounded(up)).unzip();
+        Self {
+            gamma_lo,
+            gamma_hi,
+            gamma_sign: gamma.sign,
+            omega_lo: omega_lo.into_boxed_slice(),
+            omega_hi: omega_hi.into_boxed_slice(),
+            omega_sign: omega.iter().map(|theta| theta.sign).collect(),
+        }
+    }
+}
+
+// A fractional part scaled by 2^127, as a sign and a magnitude rounded down and
+// up, each split into two u64 words.
+struct ScaledTheta {
+    sign: bool,
+    floor: (u64, u64),
+    ceil: (u64, u64),
+}
+
+impl ScaledTheta {
+    // Returns the magnitude such that the signed value is rounded up when `up`
+    // is set, and down otherwise.
+    const fn rounded(&self, up: bool) -> (u64, u64) {
+        if up != self.sign {
+            self.ceil
+        } else {
+            self.floor
+        }
+    }
+}
+
 /// Scaler for a RNS context.
 /// This is a helper struct to perform RNS scaling.
 #[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -46,18 +113,22 @@ pub struct RnsScaler {
     from: Arc<RnsContext>,
     to: Arc<RnsContext>,
     scaling_factor: ScalingFactor,
+    rounding: Rounding,
 
     gamma: Box<[u64]>,
     gamma_shoup: Box<[u64]>,
-    theta_gamma_lo: u64,
-    theta_gamma_hi: u64,
-    theta_gamma_sign: bool,
 
     omega: Box<[Box<[u64]>]>,
     omega_shoup: Box<[Box<[u64]>]>,
-    theta_omega_lo: Box<[u64]>,
-    theta_omega_hi: Box<[u64]>,
-    theta_omega_sign: Box<[bool]>,
+
+    // Upper and lower approximations of the fractional parts.
+    theta_up: Theta,
+    theta_down: Theta,
+
+    // Whether the floor or the ceiling of the approximation of the fractional
+    // part must be checked against the other approximation; only set for
+    // scalers created in variable time.
+    check_rounding: bool,
 
     theta_garner_lo: Box<[u64]>,
     theta_garner_hi: Box<[u64]>,
@@ -65,23 +136,104 @@ pub struct RnsScaler {
 }
 
 impl RnsScaler {
-    /// Create a RNS scaler by numerator / denominator.
+    /// Create a RNS scaler by numerator / denominator, rounding to the nearest
+    /// integer.
     ///
     /// Aborts if denominator is equal to 0.
     pub fn new(
         from: &Arc<RnsContext>,
         to: &Arc<RnsContext>,
         scaling_factor: ScalingFactor,
+    ) -> Self {
+        // Rounding to the nearest integer is always supported.
+        Self::new_unchecked(from, to, scaling_factor, Rounding::Nearest, false)
+    }
+
+    /// Create a RNS scaler by numerator / denominator, with the specified
+    /// rounding mode.
+    ///
+    /// The fractional part of the scaled value is approximated with a precision
+    /// of about 2^(-64) * from.moduli.len(), so values closer than that to a
+    /// half-integer may be rounded either way to the nearest integer. The
+    /// Floor and Ceiling modes are exact as long as the denominator multiplied
+    /// by the number of moduli of `from` is at most 2^64.
+    ///
+    /// Returns an error if the rounding mode is Floor or Ceiling and the
+    /// denominator is too large; see [`RnsScaler::supports_rounding`] and
+    /// [`RnsScaler::new_with_rounding_vt`].
+    pub fn new_with_rounding(
+        from: &Arc<RnsContext>,
+        to: &Arc<RnsContext>,
+        scaling_factor: ScalingFactor,
+        rounding: Rounding,
+    ) -> Result<Self> {
+        if !Self::supports_rounding(from, &scaling_factor, rounding) {
+            return Err(Error::UnsupportedRounding(rounding));
+        }
+        Ok(Self::new_unchecked(
+            from,
+            to,
+            scaling_factor,
+            rounding,
+            false,
+        ))
+    }
+
+    /// Create a RNS scaler by numerator / denominator, with the specified
+    /// rounding mode, which is exact for any denominator.
+    ///
+    /// When the rounding mode is not supported by
+    /// [`RnsScaler::new_with_rounding`], the values that are too close to an
+    /// integer for the approximation of their fractional part to decide, such
+    /// as the exact multiples of the denominator, are scaled from their lift
+    /// with big integers.
+    ///
+    /// # Safety
+    ///
+    /// The scaling may then run in variable time, so this should only be used
+    /// when the scaled values are public.
+    pub unsafe fn new_with_rounding_vt(
+        from: &Arc<RnsContext>,
+        to: &Arc<RnsContext>,
+        scaling_factor: ScalingFactor,
+        rounding: Rounding,
+    ) -> Self {
+        let check_rounding = !Self::supports_rounding(from, &scaling_factor, rounding);
+        Self::new_unchecked(from, to, scaling_factor, rounding, check_rounding)
+    }
+
+    /// Returns whether a scaler from the context `from` with the specified
+    /// scaling factor can be created in constant time with the specified
+    /// rounding mode; see [`RnsScaler::new_with_rounding`].
+    pub fn supports_rounding(
+        from: &RnsContext,
+        scaling_factor: &ScalingFactor,
+        rounding: Rounding,
+    ) -> bool {
+        // A non-integer scaled value is at distance at least 1 / denominator of
+        // the integers, which is larger than the approximation error when the
+        // denominator times the number of moduli is at most 2^64.
+        rounding == Rounding::Nearest
+            || scaling_factor.is_one
+            || scaling_factor.denominator.clone() * from.moduli.len() <= BigUint::one() << 64
+    }
+
+    // Creates the scaler; `check_rounding` must be set when the rounding mode is
+    // not supported in constant time.
+    fn new_unchecked(
+        from: &Arc<RnsContext>,
+        to: &Arc<RnsContext>,
+        scaling_factor: ScalingFactor,
+        rounding: Rounding,
+        check_rounding: bool,
     ) -> Self {
         // Let's define gamma = round(numerator * from.product / denominator)
-        let (gamma, theta_gamma_lo, theta_gamma_hi, theta_gamma_sign) =
-            Self::extract_projection_and_theta(
-                to,
-                &from.product,
-                &scaling_factor.numerator,
-                &scaling_factor.denominator,
-                false,
-            );
+        let (gamma, theta_gamma) = Self::extract_projection_and_theta(
+            to,
+            &from.product,
+            &scaling_factor.numerator,
+            &scaling_factor.denominator,
+        );
         let gamma_shoup = izip!(&gamma, &to.moduli)
             .map(|(wi, q)| q.shoup(*wi))
             .collect_vec();
@@ -93,28 +245,28 @@ impl RnsScaler {
             omega.push(vec![0u64; from.moduli.len()].into_boxed_slice());
             omega_shoup.push(vec![0u64; from.moduli.len()].into_boxed_slice());
         }
-        let mut theta_omega_lo = Vec::with_capacity(from.garner.len());
-        let mut theta_omega_hi = Vec::with_capacity(from.garner.len());
-        let mut theta_omega_sign = Vec::with_capacity(from.garner.len());
+        let mut theta_omega = Vec::with_capacity(from.garner.len());
         for i in 0..from.garner.len() {
-            let (omega_i, theta_omega_i_lo, theta_omega_i_hi, theta_omega_i_sign) =
-                Self::extract_projection_and_theta(
-                    to,
-                    &from.garner[i],
-                    &scaling_factor.numerator,
-                    &scaling_factor.denominator,
-                    true,
-                );
+            let (omega_i, theta_omega_i) = Self::extract_projection_and_theta(
+                to,
+                &from.garner[i],
+                &scaling_factor.numerator,
+                &scaling_factor.denominator,
+            );
             for j in 0..to.moduli.len() {
                 let qj = &to.moduli[j];
                 omega[j][i] = qj.reduce(omega_i[j]);
                 omega_shoup[j][i] = qj.shoup(omega[j][i]);
             }
-            theta_omega_lo.push(theta_omega_i_lo);
-            theta_omega_hi.push(theta_omega_i_hi);
-            theta_omega_sign.push(theta_omega_i_sign);
+            theta_omega.push(theta_omega_i);
         }
 
+        // The fractional parts, rounded so that the computed fractional part of
+        // the scaled value is an upper bound (resp. a lower bound) of the exact
+        // one.
+        let theta_up = Theta::new(&theta_gamma, &theta_omega, true);
+        let theta_down = Theta::new(&theta_gamma, &theta_omega, false);
+
         // Determine the shift so that the sum of the scaled theta_garner fit on an U192
         // (shift + 1) + log(q * n) <= 192
         let theta_garner_shift = min(
@@ -147,16 +299,14 @@ impl RnsScaler {
             from: from.clone(),
             to: to.clone(),
             scaling_factor,
+            rounding,
             gamma: gamma.into_boxed_slice(),
             gamma_shoup: gamma_shoup.into_boxed_slice(),
-            theta_gamma_lo,
-            theta_gamma_hi,
-            theta_gamma_sign,
             omega: omega.into_boxed_slice(),
             omega_shoup: omega_shoup.into_boxed_slice(),
-            theta_omega_lo: theta_omega_lo.into_boxed_slice(),
-            theta_omega_hi: theta_omega_hi.into_boxed_slice(),
-            theta_omega_sign: theta_omega_sign.into_boxed_slice(),
+            theta_up,
+            theta_down,
+            check_rounding,
             theta_garner_lo: theta_garner_lo.into_boxed_slice(),
             theta_garner_hi: theta_garner_hi.into_boxed_slice(),
             theta_garner_shift: theta_garner_shift as usize,
@@ -166,59 +316,55 @@ impl RnsScaler {
     // Let's define gamma = round(numerator * input / denominator)
     // and theta_gamma such that theta_gamma = numerator * input / denominator -
     // gamma. This function projects gamma in the RNS context, and scales
-    // theta_gamma by 2**127 and rounds. It outputs the projection of gamma in the
-    // RNS context, and theta_lo, theta_hi, theta_sign such that theta_gamma =
-    // (-1)**theta_sign * (theta_lo + 2^64 * theta_hi).
+    // theta_gamma by 2**127. It outputs the projection of gamma in the RNS
+    // context, and theta_gamma rounded both ways.
     fn extract_projection_and_theta(
         ctx: &RnsContext,
         input: &BigUint,
         numerator: &BigUint,
         denominator: &BigUint,
-        round_up: bool,
-    ) -> (Vec<u64>, u64, u64, bool) {
+    ) -> (Vec<u64>, ScaledTheta) {
         let gamma = (numerator * input + (denominator >> 1)) / denominator;
         let projected = ctx.project(&gamma);
 
         let mut theta = (numerator * input) % denominator;
-        let mut theta_sign = false;
+        let mut sign = false;
         if denominator > &BigUint::one() {
             // If denominator is odd, flip theta if theta > (denominator >> 1)
             if denominator & BigUint::one() == BigUint::one() {
                 if theta > (denominator >> 1) {
-                    theta_sign = true;
+                    sign = true;
                     theta = denominator - theta;
                 }
             } else {
                 // denominator is even, flip if theta >= (denominator >> 1)
                 if theta >= (denominator >> 1) {
-                    theta_sign = true;
+                    sign = true;
                     theta = denominator - theta;
                 }
             }
         }
-        // theta = ((theta << 127) + (denominator >> 1)) / denominator;
-        // We can now split theta into two u64 words.
-        if round_up {
-            if theta_sign {
-                theta = (theta << 127) / denominator;
-            } else {
-                theta = ((theta << 127) + denominator - BigUint::one()) / denominator;
-            }
-        } else if theta_sign {
-            theta = ((theta << 127) + denominator - BigUint::one()) / denominator;
-        } else {
-            theta = (theta << 127) / denominator;
-        }
-        let theta_hi_biguint: BigUint = &theta >> 64;
-        theta -= &theta_hi_biguint << 64;
-        let theta_lo = theta.to_u64().unwrap();
-        let theta_hi = theta_hi_biguint.to_u64().unwrap();
+        // We can now scale the magnitude of theta, and split it into two u64 words.
+        let split = |theta: BigUint| {
+            let theta_hi: BigUint = &theta >> 64;
+            let theta_lo: BigUint = theta - (&theta_hi << 64);
+            (theta_lo.to_u64().unwrap(), theta_hi.to_u64().unwrap())
+        };
+        let floor = split((&theta << 127) / denominator);
+        let ceil = split(((theta << 127) + denominator - BigUint::one()) / denominator);
+
+        (projected, ScaledTheta { sign, floor, ceil })
+    }
 
-        (projected, theta_lo, theta_hi, theta_sign)
+    /// Returns the rounding mode of the scaler.
+    pub const fn rounding(&self) -> Rounding {
+        self.rounding
     }
 
-    /// Output the RNS representation of the rests scaled by numerator *
-    /// denominator, and either rounded or floored.
+    /// Output the RNS representation of the rests scaled by numerator /
+    /// denominator, and rounded according to the rounding mode of the scaler.
+    /// The rests are interpreted as an integer in [-q/2, q/2), where q is the
+    /// product of the moduli of the input context.
     ///
     /// Aborts if the number of rests is different than the number of moduli in
     /// debug mode, or if the size is not in [1, ..., rests.len()].
@@ -228,9 +374,12 @@ impl RnsScaler {
         out
     }
 
-    /// Compute the RNS representation of the rests scaled by numerator *
-    /// denominator, and either rounded or floored, and store the result in
-    /// `out`.
+    /// Compute the RNS representation of the rests scaled by numerator /
+    /// denominator, and rounded according to the rounding mode of the scaler,
+    /// and store the result in `out`.
+    ///
+    /// This runs in constant time unless the scaler was created with
+    /// [`RnsScaler::new_with_rounding_vt`].
     ///
     /// Aborts if the number of rests is different than the number of moduli in
     /// debug mode, or if the size of out is not in [1, ..., rests.len()].
@@ -255,49 +404,68 @@ impl RnsScaler {
                 U256::from(*ri) * U256::from((*thetag_lo as u128) | ((*thetag_hi as u128) << 64)),
             );
         }
-        // Let's compute v = round(sum_theta_garner / 2^theta_garner_shift)
+        // Let's compute v = round(sum_theta_garner / 2^theta_garner_shift); the
+        // input is negative when the fractional part is rounded up.
         sum_theta_garner >>= self.theta_garner_shift - 1;
         let v = sum_theta_garner.as_u128().div_ceil(2);
+        let input_sign = sum_theta_garner.as_u128() & 1 == 1;
 
         // If the scaling factor is not 1, compute the inner product with the
         // theta_omega
         let mut w_sign = false;
         let mut w = 0u128;
         if !self.scaling_factor.is_one {
-            let mut sum_theta_omega = u256::ZERO;
-            for (thetao_lo, thetao_hi, thetao_sign, ri) in izip!(
-                self.theta_omega_lo.iter(),
-                self.theta_omega_hi.iter(),
-                self.theta_omega_sign.iter(),
-                rests
-            ) {
-                let product = U256::from(*ri)
-                    * U256::from((*thetao_lo as u128) | ((*thetao_hi as u128) << 64));
-                if *thetao_sign {
-                    sum_theta_omega = sum_theta_omega.wrapping_sub(product);
-                } else {
-                    sum_theta_omega = sum_theta_omega.wrapping_add(product);
+            // Select the approximation of the fractional part so that an exact
+            // integer (resp. tie) is not moved across the rounding boundary.
+            let (theta, other_theta) = match (self.rounding, input_sign) {
+                (Rounding::Floor, _) | (Rounding::Nearest, false) => {
+                    (&self.theta_up, &self.theta_down)
+                }
+                (Rounding::Ceiling, _) | (Rounding::Nearest, true) => {
+                    (&self.theta_down, &self.theta_up)
+                }
+            };
+            let mut sum_theta_omega = Self::fractional_part(theta, rests, v);
+
+            // The exact fractional part lies between the two approximations, so
+            // it is rounded like them when they are rounded to the same integer.
+            // Otherwise, the scaled value is computed exactly, in variable time.
+            if self.check_rounding {
+                let ceiling = self.rounding == Rounding::Ceiling;
+                let other_sum = Self::fractional_part(other_theta, rests, v);
+                if Self::round_fractional_part(sum_theta_omega, ceiling)
+                    != Self::round_fractional_part(other_sum, ceiling)
+                {
+                    return self.scale_exact(rests, out, starting_index);
                 }
             }
 
-            // Let's subtract v * theta_gamma to sum_theta_omega.
-            let v_theta_gamma = U256::from(v)
-                * U256::from((self.theta_gamma_lo as u128) | ((self.theta_gamma_hi as u128) << 64));
-            if self.theta_gamma_sign {
-                sum_theta_omega = sum_theta_omega.wrapping_add(v_theta_gamma);
-            } else {
-                sum_theta_omega = sum_theta_omega.wrapping_sub(v_theta_gamma);
-            }
-
-            // Let's compute w = round(sum_theta_omega / 2^(192)).
-            w_sign = (sum_theta_omega >> (63 + 128)) > u256::ZERO;
+            // Let's compute w = floor(sum_theta_omega / 2^127) or w =
+            // ceil(sum_theta_omega / 2^127), after adding (resp. subtracting) 1/2
+            // for rounding to the nearest integer.
+            let half = u256::ONE << 126;
+            let floor = match (self.rounding, input_sign) {
+                (Rounding::Floor, _) => true,
+                (Rounding::Ceiling, _) => false,
+                (Rounding::Nearest, false) => {
+                    sum_theta_omega = sum_theta_omega.wrapping_add(half);
+                    true
+                }
+                (Rounding::Nearest, true) => {
+                    sum_theta_omega = sum_theta_omega.wrapping_sub(half);
+                    false
+                }
+            };
 
-            if w_sign {
-                w = ((!sum_theta_omega) >> 126isize).as_u128() + 1;
-                w /= 2;
+            w_sign = (sum_theta_omega >> 255) > u256::ZERO;
+            let (magnitude, truncate) = if w_sign {
+                (sum_theta_omega.wrapping_neg(), !floor)
             } else {
-                w = (sum_theta_omega >> 126isize).as_u128();
-                w = w.div_ceil(2)
+                (sum_theta_omega, floor)
+            };
+            w = (magnitude >> 127isize).as_u128();
+            if !truncate && magnitude.as_u128() & ((1u128 << 127) - 1) > 0 {
+                w += 1
             }
         }
 
@@ -338,29 +506,164 @@ impl RnsScaler {
             }
         }
     }
+
+    // Computes sum_i rests_i * theta_omega_i - v * theta_gamma, scaled by 2^127,
+    // as a signed integer in two's complement.
+    fn fractional_part(theta: &Theta, rests: ArrayView1<u64>, v: u128) -> U256 {
+        let mut sum_theta_omega = u256::ZERO;
+        for (thetao_lo, thetao_hi, thetao_sign, ri) in izip!(
+            theta.omega_lo.iter(),
+            theta.omega_hi.iter(),
+            theta.omega_sign.iter(),
+            rests
+        ) {
+            let product =
+                U256::from(*ri) * U256::from((*thetao_lo as u128) | ((*thetao_hi as u128) << 64));
+            if *thetao_sign {
+                sum_theta_omega = sum_theta_omega.wrapping_sub(product);
+            } else {
+                sum_theta_omega = sum_theta_omega.wrapping_add(product);
+            }
+        }
+
+        // Let's subtract v * theta_gamma to sum_theta_omega.
+        let v_theta_gamma =
+            U256::from(v) * U256::from((theta.gamma_lo as u128) | ((theta.gamma_hi as u128) << 64));
+        if theta.gamma_sign {
+            sum_theta_omega.wrapping_add(v_theta_gamma)
+        } else {
+            sum_theta_omega.wrapping_sub(v_theta_gamma)
+        }
+    }
+
+    // Returns the floor, or the ceiling, of a fractional part scaled by 2^127.
+    fn round_fractional_part(sum_theta_omega: U256, ceiling: bool) -> I256 {
+        let sum = if ceiling {
+            sum_theta_omega.wrapping_add((u256::ONE << 127) - u256::ONE)
+        } else {
+            sum_theta_omega
+        };
+        sum.as_i256() >> 127isize
+    }
+
+    // Computes the rests scaled by numerator / denominator and rounded down (or
+    // up) from the lift of the rests in [-q/2, q/2), using big integers.
+    fn scale_exact(
+        &self,
+        rests: ArrayView1<u64>,
+        mut out: ArrayViewMut1<u64>,
+        starting_index: usize,
+    ) {
+        debug_assert_ne!(self.rounding, Rounding::Nearest);
+
+        let x = self.from.lift(rests);
+        let mut x = BigInt::from(x);
+        if &x << 1 >= BigInt::from(self.from.product.clone()) {
+            x -= BigInt::from(self.from.product.clone());
+        }
+        let xn = x * BigInt::from(self.scaling_factor.numerator.clone());
+        let d = BigInt::from(self.scaling_factor.denominator.clone());
+
+        // The quotient is rounded towards zero.
+        let mut y = &xn / &d;
+        let remainder = xn - &y * &d;
+        if self.rounding == Rounding::Floor && remainder.is_negative() {
+            y -= 1u32;
+        } else if self.rounding == Rounding::Ceiling && remainder.is_positive() {
+            y += 1u32;
+        }
+
+        let y = self.to.project_signed(&y);
+        izip!(out.iter_mut(), &y[starting_index..]).for_each(|(out_i, yi)| *out_i = *yi);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::{error::Error, panic::catch_unwind, sync::Arc};
 
-    use super::RnsScaler;
+    use super::{RnsScaler, Rounding};
     use crate::rns::{scaler::ScalingFactor, RnsContext};
     use ndarray::ArrayView1;
-    use num_bigint::BigUint;
-    use num_traits::{ToPrimitive, Zero};
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::{Signed, Zero};
     use rand::{thread_rng, RngCore};
 
+    const ROUNDINGS: [Rounding; 3] = [Rounding::Floor, Rounding::Nearest, Rounding::Ceiling];
+
+    // Exact value of x * n / d rounded with the specified rounding mode.
+    fn scale_reference(x: &BigInt, n: &BigUint, d: &BigUint, rounding: Rounding) -> BigInt {
+        // Floor of a / b for b > 0.
+        let div_floor = |a: BigInt, b: BigInt| -> BigInt {
+            if a.is_negative() {
+                let a: BigInt = -a + &b - 1u64;
+                -(a / b)
+            } else {
+                a / b
+            }
+        };
+        let xn = x * BigInt::from(n.clone());
+        let d = BigInt::from(d.clone());
+        match rounding {
+            Rounding::Floor => div_floor(xn, d),
+            Rounding::Ceiling => -div_floor(-xn, d),
+            Rounding::Nearest => {
+                let magnitude = div_floor(2 * xn.abs() + &d, 2 * d);
+                if x.is_negative() {
+                    -magnitude
+                } else {
+                    magnitude
+                }
+            }
+        }
+    }
+
+    // Projects the centered value x in the context ctx.
+    fn project_centered(ctx: &RnsContext, x: &BigInt) -> Vec<u64> {
+        let q = BigInt::from(ctx.modulus().clone());
+        let x = ((x % &q) + &q) % &q;
+        ctx.project(&x.to_biguint().unwrap())
+    }
+
     #[test]
     fn constructor() -> Result<(), Box<dyn Error>> {
         let q = Arc::new(RnsContext::new(&[4, 4611686018326724609, 1153])?);
 
         let scaler = RnsScaler::new(&q, &q, ScalingFactor::one());
         assert_eq!(scaler.from, q);
+        assert_eq!(scaler.rounding(), Rounding::Nearest);
+
+        for rounding in ROUNDINGS {
+            let scaler = RnsScaler::new_with_rounding(&q, &q, ScalingFactor::one(), rounding)?;
+            assert_eq!(scaler.rounding(), rounding);
+            assert!(!scaler.check_rounding);
+        }
 
         assert!(
             catch_unwind(|| ScalingFactor::new(&BigUint::from(1u64), &BigUint::zero())).is_err()
         );
+
+        // The floor and the ceiling are only supported in variable time for large
+        // denominators.
+        let factor = ScalingFactor::new(&BigUint::from(1u64), q.modulus());
+        for rounding in ROUNDINGS {
+            let supported = rounding == Rounding::Nearest;
+            assert_eq!(
+                RnsScaler::supports_rounding(&q, &factor, rounding),
+                supported
+            );
+            if supported {
+                RnsScaler::new_with_rounding(&q, &q, factor.clone(), rounding)?;
+            } else {
+                assert_eq!(
+                    RnsScaler::new_with_rounding(&q, &q, factor.clone(), rounding),
+                    Err(crate::Error::UnsupportedRounding(rounding))
+                );
+            }
+            let scaler =
+                unsafe { RnsScaler::new_with_rounding_vt(&q, &q, factor.clone(), rounding) };
+            assert_eq!(scaler.check_rounding, !supported);
+        }
         Ok(())
     }
 
@@ -374,32 +677,25 @@ mod tests {
             for denominator in &[1u64, 2, 3, 4, 100, 101, 1000, 1001, 4611686018326724610] {
                 let n = BigUint::from(*numerator);
                 let d = BigUint::from(*denominator);
-                let scaler = RnsScaler::new(&q, &q, ScalingFactor::new(&n, &d));
+                for rounding in ROUNDINGS {
+                    let scaler =
+                        RnsScaler::new_with_rounding(&q, &q, ScalingFactor::new(&n, &d), rounding)?;
+
+                    for _ in 0..ntests {
+                        let x = vec![
+                            rng.next_u64() % q.moduli_u64[0],
+                            rng.next_u64() % q.moduli_u64[1],
+                            rng.next_u64() % q.moduli_u64[2],
+                        ];
+                        let mut x_lift = BigInt::from(q.lift(ArrayView1::from(&x)));
+                        if x_lift >= BigInt::from(q.modulus() >> 1) {
+                            x_lift -= BigInt::from(q.modulus().clone());
+                        }
 
-                for _ in 0..ntests {
-                    let x = vec![
-                        rng.next_u64() % q.moduli_u64[0],
-                        rng.next_u64() % q.moduli_u64[1],
-                        rng.next_u64() % q.moduli_u64[2],
-                    ];
-                    let mut x_lift = q.lift(ArrayView1::from(&x));
-                    let x_sign = x_lift >= (q.modulus() >> 1);
-                    if x_sign {
-                        x_lift = q.modulus() - x_lift;
+                        let z = scaler.scale_new((&x).into(), x.len());
+                        let expected = scale_reference(&x_lift, &n, &d, rounding);
+                        assert_eq!(z, project_centered(&q, &expected));
                     }
-
-                    let z = scaler.scale_new((&x).into(), x.len());
-                    let x_scaled_round = if x_sign {
-                        if d.to_u64().unwrap() % 2 == 0 {
-                            q.modulus()
-                                - (&(&x_lift * &n + ((&d >> 1usize) - 1u64)) / &d) % q.modulus()
-                        } else {
-                            q.modulus() - (&(&x_lift * &n + (&d >> 1)) / &d) % q.modulus()
-                        }
-                    } else {
-                        &(&x_lift * &n + (&d >> 1)) / &d
-                    };
-                    assert_eq!(z, q.project(&x_scaled_round));
                 }
             }
         }
@@ -428,32 +724,140 @@ mod tests {
             for denominator in &[1u64, 2, 3, 4, 100, 101, 1000, 1001, 4611686018326724610] {
                 let n = BigUint::from(*numerator);
                 let d = BigUint::from(*denominator);
-                let scaler = RnsScaler::new(&q, &r, ScalingFactor::new(&n, &d));
+                for rounding in ROUNDINGS {
+                    let scaler =
+                        RnsScaler::new_with_rounding(&q, &r, ScalingFactor::new(&n, &d), rounding)?;
+                    for _ in 0..ntests {
+                        let x = vec![
+                            rng.next_u64() % q.moduli_u64[0],
+                            rng.next_u64() % q.moduli_u64[1],
+                            rng.next_u64() % q.moduli_u64[2],
+                        ];
+
+                        let mut x_lift = BigInt::from(q.lift(ArrayView1::from(&x)));
+                        if x_lift >= BigInt::from(q.modulus() >> 1) {
+                            x_lift -= BigInt::from(q.modulus().clone());
+                        }
+
+                        let y = scaler.scale_new((&x).into(), r.moduli.len());
+                        let expected = scale_reference(&x_lift, &n, &d, rounding);
+                        assert_eq!(y, project_centered(&r, &expected));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scale_rounding_boundaries() -> Result<(), Box<dyn Error>> {
+        let ntests = 100;
+        let q = Arc::new(RnsContext::new(&[4u64, 4611686018326724609, 1153])?);
+        let r = Arc::new(RnsContext::new(&[
+            4611686018309947393,
+            4611686018282684417,
+            4611686018257518593,
+        ])?);
+        let half_q = BigInt::from(q.modulus() >> 1);
+        let mut rng = thread_rng();
+
+        for numerator in &[1u64, 3, 100, 1001, 4611686018326724610] {
+            for denominator in &[2u64, 4, 100, 1000, 1001, 4611686018326724610] {
+                let n = BigUint::from(*numerator);
+                let d = BigUint::from(*denominator);
+                let scalers = ROUNDINGS.map(|rounding| {
+                    RnsScaler::new_with_rounding(&q, &r, ScalingFactor::new(&n, &d), rounding)
+                        .unwrap()
+                });
+
                 for _ in 0..ntests {
-                    let x = vec![
-                        rng.next_u64() % q.moduli_u64[0],
-                        rng.next_u64() % q.moduli_u64[1],
-                        rng.next_u64() % q.moduli_u64[2],
-                    ];
-
-                    let mut x_lift = q.lift(ArrayView1::from(&x));
-                    let x_sign = x_lift >= (q.modulus() >> 1);
-                    if x_sign {
-                        x_lift = q.modulus() - x_lift;
+                    // Values x such that x * n / d is close to an integer or to a
+                    // half-integer, and of both signs.
+                    let k = BigInt::from(rng.next_u64() >> 8);
+                    let (n_int, d_int) = (BigInt::from(n.clone()), BigInt::from(d.clone()));
+                    for target in [&k * &d_int / &n_int, (2 * &k + 1) * &d_int / (2 * &n_int)] {
+                        for delta in -2i64..=2 {
+                            for x_lift in [&target + delta, -(&target + delta)] {
+                                if x_lift >= half_q || x_lift < -&half_q {
+                                    continue;
+                                }
+                                let x = project_centered(&q, &x_lift);
+                                for (scaler, rounding) in scalers.iter().zip(ROUNDINGS) {
+                                    let y = scaler.scale_new((&x).into(), r.moduli.len());
+                                    let expected = scale_reference(&x_lift, &n, &d, rounding);
+                                    assert_eq!(y, project_centered(&r, &expected));
+                                }
+                            }
+                        }
                     }
+                }
+            }
+        }
+        Ok(())
+    }
 
-                    let y = scaler.scale_new((&x).into(), r.moduli.len());
-                    let x_scaled_round = if x_sign {
-                        if d.to_u64().unwrap() % 2 == 0 {
-                            r.modulus()
-                                - (&(&x_lift * &n + ((&d >> 1usize) - 1u64)) / &d) % r.modulus()
-                        } else {
-                            r.modulus() - (&(&x_lift * &n + (&d >> 1)) / &d) % r.modulus()
+    #[test]
+    fn scale_large_denominators() -> Result<(), Box<dyn Error>> {
+        let ntests = 100;
+        let moduli = [
+            4611686018326724609u64,
+            4611686018309947393,
+            4611686018282684417,
+            4611686018257518593,
+        ];
+        let q = Arc::new(RnsContext::new(&moduli)?);
+        let q_first = Arc::new(RnsContext::new(&moduli[..3])?);
+        let t = Arc::new(RnsContext::new(&[65537])?);
+        let half_q = BigInt::from(q.modulus() >> 1);
+        let mut rng = thread_rng();
+
+        // Division by the last modulus, as in a modulus switching, and scaling by
+        // t / Q, as in a decryption.
+        for (to, n, d) in [
+            (&q_first, BigUint::from(1u64), BigUint::from(moduli[3])),
+            (&t, BigUint::from(65537u64), q.modulus().clone()),
+        ] {
+            let (n_int, d_int) = (BigInt::from(n.clone()), BigInt::from(d.clone()));
+            let scalers = ROUNDINGS.map(|rounding| unsafe {
+                RnsScaler::new_with_rounding_vt(&q, to, ScalingFactor::new(&n, &d), rounding)
+            });
+
+            for _ in 0..ntests {
+                // Random values, and values x such that x * n / d is close to an
+                // integer or to a half-integer, and of both signs. The values too
+                // close to a half-integer may be rounded either way to the nearest
+                // integer.
+                let random = BigInt::from(q.lift(ArrayView1::from(&[
+                    rng.next_u64() % moduli[0],
+                    rng.next_u64() % moduli[1],
+                    rng.next_u64() % moduli[2],
+                    rng.next_u64() % moduli[3],
+                ]))) - &half_q;
+                let k = BigInt::from(rng.next_u64() % 65537);
+                let mut values = vec![(random, true)];
+                for (target, check_nearest) in [
+                    (&k * &d_int / &n_int, true),
+                    ((2 * &k + 1) * &d_int / (2 * &n_int), false),
+                ] {
+                    for delta in -2i64..=2 {
+                        values.push((&target + delta, check_nearest));
+                        values.push((-(&target + delta), check_nearest));
+                    }
+                }
+
+                for (x_lift, check_nearest) in values {
+                    if x_lift >= half_q || x_lift < -&half_q {
+                        continue;
+                    }
+                    let x = project_centered(&q, &x_lift);
+                    for (scaler, rounding) in scalers.iter().zip(ROUNDINGS) {
+                        if rounding == Rounding::Nearest && !check_nearest {
+                            continue;
                         }
-                    } else {
-                        &(&x_lift * &n + (&d >> 1)) / &d
-                    };
-                    assert_eq!(y, r.project(&x_scaled_round));
+                        let y = scaler.scale_new((&x).into(), to.moduli.len());
+                        let expected = scale_reference(&x_lift, &n, &d, rounding);
+                        assert_eq!(y, project_centered(to, &expected));
+                    }
                 }
             }
         }
@@ -0,0 +1,186 @@
+#![warn(missing_docs, unused_imports)]
+
+//! Precomputed automorphisms of polynomials.
+
+use super::{Context, Poly, Representation, SubstitutionExponent};
+use crate::{Error, Result};
+use itertools::izip;
+use ndarray::Array2;
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+/// The index maps of the substitution x -> x^exponent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Automorphism {
+    /// For each coefficient in Ntt representation, the index of the input
+    /// coefficient it is copied from.
+    ntt_indices: Box<[usize]>,
+    /// For each coefficient in PowerBasis representation, the index of the
+    /// output coefficient it is moved to.
+    power_basis_indices: Box<[usize]>,
+    /// For each coefficient in PowerBasis representation, whether it is
+    /// negated when moved.
+    power_basis_signs: Box<[bool]>,
+}
+
+/// Plan for applying a fixed set of automorphisms x -> x^exponent to
+/// polynomials of a context, where the index permutations are computed once
+/// when the plan is created instead of at each substitution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutomorphismPlan {
+    ctx: Arc<Context>,
+    automorphisms: HashMap<usize, Automorphism>,
+}
+
+impl AutomorphismPlan {
+    /// Create a plan for the substitutions x -> x^exponent of polynomials in
+    /// the context `ctx`, for all the exponents in `exponents`.
+    ///
+    /// Returns an error if an exponent is even modulo 2 * degree.
+    pub fn new(ctx: &Arc<Context>, exponents: &[usize]) -> Result<Self> {
+        let mut automorphisms = HashMap::with_capacity(exponents.len());
+        for exponent in exponents {
+            let element = SubstitutionExponent::new(ctx, *exponent)?;
+
+            let mut ntt_indices = vec![0; ctx.degree];
+            izip!(ctx.bitrev.iter(), element.power_bitrev.iter())
+                .for_each(|(j, k)| ntt_indices[*j] = *k);
+
+            let mask = ctx.degree - 1;
+            let (power_basis_indices, power_basis_signs) = (0..ctx.degree)
+                .map(|j| {
+                    let power = j * element.exponent;
+                    (power & mask, power & ctx.degree != 0)
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+
+            automorphisms.insert(
+                element.exponent,
+                Automorphism {
+                    ntt_indices: ntt_indices.into_boxed_slice(),
+                    power_basis_indices: power_basis_indices.into_boxed_slice(),
+                    power_basis_signs: power_basis_signs.into_boxed_slice(),
+                },
+            );
+        }
+
+        Ok(Self {
+            ctx: ctx.clone(),
+            automorphisms,
+        })
+    }
+
+    /// Apply the substitution x -> x^exponent to a polynomial; this is
+    /// equivalent to [`Poly::substitute`].
+    ///
+    /// Returns an error if the polynomial is not in the context of the plan,
+    /// or if the exponent modulo 2 * degree is not one of the exponents of the
+    /// plan.
+    pub fn apply(&self, p: &Poly, exponent: usize) -> Result<Poly> {
+        if p.ctx.as_ref() != self.ctx.as_ref() {
+            return Err(Error::InvalidContext);
+        }
+        let automorphism = self
+            .automorphisms
+            .get(&(exponent % (2 * self.ctx.degree)))
+            .ok_or_else(|| {
+                Error::Default(format!("The exponent {exponent} is not part of the plan"))
+            })?;
+
+        let mut q = Poly::zero_like(p);
+        if p.allow_variable_time_computations {
+            unsafe { q.allow_variable_time_computations() }
+        }
+        match p.representation {
+            Representation::Ntt | Representation::NttShoup => {
+                izip!(q.coefficients.outer_iter_mut(), p.coefficients.outer_iter()).for_each(
+                    |(mut q_row, p_row)| {
+                        izip!(q_row.iter_mut(), automorphism.ntt_indices.iter())
+                            .for_each(|(qj, k)| *qj = p_row[*k])
+                    },
+                );
+                if let Some(p_shoup) = p.coefficients_shoup.get() {
+                    let mut q_shoup = Array2::zeros(p_shoup.dim());
+                    izip!(q_shoup.outer_iter_mut(), p_shoup.outer_iter()).for_each(
+                        |(mut q_row, p_row)| {
+                            izip!(q_row.iter_mut(), automorphism.ntt_indices.iter())
+                                .for_each(|(qj, k)| *qj = p_row[*k])
+                        },
+                    );
+                    q.coefficients_shoup = OnceLock::from(q_shoup);
+                }
+            }
+            Representation::PowerBasis => {
+                izip!(
+                    q.coefficients.outer_iter_mut(),
+                    p.coefficients.outer_iter(),
+                    self.ctx.q.iter()
+                )
+                .for_each(|(mut q_row, p_row, qi)| {
+                    izip!(
+                        p_row.iter(),
+                        automorphism.power_basis_indices.iter(),
+                        automorphism.power_basis_signs.iter()
+                    )
+                    .for_each(|(pj, k, is_negated)| {
+                        q_row[*k] = if *is_negated { qi.neg(*pj) } else { *pj }
+                    })
+                });
+            }
+        }
+
+        Ok(q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AutomorphismPlan;
+    use crate::rq::{Context, Poly, Representation, SubstitutionExponent};
+    use rand::thread_rng;
+    use std::{error::Error, sync::Arc};
+
+    static MODULI: &[u64; 3] = &[1153, 4611686018326724609, 4611686018309947393];
+
+    #[test]
+    fn apply() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for degree in [16, 32] {
+            let ctx = Arc::new(Context::new(MODULI, degree)?);
+            let exponents = [1, 3, 5, 2 * degree - 1, 2 * degree + 3];
+            let plan = AutomorphismPlan::new(&ctx, &exponents)?;
+
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let p = Poly::random(&ctx, representation.clone(), &mut rng);
+                for k in exponents {
+                    let expected = p.substitute(&SubstitutionExponent::new(&ctx, k)?)?;
+                    assert_eq!(plan.apply(&p, k)?, expected);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_errors() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        assert!(AutomorphismPlan::new(&ctx, &[3, 4]).is_err());
+
+        let plan = AutomorphismPlan::new(&ctx, &[3])?;
+        let p = Poly::zero(&ctx, Representation::Ntt);
+        assert!(plan.apply(&p, 5).is_err());
+
+        let other_ctx = Arc::new(Context::new(&MODULI[..1], 16)?);
+        let p = Poly::zero(&other_ctx, Representation::Ntt);
+        assert_eq!(plan.apply(&p, 3), Err(crate::Error::InvalidContext));
+
+        Ok(())
+    }
+}
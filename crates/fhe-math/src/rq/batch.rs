@@ -0,0 +1,181 @@
+//! Changes of representation of many polynomials at once.
+
+use super::{Poly, Representation};
+use crate::{Error, Result};
+use std::sync::Arc;
+
+/// Change the representation of all the polynomials to `to`; this is
+/// equivalent to calling [`Poly::change_representation`] on each polynomial.
+///
+/// The NTTs are grouped by modulus: the rows of all the polynomials for one
+/// modulus are transformed before moving to the next modulus, so that the
+/// tables of an NTT operator are reused while they are in cache. When the
+/// `rayon` feature is enabled and the polynomials have at least
+/// [`parallel_threshold`](super::parallel_threshold) coefficients per modulus
+/// in total, the polynomials are transformed in parallel for each modulus.
+///
+/// Returns an error if the polynomials are not all in the same context, or if
+/// a polynomial has lazy coefficients and is not already in representation
+/// `to`; in both cases, no polynomial is modified.
+pub fn change_representation_batch(polys: &mut [Poly], to: Representation) -> Result<()> {
+    let Some(first) = polys.first() else {
+        return Ok(());
+    };
+    let ctx = first.ctx.clone();
+    for p in polys.iter() {
+        if !Arc::ptr_eq(&p.ctx, &ctx) && p.ctx != ctx {
+            return Err(Error::InvalidContext);
+        }
+        if p.has_lazy_coefficients && p.representation != to {
+            return Err(Error::InvalidTransition(p.representation.clone(), to));
+        }
+    }
+
+    polys
+        .iter_mut()
+        .filter(|p| p.representation == Representation::NttShoup && to != Representation::NttShoup)
+        .for_each(|p| p.drop_shoup());
+
+    // A polynomial is transformed if exactly one of its representation and
+    // `to` is PowerBasis, forward if `to` is not.
+    let forward = to != Representation::PowerBasis;
+    let transform = |i: usize, p: &mut Poly| {
+        if (p.representation != Representation::PowerBasis) == forward {
+            return;
+        }
+        let op = &p.ctx.ops[i];
+        let mut v = p.coefficients.row_mut(i);
+        match (forward, p.allow_variable_time_computations) {
+            (true, true) => unsafe { op.forward_vt(v.as_mut_ptr()) },
+            (true, false) => op.forward(v.as_slice_mut().unwrap()),
+            (false, true) => unsafe { op.backward_vt(v.as_mut_ptr()) },
+            (false, false) => op.backward(v.as_slice_mut().unwrap()),
+        }
+    };
+
+    for i in 0..ctx.q.len() {
+        #[cfg(feature = "rayon")]
+        if polys.len() * ctx.degree >= super::parallel_threshold() {
+            use rayon::prelude::*;
+            polys.par_iter_mut().for_each(|p| transform(i, p));
+            continue;
+        }
+
+        polys.iter_mut().for_each(|p| transform(i, p));
+    }
+
+    polys.iter_mut().for_each(|p| {
+        p.representation = to.clone();
+        if to == Representation::NttShoup && p.coefficients_shoup.get().is_none() {
+            p.compute_coefficients_shoup()
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::change_representation_batch;
+    use crate::{
+        rq::{Context, Poly, Representation},
+        Error,
+    };
+    use rand::thread_rng;
+    use std::{error::Error as StdError, sync::Arc};
+
+    static MODULI: &[u64; 3] = &[1153, 4611686018326724609, 4611686018309947393];
+
+    #[test]
+    fn matches_change_representation() -> Result<(), Box<dyn StdError>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let representations = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ];
+
+        // The polynomials may start in different representations, and some
+        // allow variable time computations.
+        let polys = (0..12)
+            .map(|i| {
+                let mut p = Poly::random(&ctx, representations[i % 3].clone(), &mut rng);
+                if i % 2 == 0 {
+                    unsafe { p.allow_variable_time_computations() }
+                }
+                p
+            })
+            .collect::<Vec<_>>();
+
+        for to in representations {
+            let mut batch = polys.clone();
+            change_representation_batch(&mut batch, to.clone())?;
+            for (p, q) in polys.iter().zip(batch.iter()) {
+                let mut expected = p.clone();
+                expected.change_representation(to.clone());
+                assert_eq!(q, &expected);
+                assert_eq!(q.allows_variable_time(), p.allows_variable_time());
+                assert_eq!(
+                    q.coefficients_shoup.get().is_some(),
+                    to == Representation::NttShoup
+                );
+            }
+        }
+
+        change_representation_batch(&mut [], Representation::Ntt)?;
+
+        // The polynomials transformed in parallel are identical.
+        #[cfg(feature = "rayon")]
+        {
+            use crate::rq::{set_parallel_threshold, DEFAULT_PARALLEL_THRESHOLD};
+            let mut sequential = polys.clone();
+            let mut parallel = polys.clone();
+            set_parallel_threshold(usize::MAX);
+            change_representation_batch(&mut sequential, Representation::Ntt)?;
+            set_parallel_threshold(0);
+            change_representation_batch(&mut parallel, Representation::Ntt)?;
+            set_parallel_threshold(DEFAULT_PARALLEL_THRESHOLD);
+            assert_eq!(sequential, parallel);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<(), Box<dyn StdError>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let other_ctx = Arc::new(Context::new(&MODULI[..2], 16)?);
+
+        let mut polys = vec![
+            Poly::random(&ctx, Representation::PowerBasis, &mut rng),
+            Poly::random(&other_ctx, Representation::PowerBasis, &mut rng),
+        ];
+        let expected = polys.clone();
+        assert_eq!(
+            change_representation_batch(&mut polys, Representation::Ntt),
+            Err(Error::InvalidContext)
+        );
+        assert_eq!(polys, expected);
+
+        let coefficients = vec![1u64; 16];
+        let mut polys = vec![Poly::random(&ctx, Representation::Ntt, &mut rng), unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &coefficients,
+                &ctx,
+            )
+        }];
+        let expected = polys.clone();
+        assert_eq!(
+            change_representation_batch(&mut polys, Representation::PowerBasis),
+            Err(Error::InvalidTransition(
+                Representation::Ntt,
+                Representation::PowerBasis
+            ))
+        );
+        assert_eq!(polys, expected);
+        change_representation_batch(&mut polys, Representation::Ntt)?;
+
+        Ok(())
+    }
+}
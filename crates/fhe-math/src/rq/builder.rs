@@ -0,0 +1,295 @@
+//! Builder for polynomials sampled from a distribution.
+
+use super::{traits::TryConvertFrom, Context, Poly, Representation, SensitivePoly};
+use crate::{Error, Result};
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// Distribution of the coefficients of a polynomial.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+enum Distribution {
+    /// Uniform modulo the modulus of the context.
+    #[default]
+    Uniform,
+    /// Centered binomial with the given variance.
+    Cbd(usize),
+    /// In {-1, 0, 1}, with exactly the given number of nonzero coefficients.
+    Ternary(usize),
+}
+
+/// Builder for a [`Poly`] sampled from a distribution.
+///
+/// By default, the coefficients are sampled uniformly, and the polynomial is
+/// in PowerBasis representation.
+#[derive(Clone)]
+pub struct PolyBuilder {
+    ctx: Arc<Context>,
+    representation: Representation,
+    distribution: Distribution,
+    seed: Option<<ChaCha8Rng as SeedableRng>::Seed>,
+    variable_time: bool,
+}
+
+impl Poly {
+    /// Creates a builder for polynomials in the context `ctx`.
+    pub fn builder(ctx: &Arc<Context>) -> PolyBuilder {
+        PolyBuilder {
+            ctx: ctx.clone(),
+            representation: Representation::PowerBasis,
+            distribution: Distribution::Uniform,
+            seed: None,
+            variable_time: false,
+        }
+    }
+}
+
+impl PolyBuilder {
+    /// Sets the representation of the polynomial.
+    pub fn representation(&mut self, representation: Representation) -> &mut Self {
+        self.representation = representation;
+        self
+    }
+
+    /// Sets the seed from which the polynomial is deterministically sampled;
+    /// the polynomial must then be built using `build_seeded`.
+    pub fn seed(&mut self, seed: <ChaCha8Rng as SeedableRng>::Seed) -> &mut Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Samples the coefficients uniformly, as in [`Poly::random`].
+    pub fn uniform(&mut self) -> &mut Self {
+        self.distribution = Distribution::Uniform;
+        self
+    }
+
+    /// Samples the coefficients from a centered binomial distribution of
+    /// variance `variance`, as in [`Poly::small`].
+    pub fn cbd(&mut self, variance: usize) -> &mut Self {
+        self.distribution = Distribution::Cbd(variance);
+        self
+    }
+
+    /// Samples the coefficients in {-1, 0, 1}, with exactly `hamming_weight`
+    /// nonzero coefficients.
+    pub fn ternary(&mut self, hamming_weight: usize) -> &mut Self {
+        self.distribution = Distribution::Ternary(hamming_weight);
+        self
+    }
+
+    /// Allows variable time computations on the polynomial.
+    ///
+    /// # Safety
+    ///
+    /// As for [`Poly::allow_variable_time_computations`], this should only be
+    /// used when the polynomial holds public data.
+    pub unsafe fn allow_variable_time(&mut self) -> &mut Self {
+        self.variable_time = true;
+        self
+    }
+
+    /// Build a new polynomial using the random number generator `rng`.
+    ///
+    /// The polynomial is returned as a [`SensitivePoly`], which is zeroized
+    /// when dropped. Returns an error if a seed was set, since the polynomial
+    /// would not be derived from it, or if the parameters of the distribution
+    /// are invalid: the variance of the centered binomial distribution must
+    /// belong to [1, ..., 16], and the Hamming weight of a ternary polynomial
+    /// must belong to [1, ..., degree].
+    pub fn build<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<SensitivePoly> {
+        if self.seed.is_some() {
+            return Err(Error::Sampling(
+                "A seed was set, the polynomial must be built using `build_seeded`".to_string(),
+            ));
+        }
+        self.sample(rng)
+    }
+
+    /// Build a new polynomial deterministically from the seed.
+    ///
+    /// The random number generator is derived from the seed as in
+    /// [`Poly::random_from_seed`], so that a uniform polynomial is the same as
+    /// the one of `random_from_seed`. Returns an error if no seed was set, or
+    /// if the parameters of the distribution are invalid, as in `build`.
+    pub fn build_seeded(&self) -> Result<SensitivePoly> {
+        let seed = self.seed.ok_or_else(|| {
+            Error::Sampling(
+                "No seed was set, the polynomial must be built using `build`".to_string(),
+            )
+        })?;
+        self.sample(&mut Poly::seeded_rng(seed))
+    }
+
+    fn sample<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<SensitivePoly> {
+        let mut p = match self.distribution {
+            Distribution::Uniform => {
+                Poly::random(&self.ctx, self.representation.clone(), rng).into_sensitive()
+            }
+            Distribution::Cbd(variance) => {
+                Poly::small(&self.ctx, self.representation.clone(), variance, rng)?
+            }
+            Distribution::Ternary(hamming_weight) => self.sample_ternary(hamming_weight, rng)?,
+        };
+        if self.variable_time {
+            unsafe { p.allow_variable_time_computations() }
+        }
+        Ok(p)
+    }
+
+    fn sample_ternary<R: RngCore + CryptoRng>(
+        &self,
+        hamming_weight: usize,
+        rng: &mut R,
+    ) -> Result<SensitivePoly> {
+        let degree = self.ctx.degree;
+        if !(1..=degree).contains(&hamming_weight) {
+            return Err(Error::Sampling(format!(
+                "The Hamming weight should be an integer between 1 and {degree}"
+            )));
+        }
+
+        // Select the positions of the nonzero coefficients with a partial
+        // Fisher-Yates shuffle, and a random sign for each of them.
+        let mut positions = Zeroizing::new((0..degree).collect::<Vec<_>>());
+        let mut coeffs = Zeroizing::new(vec![0i64; degree]);
+        for i in 0..hamming_weight {
+            let j = rng.gen_range(i..degree);
+            positions.swap(i, j);
+            coeffs[positions[i]] = if rng.next_u32() & 1 == 1 { 1 } else { -1 };
+        }
+
+        let mut p = Poly::try_convert_from(
+            coeffs.as_ref() as &[i64],
+            &self.ctx,
+            false,
+            Representation::PowerBasis,
+        )?
+        .into_sensitive();
+        if self.representation != Representation::PowerBasis {
+            p.change_representation(self.representation.clone());
+        }
+        Ok(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rq::{Context, Poly, Representation},
+        zq::Modulus,
+    };
+    use rand::thread_rng;
+    use std::{error::Error, sync::Arc};
+
+    const MODULI: &[u64; 3] = &[1153, 4611686018326724609, 4611686018309947393];
+
+    #[test]
+    fn distributions() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let q = Modulus::new(MODULI[0])?;
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::builder(&ctx)
+                .representation(representation.clone())
+                .build(&mut rng)?;
+            assert_eq!(p.representation(), &representation);
+            assert!(!p.allows_variable_time());
+
+            let p = unsafe {
+                Poly::builder(&ctx)
+                    .representation(representation.clone())
+                    .cbd(4)
+                    .allow_variable_time()
+                    .build(&mut rng)?
+            };
+            assert_eq!(p.representation(), &representation);
+            assert!(p.allows_variable_time());
+        }
+
+        // Uniform coefficients are in range, and not all equal.
+        let p = Poly::builder(&ctx).uniform().build(&mut rng)?;
+        for (row, qi) in p.coefficients().outer_iter().zip(MODULI) {
+            assert!(row.iter().all(|c| c < qi));
+        }
+        assert_ne!(p.as_ref(), &Poly::zero(&ctx, Representation::PowerBasis));
+
+        // Centered binomial coefficients are bounded by twice the variance.
+        for variance in 1..=16 {
+            let p = Poly::builder(&ctx).cbd(variance).build(&mut rng)?;
+            let v = unsafe { q.center_vec_vt(p.coefficients().row(0).as_slice().unwrap()) };
+            assert!(v.iter().all(|vi| vi.abs() <= 2 * variance as i64));
+        }
+
+        // Ternary polynomials have exactly the requested Hamming weight.
+        for hamming_weight in [1, 5, 16] {
+            let p = Poly::builder(&ctx)
+                .ternary(hamming_weight)
+                .build(&mut rng)?;
+            let v = unsafe { q.center_vec_vt(p.coefficients().row(0).as_slice().unwrap()) };
+            assert!(v.iter().all(|vi| vi.abs() <= 1));
+            assert_eq!(v.iter().filter(|vi| **vi != 0).count(), hamming_weight);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn seeded() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let seed = [7u8; 32];
+
+        // A uniform polynomial is the same as the one of `random_from_seed`.
+        let p = Poly::builder(&ctx)
+            .representation(Representation::Ntt)
+            .seed(seed)
+            .build_seeded()?;
+        assert_eq!(
+            p.as_ref(),
+            &Poly::random_from_seed(&ctx, Representation::Ntt, seed)
+        );
+
+        // All the distributions are deterministic given the seed.
+        let mut builder = Poly::builder(&ctx);
+        builder.seed(seed);
+        for builder in [builder.clone().cbd(3), builder.clone().ternary(8)] {
+            assert_eq!(builder.build_seeded()?, builder.build_seeded()?);
+        }
+        let other = Poly::builder(&ctx)
+            .seed([8u8; 32])
+            .ternary(8)
+            .build_seeded()?;
+        assert_ne!(other, builder.ternary(8).build_seeded()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        // A seed cannot be combined with an external random number generator.
+        assert!(Poly::builder(&ctx).seed([0u8; 32]).build(&mut rng).is_err());
+        assert!(Poly::builder(&ctx).build_seeded().is_err());
+
+        // The parameters of the distributions are validated.
+        assert!(Poly::builder(&ctx).cbd(0).build(&mut rng).is_err());
+        assert!(Poly::builder(&ctx).cbd(17).build(&mut rng).is_err());
+        assert!(Poly::builder(&ctx).ternary(0).build(&mut rng).is_err());
+        assert!(Poly::builder(&ctx).ternary(17).build(&mut rng).is_err());
+        assert!(Poly::builder(&ctx)
+            .seed([0u8; 32])
+            .ternary(17)
+            .build_seeded()
+            .is_err());
+
+        Ok(())
+    }
+}
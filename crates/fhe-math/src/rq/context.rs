@@ -1,23 +1,42 @@
-use itertools::Itertools;
+use itertools::{izip, Itertools};
 use num_bigint::BigUint;
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::Debug,
+    sync::{Arc, OnceLock},
+};
 
-use crate::{ntt::NttOperator, rns::RnsContext, zq::Modulus, Error, Result};
+use crate::{
+    ntt::{NttOperator, NttVariant},
+    rns::RnsContext,
+    zq::{primes::generate_ntt_primes, Modulus},
+    Error, Result,
+};
 
 /// Struct that holds the context associated with elements in rq.
-#[derive(Default, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Context {
     pub(crate) moduli: Box<[u64]>,
     pub(crate) q: Box<[Modulus]>,
     pub(crate) rns: Arc<RnsContext>,
-    pub(crate) ops: Box<[NttOperator]>,
+    pub(crate) ops: Box<[Arc<NttOperator>]>,
     pub(crate) degree: usize,
     pub(crate) bitrev: Box<[usize]>,
     pub(crate) inv_last_qi_mod_qj: Box<[u64]>,
     pub(crate) inv_last_qi_mod_qj_shoup: Box<[u64]>,
-    pub(crate) next_context: Option<Arc<Context>>,
+    /// The context with the last modulus dropped, built on first use.
+    #[serde(skip)]
+    next_context: OnceLock<Option<Arc<Context>>>,
 }
 
+impl PartialEq for Context {
+    fn eq(&self, other: &Self) -> bool {
+        // All the other fields are determined by the moduli and the degree.
+        self.moduli == other.moduli && self.degree == other.degree
+    }
+}
+
+impl Eq for Context {}
+
 impl Debug for Context {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Context")
@@ -29,7 +48,7 @@ impl Debug for Context {
             // .field("bitrev", &self.bitrev)
             // .field("inv_last_qi_mod_qj", &self.inv_last_qi_mod_qj)
             // .field("inv_last_qi_mod_qj_shoup", &self.inv_last_qi_mod_qj_shoup)
-            .field("next_context", &self.next_context)
+            .field("next_context", &self.next_context.get())
             .finish()
     }
 }
@@ -37,59 +56,90 @@ impl Debug for Context {
 impl Context {
     /// Creates a context from a list of moduli and a polynomial degree.
     ///
-    /// Returns an error if the moduli are not primes less than 62 bits which
-    /// supports the NTT of size `degree`.
+    /// Returns an error if the moduli are not distinct primes less than 62 bits
+    /// which supports the NTT of size `degree`.
     pub fn new(moduli: &[u64], degree: usize) -> Result<Self> {
         if !degree.is_power_of_two() || degree < 8 {
-            Err(Error::Default(
-                "The degree is not a power of two larger or equal to 8".to_string(),
-            ))
+            Err(Error::InvalidDegree(degree))
+        } else if !moduli.iter().all_unique() {
+            Err(Error::Default("Moduli must be distinct".to_string()))
         } else {
             let mut q = Vec::with_capacity(moduli.len());
-            let rns = Arc::new(RnsContext::new(moduli)?);
             let mut ops = Vec::with_capacity(moduli.len());
             for modulus in moduli {
                 let qi = Modulus::new(*modulus)?;
                 if let Some(op) = NttOperator::new(&qi, degree) {
                     q.push(qi);
-                    ops.push(op);
+                    ops.push(Arc::new(op));
                 } else {
-                    return Err(Error::Default(
-                        "Impossible to construct a Ntt operator".to_string(),
-                    ));
+                    return Err(Error::UnsupportedNtt {
+                        modulus: *modulus,
+                        degree,
+                    });
                 }
             }
-            let bitrev = (0..degree)
-                .map(|j| j.reverse_bits() >> (degree.leading_zeros() + 1))
-                .collect_vec();
+            Self::new_with_operators(moduli, &q, &ops, degree)
+        }
+    }
 
-            let mut inv_last_qi_mod_qj = vec![];
-            let mut inv_last_qi_mod_qj_shoup = vec![];
-            let q_last = moduli.last().unwrap();
-            for qi in &q[..q.len() - 1] {
-                let inv = qi.inv(qi.reduce(*q_last)).unwrap();
-                inv_last_qi_mod_qj.push(inv);
-                inv_last_qi_mod_qj_shoup.push(qi.shoup(inv));
-            }
+    /// Creates a context from the moduli and their NTT operators. The child
+    /// contexts are only built when they are first used, and share the NTT
+    /// operators of their parent instead of computing the same tables again.
+    fn new_with_operators(
+        moduli: &[u64],
+        q: &[Modulus],
+        ops: &[Arc<NttOperator>],
+        degree: usize,
+    ) -> Result<Self> {
+        let rns = Arc::new(RnsContext::new(moduli)?);
+        let bitrev = (0..degree)
+            .map(|j| j.reverse_bits() >> (degree.leading_zeros() + 1))
+            .collect_vec();
 
-            let next_context = if moduli.len() >= 2 {
-                Some(Arc::new(Context::new(&moduli[..moduli.len() - 1], degree)?))
-            } else {
-                None
-            };
-
-            Ok(Self {
-                moduli: moduli.to_owned().into_boxed_slice(),
-                q: q.into_boxed_slice(),
-                rns,
-                ops: ops.into_boxed_slice(),
-                degree,
-                bitrev: bitrev.into_boxed_slice(),
-                inv_last_qi_mod_qj: inv_last_qi_mod_qj.into_boxed_slice(),
-                inv_last_qi_mod_qj_shoup: inv_last_qi_mod_qj_shoup.into_boxed_slice(),
-                next_context,
-            })
+        let mut inv_last_qi_mod_qj = vec![];
+        let mut inv_last_qi_mod_qj_shoup = vec![];
+        let q_last = moduli.last().unwrap();
+        for qi in &q[..q.len() - 1] {
+            let inv = qi.inv(qi.reduce(*q_last)).unwrap();
+            inv_last_qi_mod_qj.push(inv);
+            inv_last_qi_mod_qj_shoup.push(qi.shoup(inv));
         }
+
+        Ok(Self {
+            moduli: moduli.to_owned().into_boxed_slice(),
+            q: q.to_owned().into_boxed_slice(),
+            rns,
+            ops: ops.to_owned().into_boxed_slice(),
+            degree,
+            bitrev: bitrev.into_boxed_slice(),
+            inv_last_qi_mod_qj: inv_last_qi_mod_qj.into_boxed_slice(),
+            inv_last_qi_mod_qj_shoup: inv_last_qi_mod_qj_shoup.into_boxed_slice(),
+            next_context: OnceLock::new(),
+        })
+    }
+
+    /// Returns the context with the last modulus dropped, or None if this
+    /// context has at most one modulus. The context is built on the first
+    /// call.
+    pub(crate) fn next_context(&self) -> Option<&Arc<Context>> {
+        self.next_context
+            .get_or_init(|| {
+                let n = self.moduli.len().saturating_sub(1);
+                (n >= 1).then(|| {
+                    // The moduli are a prefix of the valid moduli of this
+                    // context, so building the context cannot fail.
+                    Arc::new(
+                        Context::new_with_operators(
+                            &self.moduli[..n],
+                            &self.q[..n],
+                            &self.ops[..n],
+                            self.degree,
+                        )
+                        .unwrap(),
+                    )
+                })
+            })
+            .as_ref()
     }
 
     /// Creates a context in an `Arc`.
@@ -102,16 +152,112 @@ impl Context {
         self.rns.modulus()
     }
 
+    /// Returns the number of bits of the modulus.
+    pub fn modulus_bits(&self) -> usize {
+        self.rns.modulus().bits() as usize
+    }
+
+    /// Returns the number of bits of each of the moduli in this context.
+    pub fn moduli_bits(&self) -> Vec<usize> {
+        self.moduli
+            .iter()
+            .map(|qi| (u64::BITS - qi.leading_zeros()) as usize)
+            .collect()
+    }
+
     /// Returns a reference to the moduli in this context.
     pub fn moduli(&self) -> &[u64] {
         &self.moduli
     }
 
+    /// Returns the degree of the polynomials in this context.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the number of moduli in this context.
+    pub fn moduli_count(&self) -> usize {
+        self.moduli.len()
+    }
+
     /// Returns a reference to the moduli as Modulus in this context.
     pub fn moduli_operators(&self) -> &[Modulus] {
         &self.q
     }
 
+    /// Returns whether `t` is coprime to all the moduli of this context.
+    ///
+    /// Since the moduli are primes, this is the case if and only if none of
+    /// the moduli divides `t`.
+    pub fn is_coprime_to_moduli(&self, t: u64) -> bool {
+        self.moduli.iter().all(|qi| t % qi != 0)
+    }
+
+    /// Returns a trivial upper bound on the number of bits of a plaintext
+    /// modulus that can be used with this context.
+    ///
+    /// A plaintext modulus must be strictly smaller than the modulus q of the
+    /// context, so this is one less than the number of bits of q. The noise is
+    /// not accounted for: decryption also requires the noise to stay below
+    /// q / (2t) for a plaintext modulus t, so the usable plaintext moduli are
+    /// smaller, depending on the error distribution and on the computation.
+    pub fn plaintext_bits_upper_bound(&self) -> usize {
+        (self.modulus().bits() as usize).saturating_sub(1)
+    }
+
+    /// Returns the number of leading moduli shared by this context and `other`,
+    /// or 0 if the contexts have different degrees.
+    pub fn common_prefix_len(&self, other: &Context) -> usize {
+        if self.degree != other.degree {
+            0
+        } else {
+            izip!(self.moduli.iter(), other.moduli.iter())
+                .take_while(|(a, b)| a == b)
+                .count()
+        }
+    }
+
+    /// Returns a context with the same degree and without the moduli at the
+    /// provided indices; the remaining moduli are kept in their original
+    /// order, and their NTT operators are shared with this context.
+    ///
+    /// Unless only trailing moduli are dropped, the resulting context is not
+    /// on the chain of child contexts of this context, so that
+    /// [`Context::niterations_to`] returns [`Error::InvalidContext`] for it.
+    ///
+    /// Returns an error if an index is out of range or repeated, or if all
+    /// the moduli are dropped.
+    pub fn without_moduli(&self, indices: &[usize]) -> Result<Arc<Context>> {
+        if let Some(i) = indices.iter().find(|i| **i >= self.moduli.len()) {
+            return Err(Error::InvalidModulusIndex {
+                index: *i,
+                len: self.moduli.len(),
+            });
+        }
+        if let Some(i) = indices.iter().duplicates().next() {
+            return Err(Error::DuplicateModulusIndex(*i));
+        }
+        if indices.len() == self.moduli.len() {
+            return Err(Error::InvalidNumberOfModuli {
+                got: 0,
+                max: self.moduli.len(),
+            });
+        }
+
+        let kept = (0..self.moduli.len())
+            .filter(|i| !indices.contains(i))
+            .collect_vec();
+        let moduli = kept.iter().map(|i| self.moduli[*i]).collect_vec();
+        let q = kept.iter().map(|i| self.q[*i].clone()).collect_vec();
+        let ops = kept.iter().map(|i| self.ops[*i].clone()).collect_vec();
+        Ok(Arc::new(Context::new_with_operators(
+            &moduli,
+            &q,
+            &ops,
+            self.degree,
+        )?))
+    }
+
     /// Returns the number of iterations to switch to a children context.
     /// Returns an error if the context provided is not a child context.
     pub fn niterations_to(&self, context: &Arc<Context>) -> Result<usize> {
@@ -119,37 +265,129 @@ impl Context {
             return Ok(0);
         }
 
+        // Walk the chain of children of `self`, so that the contexts built on
+        // the way are cached and reused by the next calls.
         let mut niterations = 0;
-        let mut found = false;
-        let mut current_ctx = Arc::new(self.clone());
-        while current_ctx.next_context.is_some() {
+        let mut current_ctx = self.next_context();
+        while let Some(ctx) = current_ctx {
             niterations += 1;
-            current_ctx = current_ctx.next_context.as_ref().unwrap().clone();
-            if &current_ctx == context {
-                found = true;
-                break;
+            if ctx == context {
+                return Ok(niterations);
             }
+            current_ctx = ctx.next_context();
         }
-        if found {
-            Ok(niterations)
-        } else {
-            Err(Error::InvalidContext)
-        }
+        Err(Error::InvalidContext)
     }
 
     /// Returns the context after `i` iterations.
     pub fn context_at_level(&self, i: usize) -> Result<Arc<Self>> {
         if i >= self.moduli.len() {
-            Err(Error::Default(
-                "No context at the specified level".to_string(),
-            ))
+            Err(Error::NoMoreContext)
+        } else if i == 0 {
+            Ok(Arc::new(self.clone()))
         } else {
-            let mut current_ctx = Arc::new(self.clone());
-            for _ in 0..i {
-                current_ctx = current_ctx.next_context.as_ref().unwrap().clone();
+            // As in `niterations_to`, the children of `self` are cached.
+            let mut current_ctx = self.next_context().unwrap();
+            for _ in 1..i {
+                current_ctx = current_ctx.next_context().unwrap();
+            }
+            Ok(current_ctx.clone())
+        }
+    }
+}
+
+/// Builder for a [`Context`].
+#[derive(Debug, Default, Clone)]
+pub struct ContextBuilder {
+    degree: usize,
+    moduli: Vec<u64>,
+    moduli_sizes: Vec<usize>,
+    variant: NttVariant,
+}
+
+impl ContextBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the polynomial degree.
+    pub fn degree(&mut self, degree: usize) -> &mut Self {
+        self.degree = degree;
+        self
+    }
+
+    /// Sets the moduli to use.
+    /// Only one of `moduli` and `generate_moduli` can be specified.
+    pub fn moduli(&mut self, moduli: &[u64]) -> &mut Self {
+        moduli.clone_into(&mut self.moduli);
+        self
+    }
+
+    /// Sets the sizes of the moduli to generate.
+    /// Only one of `moduli` and `generate_moduli` can be specified.
+    pub fn generate_moduli(&mut self, sizes: &[usize]) -> &mut Self {
+        sizes.clone_into(&mut self.moduli_sizes);
+        self
+    }
+
+    /// Sets the kind of convolution, i.e., the polynomial ring. Only the
+    /// negacyclic convolution of R_q = ZZ_q\[x\]/(x^n + 1) is supported.
+    pub fn convolution(&mut self, variant: NttVariant) -> &mut Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Build a new `Context` inside an `Arc`.
+    ///
+    /// Returns an error if the degree is not a power of two larger or equal to
+    /// 8, if not exactly one of `moduli` and `generate_moduli` was specified,
+    /// if not enough primes of the specified sizes exist, if the convolution
+    /// is not negacyclic, or if the moduli are invalid for [`Context::new`].
+    pub fn build(&self) -> Result<Arc<Context>> {
+        if !self.degree.is_power_of_two() || self.degree < 8 {
+            return Err(Error::InvalidDegree(self.degree));
+        }
+        if self.variant != NttVariant::Negacyclic {
+            return Err(Error::UnsupportedConvolution(self.variant));
+        }
+
+        let moduli = match (self.moduli.is_empty(), self.moduli_sizes.is_empty()) {
+            (false, false) => return Err(Error::ConflictingModuli),
+            (true, true) => return Err(Error::MissingModuli),
+            (false, true) => self.moduli.clone(),
+            (true, false) => Self::generate(&self.moduli_sizes, self.degree)?,
+        };
+
+        Context::new_arc(&moduli, self.degree)
+    }
+
+    /// Generate distinct moduli with the specified sizes, supporting the NTT
+    /// of size `degree`. The moduli of the same size are generated at once
+    /// with [`generate_ntt_primes`], in descending order.
+    fn generate(sizes: &[usize], degree: usize) -> Result<Vec<u64>> {
+        let mut moduli = vec![0u64; sizes.len()];
+        for (i, size) in sizes.iter().enumerate() {
+            if !(10..=62).contains(size) {
+                return Err(Error::InvalidModulusSize(*size));
             }
-            Ok(current_ctx)
+            if moduli[i] != 0 {
+                // This size was already generated.
+                continue;
+            }
+
+            let positions = (i..sizes.len())
+                .filter(|j| sizes[*j] == *size)
+                .collect_vec();
+            let primes = generate_ntt_primes(*size, degree, positions.len()).ok_or(
+                Error::NotEnoughNttPrimes {
+                    size: *size,
+                    degree,
+                },
+            )?;
+            izip!(positions, primes).for_each(|(j, prime)| moduli[j] = prime);
         }
+        Ok(moduli)
     }
 }
 
@@ -157,8 +395,11 @@ impl Context {
 mod tests {
     use std::{error::Error, sync::Arc};
 
-    use crate::ntt::supports_ntt;
+    use super::ContextBuilder;
+    use crate::ntt::{supports_ntt, NttVariant};
     use crate::rq::Context;
+    use crate::zq::primes::{generate_ntt_primes, generate_prime};
+    use num_bigint::BigUint;
 
     const MODULI: &[u64; 5] = &[
         1153,
@@ -186,6 +427,43 @@ mod tests {
 
         // This should fail since 1153 != 1 moduli 2 * 128
         assert!(Context::new(MODULI, 128).is_err());
+
+        // The moduli must be distinct
+        for modulus in MODULI {
+            assert_eq!(
+                Context::new(&[*modulus, *modulus], 16).unwrap_err(),
+                crate::Error::Default("Moduli must be distinct".to_string())
+            );
+        }
+        assert!(Context::new(&[MODULI[1], MODULI[2], MODULI[1]], 16).is_err());
+        assert!(Context::new(&[MODULI[1], MODULI[2]], 16).is_ok());
+    }
+
+    #[test]
+    fn degree_and_moduli_count() -> Result<(), Box<dyn Error>> {
+        let context = Context::new(MODULI, 16)?;
+        assert_eq!(context.degree(), 16);
+        assert_eq!(context.moduli_count(), MODULI.len());
+
+        let context = Context::new(&MODULI[..1], 8)?;
+        assert_eq!(context.degree(), 8);
+        assert_eq!(context.moduli_count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_ntt() {
+        // 1153 = 1 modulo 2 * 64 but not modulo 2 * 128, so the error reports
+        // the first modulus of the list which does not support the NTT.
+        assert!(Context::new(&MODULI[..1], 64).is_ok());
+        match Context::new(&[MODULI[1], MODULI[0]], 128) {
+            Err(crate::Error::UnsupportedNtt { modulus, degree }) => {
+                assert_eq!(modulus, 1153);
+                assert_eq!(degree, 128);
+            }
+            _ => panic!("Expected an UnsupportedNtt error"),
+        }
     }
 
     #[test]
@@ -193,19 +471,132 @@ mod tests {
         // A context should have a children pointing to a context with one less modulus.
         let context = Arc::new(Context::new(MODULI, 16)?);
         assert_eq!(
-            context.next_context,
+            context.next_context().cloned(),
             Some(Arc::new(Context::new(&MODULI[..MODULI.len() - 1], 16)?))
         );
 
         // We can go down the chain of the MODULI.len() - 1 context's.
         let mut number_of_children = 0;
         let mut current = context;
-        while current.next_context.is_some() {
+        while current.next_context().is_some() {
             number_of_children += 1;
-            current = current.next_context.as_ref().unwrap().clone();
+            current = current.next_context().unwrap().clone();
         }
         assert_eq!(number_of_children, MODULI.len() - 1);
 
+        // The default context has no modulus, hence no children.
+        assert!(Context::default().next_context().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn next_context_shares_operators() -> Result<(), Box<dyn Error>> {
+        let context = Arc::new(Context::new(MODULI, 16)?);
+
+        let mut current = context.clone();
+        while let Some(next) = current.next_context().cloned() {
+            assert_eq!(next.ops.len(), current.ops.len() - 1);
+            for (op, parent_op) in next.ops.iter().zip(context.ops.iter()) {
+                assert!(Arc::ptr_eq(op, parent_op));
+            }
+            current = next;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_next_context() -> Result<(), Box<dyn Error>> {
+        let context = Arc::new(Context::new(MODULI, 16)?);
+        assert!(context.next_context.get().is_none());
+
+        // The lazily built contexts are the same as the ones constructed
+        // directly, including the fields not compared by `PartialEq`.
+        let mut current = context.clone();
+        while let Some(next) = current.next_context().cloned() {
+            assert!(current.next_context.get().is_some());
+            let expected = Context::new(next.moduli(), 16)?;
+            assert_eq!(next.as_ref(), &expected);
+            assert_eq!(next.q, expected.q);
+            assert_eq!(next.rns, expected.rns);
+            assert_eq!(next.bitrev, expected.bitrev);
+            assert_eq!(next.inv_last_qi_mod_qj, expected.inv_last_qi_mod_qj);
+            assert_eq!(
+                next.inv_last_qi_mod_qj_shoup,
+                expected.inv_last_qi_mod_qj_shoup
+            );
+            current = next;
+        }
+        assert_eq!(current.moduli(), &MODULI[..1]);
+
+        // Creating a large context does not build its children.
+        let context = ContextBuilder::new()
+            .degree(16384)
+            .generate_moduli(&[50; 15])
+            .build()?;
+        assert!(context.next_context.get().is_none());
+        assert_eq!(context.context_at_level(14)?.moduli_count(), 1);
+
+        // The children built by `context_at_level` and `niterations_to` are
+        // cached on the context and reused.
+        assert!(context.next_context.get().is_some());
+        let child = context.context_at_level(3)?;
+        assert!(Arc::ptr_eq(&child, &context.context_at_level(3)?));
+        assert_eq!(context.niterations_to(&child)?, 3);
+
+        // Formatting a context does not build its children.
+        let context = Context::new(MODULI, 16)?;
+        assert!(format!("{context:?}").contains("next_context: None"));
+        assert!(context.next_context.get().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn without_moduli() -> Result<(), Box<dyn Error>> {
+        let context = Arc::new(Context::new(MODULI, 16)?);
+
+        // Dropping a middle modulus keeps the other ones in order.
+        let dropped = context.without_moduli(&[2])?;
+        let moduli = [MODULI[0], MODULI[1], MODULI[3], MODULI[4]];
+        assert_eq!(dropped.moduli(), &moduli);
+        assert_eq!(
+            dropped.modulus(),
+            &(context.modulus() / BigUint::from(MODULI[2]))
+        );
+        assert_eq!(dropped, Arc::new(Context::new(&moduli, 16)?));
+        assert!(Arc::ptr_eq(&dropped.ops[2], &context.ops[3]));
+        assert_eq!(
+            context.niterations_to(&dropped),
+            Err(crate::Error::InvalidContext)
+        );
+
+        // The order of the indices does not matter.
+        let dropped = context.without_moduli(&[3, 0])?;
+        assert_eq!(dropped.moduli(), &[MODULI[1], MODULI[2], MODULI[4]]);
+
+        // Dropping trailing moduli gives a context of the chain.
+        let dropped = context.without_moduli(&[3, 4])?;
+        assert_eq!(context.niterations_to(&dropped)?, 2);
+
+        // Dropping no modulus gives the same context.
+        assert_eq!(context.without_moduli(&[])?, context);
+
+        // The indices must be in range, distinct, and leave at least one modulus.
+        assert_eq!(
+            context.without_moduli(&[5]),
+            Err(crate::Error::InvalidModulusIndex { index: 5, len: 5 })
+        );
+        assert_eq!(
+            context.without_moduli(&[1, 1]),
+            Err(crate::Error::DuplicateModulusIndex(1))
+        );
+        assert_eq!(
+            context.without_moduli(&[0, 1, 2, 3, 4]),
+            Err(crate::Error::InvalidNumberOfModuli { got: 0, max: 5 })
+        );
+
         Ok(())
     }
 
@@ -234,4 +625,177 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn is_coprime_to_moduli() -> Result<(), Box<dyn Error>> {
+        let context = Context::new(MODULI, 16)?;
+
+        // A plaintext modulus sharing a factor with one of the moduli.
+        assert!(!context.is_coprime_to_moduli(1153 * 2));
+        assert!(!context.is_coprime_to_moduli(MODULI[3]));
+        assert!(!context.is_coprime_to_moduli(0));
+
+        // Coprime plaintext moduli.
+        assert!(context.is_coprime_to_moduli(1));
+        assert!(context.is_coprime_to_moduli(2));
+        assert!(context.is_coprime_to_moduli(1032193));
+        assert!(Context::new(&MODULI[1..], 16)?.is_coprime_to_moduli(1153));
+
+        Ok(())
+    }
+
+    #[test]
+    fn plaintext_bits_upper_bound() -> Result<(), Box<dyn Error>> {
+        for modulus in MODULI {
+            let context = Context::new(&[*modulus], 16)?;
+            assert_eq!(
+                context.plaintext_bits_upper_bound(),
+                64 - modulus.leading_zeros() as usize - 1
+            );
+        }
+
+        let context = Context::new(MODULI, 16)?;
+        assert_eq!(
+            context.plaintext_bits_upper_bound(),
+            context.modulus().bits() as usize - 1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn common_prefix_len() -> Result<(), Box<dyn Error>> {
+        let context = Arc::new(Context::new(MODULI, 16)?);
+        assert_eq!(context.common_prefix_len(&context), MODULI.len());
+
+        let mut current = context.clone();
+        while let Some(next) = current.next_context().cloned() {
+            assert_eq!(current.common_prefix_len(&next), current.moduli().len() - 1);
+            assert_eq!(next.common_prefix_len(&current), current.moduli().len() - 1);
+            assert_eq!(context.common_prefix_len(&next), next.moduli().len());
+            current = next;
+        }
+
+        assert_eq!(context.common_prefix_len(&Context::new(MODULI, 32)?), 0);
+        assert_eq!(
+            context.common_prefix_len(&Context::new(&MODULI[1..], 16)?),
+            0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn modulus_bits() -> Result<(), Box<dyn Error>> {
+        let context = Context::new(&MODULI[..1], 16)?;
+        assert_eq!(context.modulus_bits(), 11);
+        assert_eq!(context.moduli_bits(), vec![11]);
+
+        // Three 62-bit primes.
+        let context = Context::new(&MODULI[1..4], 16)?;
+        assert_eq!(context.moduli_bits(), vec![62, 62, 62]);
+        assert_eq!(context.modulus_bits(), 186);
+
+        let context = Context::new(MODULI, 16)?;
+        assert_eq!(context.moduli_bits(), vec![11, 62, 62, 62, 62]);
+        assert_eq!(context.modulus_bits(), 259);
+        assert_eq!(context.modulus_bits(), context.modulus().bits() as usize);
+
+        Ok(())
+    }
+
+    #[test]
+    fn context_builder() -> Result<(), Box<dyn Error>> {
+        // Explicit moduli.
+        for k in 1..=MODULI.len() {
+            let ctx = ContextBuilder::new()
+                .moduli(&MODULI[..k])
+                .degree(16)
+                .build()?;
+            assert_eq!(ctx, Context::new_arc(&MODULI[..k], 16)?);
+        }
+
+        // Generated moduli are distinct, even for identical sizes.
+        let ctx = ContextBuilder::new()
+            .generate_moduli(&[62, 62, 20])
+            .degree(1024)
+            .convolution(NttVariant::Negacyclic)
+            .build()?;
+        let p0 = generate_prime(62, 2048, 1 << 62).unwrap();
+        let p1 = generate_prime(62, 2048, p0).unwrap();
+        let p2 = generate_prime(20, 2048, 1 << 20).unwrap();
+        assert_eq!(ctx.moduli(), &[p0, p1, p2]);
+        assert_eq!(ctx.moduli_bits(), vec![62, 62, 20]);
+        assert_eq!(ctx, Context::new_arc(&[p0, p1, p2], 1024)?);
+
+        // The moduli of the same size are generated together, in any order.
+        let ctx = ContextBuilder::new()
+            .generate_moduli(&[62, 20, 62, 20])
+            .degree(1024)
+            .build()?;
+        let primes_62 = generate_ntt_primes(62, 1024, 2).unwrap();
+        let primes_20 = generate_ntt_primes(20, 1024, 2).unwrap();
+        assert_eq!(
+            ctx.moduli(),
+            &[primes_62[0], primes_20[0], primes_62[1], primes_20[1]]
+        );
+
+        // Exactly one of the moduli and their sizes must be specified.
+        assert_eq!(
+            ContextBuilder::new().degree(16).build(),
+            Err(crate::Error::MissingModuli)
+        );
+        assert_eq!(
+            ContextBuilder::new()
+                .moduli(MODULI)
+                .generate_moduli(&[62])
+                .degree(16)
+                .build(),
+            Err(crate::Error::ConflictingModuli)
+        );
+
+        // Invalid degrees, sizes, convolutions and moduli are rejected.
+        assert_eq!(
+            ContextBuilder::new().moduli(MODULI).build().unwrap_err(),
+            crate::Error::InvalidDegree(0)
+        );
+        assert!(ContextBuilder::new()
+            .moduli(MODULI)
+            .degree(24)
+            .build()
+            .is_err());
+        assert_eq!(
+            ContextBuilder::new()
+                .generate_moduli(&[63])
+                .degree(16)
+                .build(),
+            Err(crate::Error::InvalidModulusSize(63))
+        );
+        // There are only two 11-bit primes congruent to 1 modulo 128.
+        assert_eq!(
+            ContextBuilder::new()
+                .generate_moduli(&[11, 11, 11])
+                .degree(64)
+                .build(),
+            Err(crate::Error::NotEnoughNttPrimes {
+                size: 11,
+                degree: 64
+            })
+        );
+        assert_eq!(
+            ContextBuilder::new()
+                .moduli(MODULI)
+                .degree(16)
+                .convolution(NttVariant::Cyclic)
+                .build(),
+            Err(crate::Error::UnsupportedConvolution(NttVariant::Cyclic))
+        );
+        assert!(ContextBuilder::new()
+            .moduli(MODULI)
+            .degree(128)
+            .build()
+            .is_err());
+
+        Ok(())
+    }
 }
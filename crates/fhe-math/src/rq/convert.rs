@@ -1,25 +1,29 @@
 //! Implementation of conversions from and to polynomials.
 
-use super::{traits::TryConvertFrom, Context, Poly, Representation};
+use super::{serialize::coefficient_nbits, traits::TryConvertFrom, Context, Poly, Representation};
 use crate::{
     proto::rq::{Representation as RepresentationProto, Rq},
+    zq::{simd, Modulus},
     Error, Result,
 };
+use fhe_util::transcode_from_bytes;
 use itertools::{izip, Itertools};
-use ndarray::{Array2, ArrayView, Axis};
-use num_bigint::BigUint;
-use std::sync::Arc;
+use ndarray::{s, Array2, ArrayView, ArrayView2, Axis};
+use num_bigint::{BigInt, BigUint};
+use std::sync::{Arc, OnceLock};
 use zeroize::{Zeroize, Zeroizing};
 
 impl From<&Poly> for Rq {
     fn from(p: &Poly) -> Self {
-        assert!(!p.has_lazy_coefficients);
-
-        let mut q = p.clone();
-        if p.representation != Representation::PowerBasis {
-            q.change_representation(Representation::PowerBasis);
-        }
+        let mut proto = Rq::from_poly_without_coefficients(p);
+        p.serialize_into(&mut proto.coefficients);
+        proto
+    }
+}
 
+impl Rq {
+    // Returns the message of the polynomial, without its coefficients.
+    pub(super) fn from_poly_without_coefficients(p: &Poly) -> Self {
         let mut proto = Rq::default();
         match p.representation {
             Representation::PowerBasis => {
@@ -32,22 +36,42 @@ impl From<&Poly> for Rq {
                 proto.representation = RepresentationProto::Nttshoup as i32;
             }
         }
-        let mut serialization_length = 0;
-        p.ctx
-            .q
-            .iter()
-            .for_each(|qi| serialization_length += qi.serialization_length(p.ctx.degree));
-        let mut serialization = Vec::with_capacity(serialization_length);
-
-        izip!(q.coefficients.outer_iter(), p.ctx.q.iter())
-            .for_each(|(v, qi)| serialization.append(&mut qi.serialize_vec(v.as_slice().unwrap())));
-        proto.coefficients = serialization;
         proto.degree = p.ctx.degree as u32;
         proto.allow_variable_time = p.allow_variable_time_computations;
         proto
     }
 }
 
+// Returns an error unless the representation is PowerBasis, which is required
+// to convert from signed or wide integers.
+fn require_power_basis(representation: Option<Representation>) -> Result<()> {
+    match representation {
+        Some(Representation::PowerBasis) => Ok(()),
+        Some(r) => Err(Error::IncorrectRepresentation(
+            r,
+            Representation::PowerBasis,
+        )),
+        None => Err(Error::MissingRepresentation),
+    }
+}
+
+// Reduces the coefficients of each RNS channel modulo its modulus, so that
+// values out of range cannot produce an invalid polynomial.
+fn reduce_coefficients(coefficients: &mut Array2<u64>, ctx: &Context, variable_time: bool) {
+    izip!(coefficients.outer_iter_mut(), ctx.q.iter()).for_each(|(mut v, qi)| {
+        if variable_time {
+            unsafe { qi.reduce_vec_vt(v.as_slice_mut().unwrap()) }
+        } else {
+            qi.reduce_vec(v.as_slice_mut().unwrap())
+        }
+    });
+}
+
+/// Converts a vector of values; when the vector contains all the coefficients
+/// in RNS representation, which is required for the Ntt and NttShoup
+/// representations, the values of each RNS channel are reduced modulo its
+/// modulus. Shorter vectors are only accepted in PowerBasis representation,
+/// and each value is reduced modulo each of the moduli.
 impl TryConvertFrom<Vec<u64>> for Poly {
     fn try_convert_from<R>(
         mut v: Vec<u64>,
@@ -59,52 +83,49 @@ impl TryConvertFrom<Vec<u64>> for Poly {
         R: Into<Option<Representation>>,
     {
         let repr = representation.into();
+        let len = v.len();
         match repr {
             Some(Representation::Ntt) => {
-                if let Ok(coefficients) = Array2::from_shape_vec((ctx.q.len(), ctx.degree), v) {
+                if let Ok(mut coefficients) = Array2::from_shape_vec((ctx.q.len(), ctx.degree), v) {
+                    reduce_coefficients(&mut coefficients, ctx, variable_time);
                     Ok(Self {
                         ctx: ctx.clone(),
                         representation: repr.unwrap(),
                         allow_variable_time_computations: variable_time,
                         coefficients,
-                        coefficients_shoup: None,
+                        coefficients_shoup: OnceLock::new(),
                         has_lazy_coefficients: false,
                     })
                 } else {
-                    Err(Error::Default(
-                        "In Ntt representation, all coefficients must be specified".to_string(),
-                    ))
+                    Err(Error::InvalidLength(len, ctx.q.len() * ctx.degree))
                 }
             }
             Some(Representation::NttShoup) => {
-                if let Ok(coefficients) = Array2::from_shape_vec((ctx.q.len(), ctx.degree), v) {
-                    let mut p = Self {
+                if let Ok(mut coefficients) = Array2::from_shape_vec((ctx.q.len(), ctx.degree), v) {
+                    reduce_coefficients(&mut coefficients, ctx, variable_time);
+                    Ok(Self {
                         ctx: ctx.clone(),
                         representation: repr.unwrap(),
                         allow_variable_time_computations: variable_time,
                         coefficients,
-                        coefficients_shoup: None,
+                        coefficients_shoup: OnceLock::new(),
                         has_lazy_coefficients: false,
-                    };
-                    p.compute_coefficients_shoup();
-                    Ok(p)
+                    })
                 } else {
-                    Err(Error::Default(
-                        "In NttShoup representation, all coefficients must be specified"
-                            .to_string(),
-                    ))
+                    Err(Error::InvalidLength(len, ctx.q.len() * ctx.degree))
                 }
             }
             Some(Representation::PowerBasis) => {
                 if v.len() == ctx.q.len() * ctx.degree {
-                    let coefficients =
+                    let mut coefficients =
                         Array2::from_shape_vec((ctx.q.len(), ctx.degree), v).unwrap();
+                    reduce_coefficients(&mut coefficients, ctx, variable_time);
                     Ok(Self {
                         ctx: ctx.clone(),
                         representation: repr.unwrap(),
                         allow_variable_time_computations: variable_time,
                         coefficients,
-                        coefficients_shoup: None,
+                        coefficients_shoup: OnceLock::new(),
                         has_lazy_coefficients: false,
                     })
                 } else if v.len() <= ctx.degree {
@@ -132,13 +153,10 @@ impl TryConvertFrom<Vec<u64>> for Poly {
                     }
                     Ok(out)
                 } else {
-                    Err(Error::Default("In PowerBasis representation, either all coefficients must be specified, or only coefficients up to the degree".to_string()))
+                    Err(Error::TooManyValues(ctx.degree))
                 }
             }
-            None => Err(Error::Default(
-                "When converting from a vector, the representation needs to be specified"
-                    .to_string(),
-            )),
+            None => Err(Error::MissingRepresentation),
         }
     }
 }
@@ -153,44 +171,54 @@ impl TryConvertFrom<&Rq> for Poly {
     where
         R: Into<Option<Representation>>,
     {
-        let repr = value
-            .representation
-            .try_into()
-            .map_err(|_| Error::Default("Invalid representation".to_string()))?;
+        let repr = value.representation.try_into().map_err(|_| {
+            Error::Serialization(format!("Invalid representation: {}", value.representation))
+        })?;
         let representation_from_proto = match repr {
             RepresentationProto::Powerbasis => Representation::PowerBasis,
             RepresentationProto::Ntt => Representation::Ntt,
             RepresentationProto::Nttshoup => Representation::NttShoup,
-            _ => return Err(Error::Default("Unknown representation".to_string())),
+            _ => {
+                return Err(Error::Serialization(format!(
+                    "Unknown representation: {}",
+                    value.representation
+                )))
+            }
         };
 
         let variable_time = variable_time || value.allow_variable_time;
 
         if let Some(r) = representation.into() as Option<Representation> {
             if r != representation_from_proto {
-                return Err(Error::Default("The representation asked for does not match the representation in the serialization".to_string()));
+                return Err(Error::IncorrectRepresentation(representation_from_proto, r));
             }
         }
 
         let degree = value.degree as usize;
-        if degree % 8 != 0 || degree < 8 {
-            return Err(Error::Default("Invalid degree".to_string()));
+        if !degree.is_power_of_two() || degree < 8 {
+            return Err(Error::InvalidDegree(degree));
+        }
+        if degree != ctx.degree {
+            return Err(Error::Serialization(format!(
+                "The degree {} of the serialization does not match the degree {} of the context",
+                degree, ctx.degree
+            )));
         }
 
-        let mut expected_nbytes = 0;
-        ctx.q
-            .iter()
-            .for_each(|qi| expected_nbytes += qi.serialization_length(degree));
+        let length = |qi: &Modulus| coefficient_nbits(qi, value.byte_aligned) * degree / 8;
+        let expected_nbytes = ctx.q.iter().map(length).sum::<usize>();
         if value.coefficients.len() != expected_nbytes {
-            return Err(Error::Default("Invalid coefficients".to_string()));
+            return Err(Error::Serialization("Invalid coefficients".to_string()));
         }
 
         let mut power_basis_coefficients = Vec::with_capacity(ctx.q.len() * ctx.degree);
         let mut index = 0;
-        for i in 0..ctx.q.len() {
-            let qi = &ctx.q[i];
-            let size = qi.serialization_length(degree);
-            let mut v = qi.deserialize_vec(&value.coefficients[index..index + size]);
+        for qi in ctx.q.iter() {
+            let size = length(qi);
+            let mut v = transcode_from_bytes(
+                &value.coefficients[index..index + size],
+                coefficient_nbits(qi, value.byte_aligned),
+            );
             power_basis_coefficients.append(&mut v);
             index += size;
         }
@@ -206,6 +234,8 @@ impl TryConvertFrom<&Rq> for Poly {
     }
 }
 
+/// Converts an array of coefficients in RNS representation, whose rows are
+/// reduced modulo the moduli of their RNS channels.
 impl TryConvertFrom<Array2<u64>> for Poly {
     fn try_convert_from<R>(
         a: Array2<u64>,
@@ -217,25 +247,50 @@ impl TryConvertFrom<Array2<u64>> for Poly {
         R: Into<Option<Representation>>,
     {
         if a.shape() != [ctx.q.len(), ctx.degree] {
-            Err(Error::Default(
-                "The array of coefficient does not have the correct shape".to_string(),
+            Err(Error::InvalidShape(
+                a.shape().to_vec(),
+                vec![ctx.q.len(), ctx.degree],
             ))
         } else if let Some(repr) = representation.into() {
-            let mut p = Self {
+            let mut a = a;
+            reduce_coefficients(&mut a, ctx, variable_time);
+            Ok(Self {
                 ctx: ctx.clone(),
                 representation: repr,
                 allow_variable_time_computations: variable_time,
                 coefficients: a,
-                coefficients_shoup: None,
+                coefficients_shoup: OnceLock::new(),
                 has_lazy_coefficients: false,
-            };
-            if p.representation == Representation::NttShoup {
-                p.compute_coefficients_shoup()
-            }
-            Ok(p)
+            })
         } else {
-            Err(Error::Default("When converting from a 2-dimensional array, the representation needs to be specified".to_string()))
+            Err(Error::MissingRepresentation)
+        }
+    }
+}
+
+impl<'a> TryConvertFrom<ArrayView2<'a, u64>> for Poly {
+    fn try_convert_from<R>(
+        a: ArrayView2<'a, u64>,
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: R,
+    ) -> Result<Self>
+    where
+        R: Into<Option<Representation>>,
+    {
+        if a.shape() != [ctx.q.len(), ctx.degree] {
+            return Err(Error::InvalidShape(
+                a.shape().to_vec(),
+                vec![ctx.q.len(), ctx.degree],
+            ));
         }
+        // The coefficients are copied once, directly in standard layout.
+        Poly::try_convert_from(
+            a.as_standard_layout().into_owned(),
+            ctx,
+            variable_time,
+            representation,
+        )
     }
 }
 
@@ -253,6 +308,24 @@ impl<'a> TryConvertFrom<&'a [u64]> for Poly {
     }
 }
 
+impl<'a> TryConvertFrom<&'a [u32]> for Poly {
+    fn try_convert_from<R>(
+        v: &'a [u32],
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: R,
+    ) -> Result<Self>
+    where
+        R: Into<Option<Representation>>,
+    {
+        // Widen the values with vector instructions when available.
+        let mut w = vec![0u64; v.len()];
+        let n = simd::widen_u32(&mut w, v);
+        izip!(w[n..].iter_mut(), v[n..].iter()).for_each(|(wi, vi)| *wi = *vi as u64);
+        Poly::try_convert_from(w, ctx, variable_time, representation)
+    }
+}
+
 impl<'a> TryConvertFrom<&'a [i64]> for Poly {
     fn try_convert_from<R>(
         v: &'a [i64],
@@ -263,12 +336,8 @@ impl<'a> TryConvertFrom<&'a [i64]> for Poly {
     where
         R: Into<Option<Representation>>,
     {
-        if representation.into() != Some(Representation::PowerBasis) {
-            Err(Error::Default(
-                "Converting signed integer require to import in PowerBasis representation"
-                    .to_string(),
-            ))
-        } else if v.len() <= ctx.degree {
+        require_power_basis(representation.into())?;
+        if v.len() <= ctx.degree {
             let mut out = Self::zero(ctx, Representation::PowerBasis);
             if variable_time {
                 unsafe { out.allow_variable_time_computations() }
@@ -283,7 +352,80 @@ impl<'a> TryConvertFrom<&'a [i64]> for Poly {
             });
             Ok(out)
         } else {
-            Err(Error::Default("In PowerBasis representation with signed integers, only `degree` coefficients can be specified".to_string()))
+            Err(Error::TooManyValues(ctx.degree))
+        }
+    }
+}
+
+impl<'a> TryConvertFrom<&'a [i32]> for Poly {
+    fn try_convert_from<R>(
+        v: &'a [i32],
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: R,
+    ) -> Result<Self>
+    where
+        R: Into<Option<Representation>>,
+    {
+        // The widened values are zeroized once converted.
+        let w = Zeroizing::new(v.iter().map(|vi| *vi as i64).collect_vec());
+        Poly::try_convert_from(w.as_slice(), ctx, variable_time, representation)
+    }
+}
+
+impl<'a> TryConvertFrom<&'a [u128]> for Poly {
+    fn try_convert_from<R>(
+        v: &'a [u128],
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: R,
+    ) -> Result<Self>
+    where
+        R: Into<Option<Representation>>,
+    {
+        require_power_basis(representation.into())?;
+        if v.len() <= ctx.degree {
+            let mut out = Self::zero(ctx, Representation::PowerBasis);
+            if variable_time {
+                unsafe { out.allow_variable_time_computations() }
+            }
+            izip!(out.coefficients.outer_iter_mut(), ctx.q.iter()).for_each(|(mut w, qi)| {
+                let wi = w.as_slice_mut().unwrap();
+                if variable_time {
+                    unsafe { wi[..v.len()].copy_from_slice(&qi.reduce_vec_u128_vt(v)) }
+                } else {
+                    wi[..v.len()].copy_from_slice(Zeroizing::new(qi.reduce_vec_u128(v)).as_ref());
+                }
+            });
+            Ok(out)
+        } else {
+            Err(Error::TooManyValues(ctx.degree))
+        }
+    }
+}
+
+impl<'a> TryConvertFrom<&'a [BigInt]> for Poly {
+    fn try_convert_from<R>(
+        v: &'a [BigInt],
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: R,
+    ) -> Result<Self>
+    where
+        R: Into<Option<Representation>>,
+    {
+        require_power_basis(representation.into())?;
+        if v.len() <= ctx.degree {
+            let mut out = Self::zero(ctx, Representation::PowerBasis);
+            if variable_time {
+                unsafe { out.allow_variable_time_computations() }
+            }
+            izip!(out.coefficients.axis_iter_mut(Axis(1)), v).for_each(|(mut c, vi)| {
+                c.assign(&ArrayView::from(&ctx.rns.project_signed(vi)));
+            });
+            Ok(out)
+        } else {
+            Err(Error::TooManyValues(ctx.degree))
         }
     }
 }
@@ -302,6 +444,24 @@ impl<'a> TryConvertFrom<&'a Vec<i64>> for Poly {
     }
 }
 
+impl TryConvertFrom<Vec<i64>> for Poly {
+    fn try_convert_from<R>(
+        mut v: Vec<i64>,
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: R,
+    ) -> Result<Self>
+    where
+        R: Into<Option<Representation>>,
+    {
+        let p = Poly::try_convert_from(v.as_slice(), ctx, variable_time, representation);
+        if !variable_time {
+            v.zeroize();
+        }
+        p
+    }
+}
+
 impl<'a> TryConvertFrom<&'a [BigUint]> for Poly {
     fn try_convert_from<R>(
         v: &'a [BigUint],
@@ -315,39 +475,23 @@ impl<'a> TryConvertFrom<&'a [BigUint]> for Poly {
         let repr = representation.into();
 
         if v.len() > ctx.degree {
-            Err(Error::Default(
-                "The slice contains too many big integers compared to the polynomial degree"
-                    .to_string(),
-            ))
+            Err(Error::TooManyValues(ctx.degree))
         } else if repr.is_some() {
             let mut coefficients = Array2::zeros((ctx.q.len(), ctx.degree));
+            coefficients
+                .slice_mut(s![.., ..v.len()])
+                .assign(&ctx.rns.project_many(v));
 
-            izip!(coefficients.axis_iter_mut(Axis(1)), v).for_each(|(mut c, vi)| {
-                c.assign(&ArrayView::from(&ctx.rns.project(vi)));
-            });
-
-            let mut p = Self {
+            Ok(Self {
                 ctx: ctx.clone(),
                 representation: repr.unwrap(),
                 allow_variable_time_computations: variable_time,
                 coefficients,
-                coefficients_shoup: None,
+                coefficients_shoup: OnceLock::new(),
                 has_lazy_coefficients: false,
-            };
-
-            match p.representation {
-                Representation::PowerBasis => Ok(p),
-                Representation::Ntt => Ok(p),
-                Representation::NttShoup => {
-                    p.compute_coefficients_shoup();
-                    Ok(p)
-                }
-            }
+            })
         } else {
-            Err(Error::Default(
-                "When converting from a vector, the representation needs to be specified"
-                    .to_string(),
-            ))
+            Err(Error::MissingRepresentation)
         }
     }
 }
@@ -414,6 +558,20 @@ impl From<&Poly> for Vec<u64> {
     }
 }
 
+/// Moves the coefficients out of the polynomial without copying them, as in
+/// [`Poly::into_coefficients`]; the RNS channels are concatenated.
+impl From<Poly> for Vec<u64> {
+    fn from(p: Poly) -> Self {
+        p.into_coefficients().into_raw_vec()
+    }
+}
+
+impl From<&Poly> for Array2<u64> {
+    fn from(p: &Poly) -> Self {
+        p.coefficients.clone()
+    }
+}
+
 impl From<&Poly> for Vec<BigUint> {
     fn from(p: &Poly) -> Self {
         izip!(p.coefficients.axis_iter(Axis(1)))
@@ -427,14 +585,112 @@ mod tests {
     use crate::{
         proto::rq::Rq,
         rq::{traits::TryConvertFrom, Context, Poly, Representation},
+        test_allocator::{allocations, leaked_canaries},
+        zq::primes::generate_prime,
         Error as CrateError,
     };
-    use num_bigint::BigUint;
-    use rand::thread_rng;
+    use itertools::Itertools;
+    use ndarray::Array2;
+    use num_bigint::{BigInt, BigUint};
+    use proptest::collection::vec as prop_vec;
+    use proptest::prelude::any;
+    use rand::{thread_rng, RngCore};
     use std::{error::Error, sync::Arc};
 
     static MODULI: &[u64; 3] = &[1153, 4611686018326724609, 4611686018309947393];
 
+    proptest! {
+        #[test]
+        fn try_convert_from_u32(
+            v in prop_vec(any::<u32>(), 16),
+            w in prop_vec(any::<u32>(), 16 * MODULI.len())
+        ) {
+            let ctx = Arc::new(Context::new(MODULI, 16).unwrap());
+            let v_u64 = v.iter().map(|vi| *vi as u64).collect_vec();
+            let w_u64 = w.iter().map(|wi| *wi as u64).collect_vec();
+            for variable_time in [false, true] {
+                prop_assert_eq!(
+                    Poly::try_convert_from(v.as_slice(), &ctx, variable_time, Representation::PowerBasis).unwrap(),
+                    Poly::try_convert_from(v_u64.as_slice(), &ctx, variable_time, Representation::PowerBasis).unwrap()
+                );
+                prop_assert_eq!(
+                    Poly::try_convert_from(w.as_slice(), &ctx, variable_time, Representation::Ntt).unwrap(),
+                    Poly::try_convert_from(w_u64.as_slice(), &ctx, variable_time, Representation::Ntt).unwrap()
+                );
+            }
+            prop_assert!(Poly::try_convert_from(v.as_slice(), &ctx, false, Representation::Ntt).is_err());
+        }
+    }
+
+    #[test]
+    fn conversion_errors() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let n = MODULI.len() * 16;
+
+        assert_eq!(
+            Poly::try_convert_from(vec![0u64; 17], &ctx, false, Representation::Ntt),
+            Err(CrateError::InvalidLength(17, n))
+        );
+        assert_eq!(
+            Poly::try_convert_from(vec![0u64; 17], &ctx, false, Representation::NttShoup),
+            Err(CrateError::InvalidLength(17, n))
+        );
+        assert_eq!(
+            Poly::try_convert_from(vec![0u64; 17], &ctx, false, Representation::PowerBasis),
+            Err(CrateError::TooManyValues(16))
+        );
+        assert_eq!(
+            Poly::try_convert_from(vec![0u64; 16], &ctx, false, None),
+            Err(CrateError::MissingRepresentation)
+        );
+        assert_eq!(
+            Poly::try_convert_from(&[0i64; 17], &ctx, false, Representation::PowerBasis),
+            Err(CrateError::TooManyValues(16))
+        );
+        assert_eq!(
+            Poly::try_convert_from(&[0i64], &ctx, false, Representation::Ntt),
+            Err(CrateError::IncorrectRepresentation(
+                Representation::Ntt,
+                Representation::PowerBasis
+            ))
+        );
+        assert_eq!(
+            Poly::try_convert_from(&[0u128] as &[u128], &ctx, false, None),
+            Err(CrateError::MissingRepresentation)
+        );
+        assert_eq!(
+            Poly::try_convert_from(
+                &[BigInt::from(0)] as &[BigInt],
+                &ctx,
+                false,
+                Representation::NttShoup
+            ),
+            Err(CrateError::IncorrectRepresentation(
+                Representation::NttShoup,
+                Representation::PowerBasis
+            ))
+        );
+        assert_eq!(
+            Poly::try_convert_from(vec![BigUint::from(0u64); 17].as_slice(), &ctx, false, None),
+            Err(CrateError::TooManyValues(16))
+        );
+        assert_eq!(
+            Poly::try_convert_from(
+                Array2::zeros((1, n)),
+                &ctx,
+                false,
+                Representation::PowerBasis
+            ),
+            Err(CrateError::InvalidShape(vec![1, n], vec![MODULI.len(), 16]))
+        );
+        assert_eq!(
+            Poly::try_convert_from(Array2::zeros((MODULI.len(), 16)), &ctx, false, None),
+            Err(CrateError::MissingRepresentation)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn proto() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -448,15 +704,21 @@ mod tests {
                 p
             );
             assert_eq!(
-				Poly::try_convert_from(&proto, &ctx, false, Representation::Ntt)
-					.expect_err("Should fail because of mismatched representations"),
-					CrateError::Default("The representation asked for does not match the representation in the serialization".to_string())
-			);
+                Poly::try_convert_from(&proto, &ctx, false, Representation::Ntt)
+                    .expect_err("Should fail because of mismatched representations"),
+                CrateError::IncorrectRepresentation(
+                    Representation::PowerBasis,
+                    Representation::Ntt
+                )
+            );
             assert_eq!(
-				Poly::try_convert_from(&proto, &ctx, false, Representation::NttShoup)
-					.expect_err("Should fail because of mismatched representations"),
-					CrateError::Default("The representation asked for does not match the representation in the serialization".to_string())
-			);
+                Poly::try_convert_from(&proto, &ctx, false, Representation::NttShoup)
+                    .expect_err("Should fail because of mismatched representations"),
+                CrateError::IncorrectRepresentation(
+                    Representation::PowerBasis,
+                    Representation::NttShoup
+                )
+            );
         }
 
         let ctx = Arc::new(Context::new(MODULI, 16)?);
@@ -468,23 +730,59 @@ mod tests {
             p
         );
         assert_eq!(
-			Poly::try_convert_from(&proto, &ctx, false, Representation::Ntt)
-				.expect_err("Should fail because of mismatched representations"),
-				CrateError::Default("The representation asked for does not match the representation in the serialization".to_string())
-		);
+            Poly::try_convert_from(&proto, &ctx, false, Representation::Ntt)
+                .expect_err("Should fail because of mismatched representations"),
+            CrateError::IncorrectRepresentation(Representation::PowerBasis, Representation::Ntt)
+        );
         assert_eq!(
-			Poly::try_convert_from(&proto, &ctx, false, Representation::NttShoup)
-				.expect_err("Should fail because of mismatched representations"),
-				CrateError::Default("The representation asked for does not match the representation in the serialization".to_string())
-		);
+            Poly::try_convert_from(&proto, &ctx, false, Representation::NttShoup)
+                .expect_err("Should fail because of mismatched representations"),
+            CrateError::IncorrectRepresentation(
+                Representation::PowerBasis,
+                Representation::NttShoup
+            )
+        );
 
         let ctx = Arc::new(Context::new(&MODULI[0..1], 16)?);
         assert_eq!(
             Poly::try_convert_from(&proto, &ctx, false, None)
                 .expect_err("Should fail because of incorrect context"),
-            CrateError::Default("Invalid coefficients".to_string())
+            CrateError::Serialization("Invalid coefficients".to_string())
+        );
+
+        // A degree which is a multiple of 8 but not a power of two.
+        let mut proto_24 = proto.clone();
+        proto_24.degree = 24;
+        assert_eq!(
+            Poly::try_convert_from(&proto_24, &ctx, false, None)
+                .expect_err("Should fail because of an invalid degree"),
+            CrateError::InvalidDegree(24)
         );
 
+        // A degree mismatching the degree of the context.
+        let ctx = Arc::new(Context::new(MODULI, 32)?);
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let proto_32 = Rq::from(&p);
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        assert!(matches!(
+            Poly::try_convert_from(&proto_32, &ctx, false, None)
+                .expect_err("Should fail because of mismatched degrees"),
+            CrateError::Serialization(_)
+        ));
+        let mut proto_16 = proto.clone();
+        proto_16.degree = 32;
+        assert!(Poly::try_convert_from(&proto_16, &ctx, false, None).is_err());
+
+        // The errors for invalid representations report the raw value.
+        for value in [0, 7] {
+            let mut proto_invalid = proto.clone();
+            proto_invalid.representation = value;
+            assert!(Poly::try_convert_from(&proto_invalid, &ctx, false, None)
+                .expect_err("Should fail because of the representation")
+                .to_string()
+                .ends_with(&format!("representation: {value}")));
+        }
+
         Ok(())
     }
 
@@ -579,74 +877,358 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn try_convert_from_i32_u128_and_signed_vec_zero() -> Result<(), Box<dyn Error>> {
+        for moduli in [&MODULI[..1], MODULI] {
+            let ctx = Arc::new(Context::new(moduli, 16)?);
+            let zero = Poly::zero(&ctx, Representation::PowerBasis);
+
+            // Power Basis
+            assert_eq!(
+                Poly::try_convert_from(&[0i32] as &[i32], &ctx, false, Representation::PowerBasis)?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(
+                    &[0u128] as &[u128],
+                    &ctx,
+                    false,
+                    Representation::PowerBasis
+                )?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(vec![0i64], &ctx, false, Representation::PowerBasis)?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(&vec![0i64], &ctx, false, Representation::PowerBasis)?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(
+                    &[0i32; 16] as &[i32],
+                    &ctx,
+                    false,
+                    Representation::PowerBasis
+                )?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(
+                    &[0u128; 16] as &[u128],
+                    &ctx,
+                    false,
+                    Representation::PowerBasis
+                )?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(vec![0i64; 16], &ctx, false, Representation::PowerBasis)?,
+                zero
+            );
+            assert_eq!(
+                Poly::try_convert_from(
+                    &[0i32; 17] as &[i32],
+                    &ctx,
+                    false,
+                    Representation::PowerBasis
+                ),
+                Err(crate::Error::TooManyValues(16))
+            );
+            assert_eq!(
+                Poly::try_convert_from(
+                    &[0u128; 17] as &[u128],
+                    &ctx,
+                    false,
+                    Representation::PowerBasis
+                ),
+                Err(crate::Error::TooManyValues(16))
+            );
+            assert_eq!(
+                Poly::try_convert_from(vec![0i64; 17], &ctx, false, Representation::PowerBasis),
+                Err(crate::Error::TooManyValues(16))
+            );
+
+            // Ntt, and no representation
+            let expected = Err(crate::Error::IncorrectRepresentation(
+                Representation::Ntt,
+                Representation::PowerBasis,
+            ));
+            assert_eq!(
+                Poly::try_convert_from(&[0i32; 16] as &[i32], &ctx, false, Representation::Ntt),
+                expected
+            );
+            assert_eq!(
+                Poly::try_convert_from(&[0u128; 16] as &[u128], &ctx, false, Representation::Ntt),
+                expected
+            );
+            assert_eq!(
+                Poly::try_convert_from(vec![0i64; 16], &ctx, false, Representation::Ntt),
+                expected
+            );
+            assert_eq!(
+                Poly::try_convert_from(vec![0i64; 16], &ctx, false, None),
+                Err(crate::Error::MissingRepresentation)
+            );
+            assert_eq!(
+                Poly::try_convert_from(&[0i32] as &[i32], &ctx, false, None),
+                Err(crate::Error::MissingRepresentation)
+            );
+        }
+
+        // The new types agree with the existing conversions.
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let v = [-3i32, 0, i32::MIN, i32::MAX, 7];
+        let v_i64 = v.iter().map(|vi| *vi as i64).collect_vec();
+        let expected =
+            Poly::try_convert_from(v_i64.as_slice(), &ctx, false, Representation::PowerBasis)?;
+        for variable_time in [false, true] {
+            assert_eq!(
+                Poly::try_convert_from(
+                    &v as &[i32],
+                    &ctx,
+                    variable_time,
+                    Representation::PowerBasis
+                )?
+                .coefficients,
+                expected.coefficients
+            );
+            assert_eq!(
+                Poly::try_convert_from(
+                    v_i64.clone(),
+                    &ctx,
+                    variable_time,
+                    Representation::PowerBasis
+                )?
+                .coefficients,
+                expected.coefficients
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn try_convert_from_vec_zero() -> Result<(), Box<dyn Error>> {
         for modulus in MODULI {
             let ctx = Arc::new(Context::new(&[*modulus], 16)?);
             assert_eq!(
-                Poly::try_convert_from(vec![], &ctx, false, Representation::PowerBasis)?,
+                Poly::try_convert_from(Vec::<u64>::new(), &ctx, false, Representation::PowerBasis)?,
                 Poly::zero(&ctx, Representation::PowerBasis)
             );
-            assert!(Poly::try_convert_from(vec![], &ctx, false, Representation::Ntt).is_err());
+            assert!(
+                Poly::try_convert_from(Vec::<u64>::new(), &ctx, false, Representation::Ntt)
+                    .is_err()
+            );
 
             assert_eq!(
-                Poly::try_convert_from(vec![0], &ctx, false, Representation::PowerBasis)?,
+                Poly::try_convert_from(vec![0u64], &ctx, false, Representation::PowerBasis)?,
                 Poly::zero(&ctx, Representation::PowerBasis)
             );
-            assert!(Poly::try_convert_from(vec![0], &ctx, false, Representation::Ntt).is_err());
+            assert!(Poly::try_convert_from(vec![0u64], &ctx, false, Representation::Ntt).is_err());
 
             assert_eq!(
-                Poly::try_convert_from(vec![0; 16], &ctx, false, Representation::PowerBasis)?,
+                Poly::try_convert_from(vec![0u64; 16], &ctx, false, Representation::PowerBasis)?,
                 Poly::zero(&ctx, Representation::PowerBasis)
             );
             assert_eq!(
-                Poly::try_convert_from(vec![0; 16], &ctx, false, Representation::Ntt)?,
+                Poly::try_convert_from(vec![0u64; 16], &ctx, false, Representation::Ntt)?,
                 Poly::zero(&ctx, Representation::Ntt)
             );
 
+            assert!(Poly::try_convert_from(
+                vec![0u64; 17],
+                &ctx,
+                false,
+                Representation::PowerBasis
+            )
+            .is_err());
             assert!(
-                Poly::try_convert_from(vec![0; 17], &ctx, false, Representation::PowerBasis)
-                    .is_err()
+                Poly::try_convert_from(vec![0u64; 17], &ctx, false, Representation::Ntt).is_err()
             );
-            assert!(Poly::try_convert_from(vec![0; 17], &ctx, false, Representation::Ntt).is_err());
         }
 
         let ctx = Arc::new(Context::new(MODULI, 16)?);
         assert_eq!(
-            Poly::try_convert_from(vec![], &ctx, false, Representation::PowerBasis)?,
+            Poly::try_convert_from(Vec::<u64>::new(), &ctx, false, Representation::PowerBasis)?,
             Poly::zero(&ctx, Representation::PowerBasis)
         );
-        assert!(Poly::try_convert_from(vec![], &ctx, false, Representation::Ntt).is_err());
+        assert!(
+            Poly::try_convert_from(Vec::<u64>::new(), &ctx, false, Representation::Ntt).is_err()
+        );
 
         assert_eq!(
-            Poly::try_convert_from(vec![0], &ctx, false, Representation::PowerBasis)?,
+            Poly::try_convert_from(vec![0u64], &ctx, false, Representation::PowerBasis)?,
             Poly::zero(&ctx, Representation::PowerBasis)
         );
-        assert!(Poly::try_convert_from(vec![0], &ctx, false, Representation::Ntt).is_err());
+        assert!(Poly::try_convert_from(vec![0u64], &ctx, false, Representation::Ntt).is_err());
 
         assert_eq!(
-            Poly::try_convert_from(vec![0; 16], &ctx, false, Representation::PowerBasis)?,
+            Poly::try_convert_from(vec![0u64; 16], &ctx, false, Representation::PowerBasis)?,
             Poly::zero(&ctx, Representation::PowerBasis)
         );
-        assert!(Poly::try_convert_from(vec![0; 16], &ctx, false, Representation::Ntt).is_err());
+        assert!(Poly::try_convert_from(vec![0u64; 16], &ctx, false, Representation::Ntt).is_err());
 
         assert!(
-            Poly::try_convert_from(vec![0; 17], &ctx, false, Representation::PowerBasis).is_err()
+            Poly::try_convert_from(vec![0u64; 17], &ctx, false, Representation::PowerBasis)
+                .is_err()
         );
-        assert!(Poly::try_convert_from(vec![0; 17], &ctx, false, Representation::Ntt).is_err());
+        assert!(Poly::try_convert_from(vec![0u64; 17], &ctx, false, Representation::Ntt).is_err());
 
         assert_eq!(
-            Poly::try_convert_from(vec![0; 48], &ctx, false, Representation::PowerBasis)?,
+            Poly::try_convert_from(vec![0u64; 48], &ctx, false, Representation::PowerBasis)?,
             Poly::zero(&ctx, Representation::PowerBasis)
         );
         assert_eq!(
-            Poly::try_convert_from(vec![0; 48], &ctx, false, Representation::Ntt)?,
+            Poly::try_convert_from(vec![0u64; 48], &ctx, false, Representation::Ntt)?,
             Poly::zero(&ctx, Representation::Ntt)
         );
 
         Ok(())
     }
 
+    #[test]
+    fn array2() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+            let a = Array2::<u64>::from(&p);
+            assert_eq!(a.shape(), [MODULI.len(), 16]);
+            assert_eq!(a, p.coefficients());
+            let q = Poly::try_convert_from(a, &ctx, false, representation.clone())?;
+            assert_eq!(p, q);
+
+            let q =
+                Poly::try_convert_from(p.clone().into_coefficients(), &ctx, false, representation)?;
+            assert_eq!(p, q);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn array_view2() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for representation in [Representation::PowerBasis, Representation::Ntt] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+
+            // A view in standard layout is copied with a single allocation.
+            let before = allocations();
+            let q = Poly::try_convert_from(p.coefficients(), &ctx, false, representation.clone())?;
+            assert_eq!(allocations(), before + 1);
+            assert_eq!(p, q);
+
+            // So is a view in another layout, which is copied in standard layout.
+            let transposed = p.coefficients().t().to_owned();
+            let before = allocations();
+            let q = Poly::try_convert_from(transposed.t(), &ctx, false, representation.clone())?;
+            assert_eq!(allocations(), before + 1);
+            assert_eq!(p, q);
+            assert!(q.coefficients().is_standard_layout());
+
+            // A shared array is only copied when it has other references.
+            let shared = Arc::new(Array2::from(&p));
+            let other = shared.clone();
+            let before = allocations();
+            let q = Poly::from_shared(shared, &ctx, false, representation.clone())?;
+            assert_eq!(allocations(), before + 1);
+            assert_eq!(p, q);
+            let before = allocations();
+            let q = Poly::from_shared(other, &ctx, false, representation.clone())?;
+            assert_eq!(allocations(), before);
+            assert_eq!(p, q);
+        }
+
+        // The shapes are checked as for the owned arrays.
+        let a = Array2::<u64>::zeros((2, 16));
+        let expected = Poly::try_convert_from(a.clone(), &ctx, false, Representation::Ntt);
+        assert_eq!(
+            expected,
+            Err(CrateError::InvalidShape(vec![2, 16], vec![3, 16]))
+        );
+        assert_eq!(
+            Poly::try_convert_from(a.view(), &ctx, false, Representation::Ntt),
+            expected
+        );
+        assert_eq!(
+            Poly::from_shared(Arc::new(a), &ctx, false, Representation::Ntt),
+            expected
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_range_values_are_reduced() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let mut v = vec![0u64; MODULI.len() * 16];
+        v[0] = MODULI[0] + 5;
+        v[16 + 3] = u64::MAX;
+        v[32 + 15] = MODULI[2];
+        let mut expected = v.clone();
+        expected[0] = 5;
+        expected[16 + 3] = u64::MAX % MODULI[1];
+        expected[32 + 15] = 0;
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            for variable_time in [false, true] {
+                let p =
+                    Poly::try_convert_from(v.clone(), &ctx, variable_time, representation.clone())?;
+                assert_eq!(Vec::<u64>::from(&p), expected);
+
+                let a = Array2::from_shape_vec((MODULI.len(), 16), v.clone())?;
+                let q = Poly::try_convert_from(a, &ctx, variable_time, representation.clone())?;
+                assert_eq!(q, p);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn consuming_extraction() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+            let v = Vec::<u64>::from(&p);
+            let a = Array2::<u64>::from(&p);
+
+            // The storage is moved out without allocation.
+            let q = p.clone();
+            let before = allocations();
+            let v_moved = Vec::<u64>::from(q);
+            assert_eq!(allocations(), before);
+            assert_eq!(v_moved, v);
+            assert_eq!(p.clone().into_coefficients(), a);
+        }
+
+        // The Shoup coefficients are zeroized before being dropped.
+        let p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        let shoup = p.coefficients_shoup();
+        let canaries = [shoup[[0, 0]], shoup[[2, 15]]];
+        let mut coefficients = None;
+        let leaks = leaked_canaries(canaries, || coefficients = Some(p.into_coefficients()));
+        assert_eq!(leaks, 0);
+        assert!(coefficients.is_some());
+
+        Ok(())
+    }
+
     #[test]
     fn biguint() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -678,4 +1260,132 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn biguint_large_degree() -> Result<(), Box<dyn Error>> {
+        // When the `rayon` feature is enabled, the coefficients are projected
+        // in parallel; the output must match the sequential projection.
+        let mut rng = thread_rng();
+        let degree = 8192;
+        let moduli = [
+            generate_prime(62, 2 * degree as u64, 1 << 62).unwrap(),
+            generate_prime(50, 2 * degree as u64, 1 << 50).unwrap(),
+        ];
+        let ctx = Arc::new(Context::new(&moduli, degree)?);
+        let values = (0..degree)
+            .map(|_| (BigUint::from(rng.next_u64()) << 64) + rng.next_u64())
+            .collect_vec();
+
+        let p = Poly::try_convert_from(values.as_slice(), &ctx, false, Representation::PowerBasis)?;
+        for (column, v) in p.coefficients().columns().into_iter().zip(&values) {
+            assert_eq!(column.to_vec(), ctx.rns.project(v));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn u128() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let v = [
+            0u128,
+            1,
+            u64::MAX as u128,
+            (MODULI[1] as u128) << 64,
+            ((MODULI[2] as u128) << 64) - 1,
+            u128::MAX,
+        ];
+
+        for variable_time in [false, true] {
+            let p = Poly::try_convert_from(
+                v.as_slice(),
+                &ctx,
+                variable_time,
+                Representation::PowerBasis,
+            )?;
+            let expected = v
+                .iter()
+                .map(|vi| BigUint::from(*vi) % ctx.modulus())
+                .chain(std::iter::repeat(BigUint::from(0u64)))
+                .take(16)
+                .collect::<Vec<_>>();
+            assert_eq!(Vec::<BigUint>::from(&p), expected);
+        }
+
+        assert!(Poly::try_convert_from(v.as_slice(), &ctx, false, Representation::Ntt).is_err());
+        assert!(Poly::try_convert_from(
+            &[0u128; 17] as &[u128],
+            &ctx,
+            false,
+            Representation::PowerBasis
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bigint() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let modulus = BigInt::from(ctx.modulus().clone());
+        let v = [
+            BigInt::from(0),
+            BigInt::from(-1),
+            BigInt::from(1),
+            BigInt::from(i64::MIN) * BigInt::from(i64::MAX),
+            -&modulus,
+            -&modulus - 1,
+            &modulus + 1,
+        ];
+
+        for variable_time in [false, true] {
+            let p = Poly::try_convert_from(
+                v.as_slice(),
+                &ctx,
+                variable_time,
+                Representation::PowerBasis,
+            )?;
+            let expected = v
+                .iter()
+                .map(|vi| {
+                    (((vi % &modulus) + &modulus) % &modulus)
+                        .to_biguint()
+                        .unwrap()
+                })
+                .chain(std::iter::repeat(BigUint::from(0u64)))
+                .take(16)
+                .collect::<Vec<_>>();
+            assert_eq!(Vec::<BigUint>::from(&p), expected);
+        }
+
+        // Consistency with the conversion of i64 and with the centered lift.
+        let small = (0..16)
+            .map(|_| (rng.next_u64() as i64) >> 8)
+            .collect::<Vec<_>>();
+        let small_bigint = small.iter().map(|s| BigInt::from(*s)).collect::<Vec<_>>();
+        let p = Poly::try_convert_from(
+            small_bigint.as_slice(),
+            &ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        assert_eq!(
+            p,
+            Poly::try_convert_from(small.as_slice(), &ctx, false, Representation::PowerBasis)?
+        );
+        assert_eq!(p.lift_centered(), small_bigint);
+
+        assert!(Poly::try_convert_from(v.as_slice(), &ctx, false, Representation::Ntt).is_err());
+        assert!(Poly::try_convert_from(v.as_slice(), &ctx, false, None).is_err());
+        assert!(Poly::try_convert_from(
+            vec![BigInt::from(0); 17].as_slice(),
+            &ctx,
+            false,
+            Representation::PowerBasis
+        )
+        .is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,186 @@
+//! Distributions of polynomials, for use with the `rand` ecosystem.
+
+use super::{Context, Poly, Representation, SensitivePoly};
+use crate::{Error, Result};
+use rand::{distributions::Distribution, CryptoRng, Rng, RngCore};
+use std::sync::Arc;
+
+/// Uniform distribution of polynomials in a context, so that a polynomial can
+/// be sampled with `rng.sample(UniformPoly::new(&ctx, representation))`.
+///
+/// Sampling is equivalent to [`Poly::random_from_rng`] with the same
+/// generator. The [`Distribution`] trait cannot require the generator to be
+/// cryptographically secure as the other sampling functions do; this is the
+/// responsibility of the caller.
+#[derive(Debug, Clone)]
+pub struct UniformPoly {
+    ctx: Arc<Context>,
+    representation: Representation,
+}
+
+impl UniformPoly {
+    /// Creates the uniform distribution of polynomials in the context `ctx`,
+    /// in representation `representation`.
+    pub fn new(ctx: &Arc<Context>, representation: Representation) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            representation,
+        }
+    }
+}
+
+impl Distribution<Poly> for UniformPoly {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Poly {
+        Poly::random_from_rng(
+            &self.ctx,
+            self.representation.clone(),
+            &mut AssumeCrypto(rng),
+        )
+    }
+}
+
+/// Distribution of small polynomials in a context, whose coefficients follow
+/// a centered binomial distribution of a given variance.
+///
+/// Sampling is equivalent to [`Poly::small`] with the same generator, and the
+/// polynomial is returned as a [`SensitivePoly`]. As for [`UniformPoly`], the
+/// caller is responsible for using a cryptographically secure generator.
+#[derive(Debug, Clone)]
+pub struct CbdPoly {
+    ctx: Arc<Context>,
+    representation: Representation,
+    variance: usize,
+}
+
+impl CbdPoly {
+    /// Creates the centered binomial distribution of variance `variance` of
+    /// polynomials in the context `ctx`, in representation `representation`.
+    ///
+    /// Returns an error if the variance does not belong to [1, ..., 16].
+    pub fn new(
+        ctx: &Arc<Context>,
+        representation: Representation,
+        variance: usize,
+    ) -> Result<Self> {
+        if !(1..=16).contains(&variance) {
+            return Err(Error::Sampling(
+                "The variance should be an integer between 1 and 16".to_string(),
+            ));
+        }
+        Ok(Self {
+            ctx: ctx.clone(),
+            representation,
+            variance,
+        })
+    }
+}
+
+impl Distribution<SensitivePoly> for CbdPoly {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SensitivePoly {
+        Poly::small(
+            &self.ctx,
+            self.representation.clone(),
+            self.variance,
+            &mut AssumeCrypto(rng),
+        )
+        .expect("the variance is valid")
+    }
+}
+
+/// Wrapper around the generator passed to [`Distribution::sample`], so that
+/// it can be given to the sampling functions of [`Poly`].
+struct AssumeCrypto<'a, R: ?Sized>(&'a mut R);
+
+impl<R: RngCore + ?Sized> RngCore for AssumeCrypto<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl<R: RngCore + ?Sized> CryptoRng for AssumeCrypto<'_, R> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CbdPoly, UniformPoly};
+    use crate::rq::{Context, Poly, Representation};
+    use rand::{distributions::Distribution, Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::{error::Error, sync::Arc};
+
+    const MODULI: &[u64; 3] = &[1153, 4611686018326724609, 4611686018309947393];
+
+    #[test]
+    fn uniform() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let seed = [3u8; 32];
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let distribution = UniformPoly::new(&ctx, representation.clone());
+
+            // Sampling is reproducible, and matches `random_from_seed`.
+            let p = Poly::seeded_rng(seed).sample(&distribution);
+            assert_eq!(p, Poly::seeded_rng(seed).sample(&distribution));
+            assert_eq!(
+                p,
+                Poly::random_from_seed(&ctx, representation.clone(), seed)
+            );
+            assert_eq!(p.representation(), &representation);
+
+            // Consecutive samples are those of `random_from_rng`.
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let mut expected_rng = ChaCha8Rng::seed_from_u64(0);
+            for p in distribution.sample_iter(&mut rng).take(3) {
+                assert_eq!(
+                    p,
+                    Poly::random_from_rng(&ctx, representation.clone(), &mut expected_rng)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn cbd() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        for variance in [1, 4, 16] {
+            let distribution = CbdPoly::new(&ctx, Representation::Ntt, variance)?;
+
+            // Sampling is reproducible, and matches `small`.
+            let p = ChaCha8Rng::seed_from_u64(1).sample(&distribution);
+            assert_eq!(p, ChaCha8Rng::seed_from_u64(1).sample(&distribution));
+            assert_eq!(
+                p,
+                Poly::small(
+                    &ctx,
+                    Representation::Ntt,
+                    variance,
+                    &mut ChaCha8Rng::seed_from_u64(1)
+                )?
+            );
+            assert_ne!(p, ChaCha8Rng::seed_from_u64(2).sample(&distribution));
+        }
+
+        assert!(CbdPoly::new(&ctx, Representation::Ntt, 0).is_err());
+        assert!(CbdPoly::new(&ctx, Representation::Ntt, 17).is_err());
+
+        Ok(())
+    }
+}
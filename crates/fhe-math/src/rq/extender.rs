@@ -0,0 +1,241 @@
+#![warn(missing_docs, unused_imports)]
+
+//! Polynomial extender.
+
+use super::{Context, Poly, Representation};
+use crate::{
+    rns::{RnsContext, RnsConverter},
+    Error, Result,
+};
+use itertools::izip;
+use ndarray::{s, Array2, Axis};
+use std::sync::{Arc, OnceLock};
+use zeroize::Zeroize;
+
+/// Context extender, from a context `from` of modulus q to a context `to` whose
+/// moduli start with the moduli of `from`, i.e., of modulus q * p.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Extender {
+    from: Arc<Context>,
+    to: Arc<Context>,
+    converter: Option<RnsConverter>,
+}
+
+impl Extender {
+    /// Create an extender from a context `from` to a context `to`.
+    ///
+    /// Returns an error if the degrees differ, or if the moduli of `from` are
+    /// not a prefix of the moduli of `to`.
+    pub fn new(from: &Arc<Context>, to: &Arc<Context>) -> Result<Self> {
+        if from.degree != to.degree {
+            return Err(Error::InvalidContext);
+        }
+        if !to.moduli.starts_with(&from.moduli) {
+            return Err(Error::InvalidContext);
+        }
+
+        let converter = if to.moduli.len() > from.moduli.len() {
+            let new_rns = Arc::new(RnsContext::new(&to.moduli[from.moduli.len()..])?);
+            Some(RnsConverter::new(&from.rns, &new_rns))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            from: from.clone(),
+            to: to.clone(),
+            converter,
+        })
+    }
+
+    /// Extend a polynomial to the context `to`: the coefficients are lifted in
+    /// the centered interval [-q/2, q/2), and their residues modulo the new
+    /// moduli are computed using a fast base extension; the residues modulo the
+    /// moduli of `from` are unchanged. As described in
+    /// [`RnsConverter::convert`], coefficients very close to -q/2 or q/2 may be
+    /// lifted to the other end of the interval.
+    ///
+    /// For polynomials in Ntt or NttShoup representation, only the new rows are
+    /// transformed back to the Ntt representation, and the output is in Ntt
+    /// representation.
+    ///
+    /// Returns an error if the polynomial is not in the context `from`.
+    pub fn extend(&self, p: &Poly) -> Result<Poly> {
+        if p.ctx.as_ref() != self.from.as_ref() {
+            return Err(Error::InvalidContext);
+        }
+
+        let representation = if p.representation == Representation::PowerBasis {
+            Representation::PowerBasis
+        } else {
+            Representation::Ntt
+        };
+
+        let k = self.from.q.len();
+        let mut coefficients = Array2::<u64>::zeros((self.to.q.len(), self.to.degree));
+        coefficients.slice_mut(s![..k, ..]).assign(&p.coefficients);
+
+        if let Some(converter) = &self.converter {
+            if p.representation == Representation::PowerBasis {
+                izip!(
+                    coefficients.slice_mut(s![k.., ..]).axis_iter_mut(Axis(1)),
+                    p.coefficients.axis_iter(Axis(1))
+                )
+                .for_each(|(new_column, column)| converter.convert(column, new_column));
+            } else {
+                let mut p_coefficients_powerbasis = p.coefficients.clone();
+                // Backward NTT
+                if p.allow_variable_time_computations {
+                    izip!(p_coefficients_powerbasis.outer_iter_mut(), p.ctx.ops.iter())
+                        .for_each(|(mut v, op)| unsafe { op.backward_vt(v.as_mut_ptr()) });
+                } else {
+                    izip!(p_coefficients_powerbasis.outer_iter_mut(), p.ctx.ops.iter())
+                        .for_each(|(mut v, op)| op.backward(v.as_slice_mut().unwrap()));
+                }
+                // Conversion
+                izip!(
+                    coefficients.slice_mut(s![k.., ..]).axis_iter_mut(Axis(1)),
+                    p_coefficients_powerbasis.axis_iter(Axis(1))
+                )
+                .for_each(|(new_column, column)| converter.convert(column, new_column));
+                p_coefficients_powerbasis.as_slice_mut().unwrap().zeroize();
+                // Forward NTT on the new rows
+                if p.allow_variable_time_computations {
+                    izip!(
+                        coefficients.slice_mut(s![k.., ..]).outer_iter_mut(),
+                        &self.to.ops[k..]
+                    )
+                    .for_each(|(mut v, op)| unsafe { op.forward_vt(v.as_mut_ptr()) });
+                } else {
+                    izip!(
+                        coefficients.slice_mut(s![k.., ..]).outer_iter_mut(),
+                        &self.to.ops[k..]
+                    )
+                    .for_each(|(mut v, op)| op.forward(v.as_slice_mut().unwrap()));
+                }
+            }
+        }
+
+        Ok(Poly {
+            ctx: self.to.clone(),
+            representation,
+            allow_variable_time_computations: p.allow_variable_time_computations,
+            coefficients,
+            coefficients_shoup: OnceLock::new(),
+            has_lazy_coefficients: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Extender;
+    use crate::{
+        rq::{traits::TryConvertFrom, Context, Poly, Representation},
+        test_allocator::leaked_canaries,
+    };
+    use ndarray::{s, Array2};
+    use num_bigint::BigUint;
+    use rand::thread_rng;
+    use std::{error::Error, sync::Arc};
+
+    // Moduli to be used in tests.
+    static MODULI: &[u64; 5] = &[
+        1153,
+        4611686018326724609,
+        4611686018309947393,
+        4611686018232352769,
+        4611686018171535361,
+    ];
+
+    #[test]
+    fn extend() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let to = Arc::new(Context::new(MODULI, 16)?);
+        for k in 1..=MODULI.len() {
+            let from = Arc::new(Context::new(&MODULI[..k], 16)?);
+            let extender = Extender::new(&from, &to)?;
+            let p_modulus: BigUint = MODULI[k..].iter().map(|pi| BigUint::from(*pi)).product();
+
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let p = Poly::random(&from, representation.clone(), &mut rng);
+                let extended = extender.extend(&p)?;
+                assert_eq!(extended.ctx(), &to);
+
+                // The original rows are unchanged.
+                assert_eq!(
+                    extended.coefficients.slice(s![..k, ..]),
+                    p.coefficients.view()
+                );
+
+                // The extended polynomial has the same centered coefficients.
+                let mut p_power_basis = p.clone();
+                p_power_basis.change_representation(Representation::PowerBasis);
+                let mut extended_power_basis = extended.clone();
+                extended_power_basis.change_representation(Representation::PowerBasis);
+                assert_eq!(
+                    extended_power_basis.lift_centered(),
+                    p_power_basis.lift_centered()
+                );
+
+                // Multiplying by the new moduli and switching down recovers the
+                // original polynomial exactly.
+                let mut q = &extended_power_basis * &p_modulus;
+                q.mod_switch_down_to(&from)?;
+                assert_eq!(q, p_power_basis);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn extender_constructor() -> Result<(), Box<dyn Error>> {
+        let to = Arc::new(Context::new(MODULI, 16)?);
+
+        // The moduli of `from` must be a prefix of the moduli of `to`.
+        let from = Arc::new(Context::new(&MODULI[1..3], 16)?);
+        assert!(Extender::new(&from, &to).is_err());
+        let from = Arc::new(Context::new(&MODULI[..2], 16)?);
+        assert!(Extender::new(&to, &from).is_err());
+
+        // The degrees must match.
+        let from = Arc::new(Context::new(&MODULI[..2], 8)?);
+        assert!(Extender::new(&from, &to).is_err());
+
+        // The input polynomial must be in the context `from`.
+        let from = Arc::new(Context::new(&MODULI[..2], 16)?);
+        let extender = Extender::new(&from, &to)?;
+        let p = Poly::zero(&to, Representation::PowerBasis);
+        assert!(extender.extend(&p).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn extend_zeroizes_intermediates() -> Result<(), Box<dyn Error>> {
+        let from = Arc::new(Context::new(&MODULI[1..3], 16)?);
+        let to = Arc::new(Context::new(&MODULI[1..], 16)?);
+        let extender = Extender::new(&from, &to)?;
+        let canary = 0x0123456789abcdef;
+        let canaries = [canary, canary];
+        let mut p = Poly::try_convert_from(
+            Array2::from_elem((2, 16), canary),
+            &from,
+            false,
+            Representation::PowerBasis,
+        )?;
+        p.change_representation(Representation::Ntt);
+
+        // The coefficients converted back to PowerBasis representation are
+        // cleared once the polynomial is extended.
+        let leaks = leaked_canaries(canaries, || drop(extender.extend(&p).unwrap()));
+        assert_eq!(leaks, 0);
+
+        Ok(())
+    }
+}
@@ -3,25 +3,46 @@
 //! Polynomials in R_q\[x\] = (ZZ_q1 x ... x ZZ_qn)\[x\] where the qi's are
 //! prime moduli in zq.
 
+mod batch;
+mod builder;
 mod context;
 mod convert;
+mod distributions;
 mod ops;
+mod parallel;
 mod serialize;
 
+pub mod automorphism;
+pub mod extender;
 pub mod scaler;
 pub mod switcher;
 pub mod traits;
-use self::{scaler::Scaler, switcher::Switcher, traits::TryConvertFrom};
-use crate::{Error, Result};
-pub use context::Context;
-use fhe_util::sample_vec_cbd;
+use self::{extender::Extender, scaler::Scaler, switcher::Switcher, traits::TryConvertFrom};
+use crate::{
+    rns::{RnsContext, RnsConverter, ScalingFactor},
+    Error, Result,
+};
+pub use batch::change_representation_batch;
+pub use builder::PolyBuilder;
+pub use context::{Context, ContextBuilder};
+pub use distributions::{CbdPoly, UniformPoly};
+use fhe_util::sample_vec_cbd_from_rng;
 use itertools::{izip, Itertools};
 use ndarray::{s, Array2, ArrayView2, Axis};
-pub use ops::dot_product;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{ToPrimitive, Zero};
+pub use ops::{dot_product, mul_accumulate};
+use parallel::for_each_residue;
+#[cfg(feature = "rayon")]
+pub use parallel::{parallel_threshold, set_parallel_threshold, DEFAULT_PARALLEL_THRESHOLD};
 use rand::{CryptoRng, RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use sha2::{Digest, Sha256};
-use std::sync::Arc;
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+    sync::{Arc, OnceLock},
+};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 use zeroize::{Zeroize, Zeroizing};
 
 /// Possible representations of the underlying polynomial.
@@ -38,6 +59,60 @@ pub enum Representation {
     NttShoup,
 }
 
+impl Representation {
+    /// The name of the representation, as used by `Display` and `FromStr`.
+    const fn name(&self) -> &'static str {
+        match self {
+            Representation::PowerBasis => "power-basis",
+            Representation::Ntt => "ntt",
+            Representation::NttShoup => "ntt-shoup",
+        }
+    }
+}
+
+/// Displays the representation as "power-basis", "ntt", or "ntt-shoup".
+impl Display for Representation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses a representation from its name as displayed, ignoring the case.
+impl FromStr for Representation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ]
+        .into_iter()
+        .find(|r| r.name().eq_ignore_ascii_case(s))
+        .ok_or_else(|| Error::UnknownRepresentation(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Representation {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Representation> for &'static str {
+    fn from(representation: Representation) -> Self {
+        representation.name()
+    }
+}
+
+impl From<&Representation> for &'static str {
+    fn from(representation: &Representation) -> Self {
+        representation.name()
+    }
+}
+
 /// An exponent for a substitution.
 #[derive(Debug, PartialEq, Eq)]
 pub struct SubstitutionExponent {
@@ -75,17 +150,42 @@ impl SubstitutionExponent {
     }
 }
 
+/// A polynomial holding sensitive data, such as secret keys or noise, which
+/// dereferences to [`Poly`] and is zeroized when dropped.
+pub type SensitivePoly = Zeroizing<Poly>;
+
 /// Struct that holds a polynomial for a specific context.
-#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+///
+/// Whether variable time computations are allowed is part of the polynomial:
+/// it is copied by `Clone`, and preserved by the changes of representation.
+///
+/// In NttShoup representation, the Shoup coefficients are computed by
+/// `change_representation`, or else on the first multiplication by the
+/// polynomial; they do not affect equality nor serialization.
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Poly {
     ctx: Arc<Context>,
     representation: Representation,
     has_lazy_coefficients: bool,
     allow_variable_time_computations: bool,
     coefficients: Array2<u64>,
-    coefficients_shoup: Option<Array2<u64>>,
+    #[serde(skip)]
+    coefficients_shoup: OnceLock<Array2<u64>>,
+}
+
+impl PartialEq for Poly {
+    fn eq(&self, other: &Self) -> bool {
+        // The Shoup coefficients are derived from the coefficients.
+        self.ctx == other.ctx
+            && self.representation == other.representation
+            && self.has_lazy_coefficients == other.has_lazy_coefficients
+            && self.allow_variable_time_computations == other.allow_variable_time_computations
+            && self.coefficients == other.coefficients
+    }
 }
 
+impl Eq for Poly {}
+
 // Implements zeroization of polynomials
 impl Zeroize for Poly {
     fn zeroize(&mut self) {
@@ -108,6 +208,90 @@ impl AsMut<Poly> for Poly {
     }
 }
 
+impl Debug for Poly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Poly")
+            .field("representation", &self.representation)
+            .field("degree", &self.degree())
+            .field("moduli", &self.ctx.moduli)
+            .field("has_lazy_coefficients", &self.has_lazy_coefficients)
+            .field(
+                "allow_variable_time_computations",
+                &self.allow_variable_time_computations,
+            )
+            // The coefficients may be secret, and are too large to be logged;
+            // use `Poly::dump_coefficients` to display them.
+            .field("coefficients", &format_args!("<redacted>"))
+            // The Shoup coefficients are derived from the coefficients.
+            .field(
+                "has_coefficients_shoup",
+                &self.coefficients_shoup.get().is_some(),
+            )
+            .finish()
+    }
+}
+
+/// Number of coefficients displayed when the formatter has no precision.
+const DISPLAY_THRESHOLD: usize = 8;
+
+/// Displays the polynomial as `c0 + c1*x + c2*x^2 + ...`, where the
+/// coefficients are centered modulo the modulus of the context, and the zero
+/// coefficients are skipped. Only the coefficients of degree lower than a
+/// threshold are displayed, followed by an ellipsis if the polynomial has a
+/// larger degree; the threshold is 8 by default and can be set using the
+/// precision of the formatter, e.g. `format!("{p:.16}")`.
+///
+/// Unlike `Debug`, this prints the coefficients of the polynomial, so it
+/// must not be used to log secret polynomials.
+impl Display for Poly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let threshold = f.precision().unwrap_or(DISPLAY_THRESHOLD);
+
+        let mut p = Zeroizing::new(self.clone());
+        p.reduce();
+        p.change_representation(Representation::PowerBasis);
+
+        let modulus = self.ctx.modulus();
+        let half_modulus = modulus >> 1;
+        let mut is_first = true;
+        for (i, c) in p
+            .coefficients
+            .axis_iter(Axis(1))
+            .take(threshold)
+            .enumerate()
+        {
+            let c = self.ctx.rns.lift(c);
+            if c.is_zero() {
+                continue;
+            }
+            let (is_negative, c) = if c > half_modulus {
+                (true, modulus - c)
+            } else {
+                (false, c)
+            };
+            match (is_first, is_negative) {
+                (true, true) => write!(f, "-")?,
+                (true, false) => {}
+                (false, true) => write!(f, " - ")?,
+                (false, false) => write!(f, " + ")?,
+            }
+            match i {
+                0 => write!(f, "{c}")?,
+                1 => write!(f, "{c}*x")?,
+                _ => write!(f, "{c}*x^{i}")?,
+            }
+            is_first = false;
+        }
+        if is_first {
+            write!(f, "0")?;
+        }
+        if self.degree() > threshold {
+            write!(f, " + ...")?;
+        }
+        Ok(())
+    }
+}
+
 impl Poly {
     /// Creates a polynomial holding the constant 0.
     pub fn zero(ctx: &Arc<Context>, representation: Representation) -> Self {
@@ -117,12 +301,118 @@ impl Poly {
             allow_variable_time_computations: false,
             has_lazy_coefficients: false,
             coefficients: Array2::zeros((ctx.q.len(), ctx.degree)),
-            coefficients_shoup: if representation == Representation::NttShoup {
-                Some(Array2::zeros((ctx.q.len(), ctx.degree)))
-            } else {
-                None
-            },
+            coefficients_shoup: OnceLock::new(),
+        }
+    }
+
+    /// Creates a polynomial holding the constant 0, with the same context and
+    /// representation as `other`. Variable time computations are not allowed
+    /// on the output, even if they are allowed for `other`.
+    pub fn zero_like(other: &Poly) -> Self {
+        Self::zero(other.ctx(), other.representation().clone())
+    }
+
+    /// Creates a polynomial from coefficients in RNS representation that may be
+    /// shared, e.g., between the polynomials of a public batch.
+    ///
+    /// The coefficients are moved without copying when `coefficients` is the
+    /// only reference to the array, and copied once otherwise; as for the
+    /// conversion from an `Array2<u64>`, returns an error if the shape of the
+    /// array does not match the context.
+    pub fn from_shared(
+        coefficients: Arc<Array2<u64>>,
+        ctx: &Arc<Context>,
+        variable_time: bool,
+        representation: Representation,
+    ) -> Result<Self> {
+        if coefficients.shape() != [ctx.q.len(), ctx.degree] {
+            return Err(Error::InvalidShape(
+                coefficients.shape().to_vec(),
+                vec![ctx.q.len(), ctx.degree],
+            ));
+        }
+        let coefficients = Arc::try_unwrap(coefficients).unwrap_or_else(|a| a.as_ref().clone());
+        Poly::try_convert_from(coefficients, ctx, variable_time, representation)
+    }
+
+    /// Creates the monomial coeff * x^exponent.
+    ///
+    /// The exponent is reduced modulo 2 * degree, and since x^degree = -1, the
+    /// sign of the coefficient is flipped when the reduced exponent is larger or
+    /// equal to the degree.
+    pub fn monomial(
+        ctx: &Arc<Context>,
+        representation: Representation,
+        exponent: usize,
+        coeff: i64,
+    ) -> Self {
+        let mut p = Poly::zero(ctx, Representation::PowerBasis);
+        let exponent = exponent % (2 * ctx.degree);
+        let (index, negate) = if exponent >= ctx.degree {
+            (exponent - ctx.degree, true)
+        } else {
+            (exponent, false)
+        };
+        izip!(p.coefficients.outer_iter_mut(), ctx.q.iter()).for_each(|(mut v, qi)| {
+            let c = qi.reduce_i64(coeff);
+            v[index] = if negate { qi.neg(c) } else { c };
+        });
+        p.change_representation(representation);
+        p
+    }
+
+    /// Creates a polynomial from an iterator of coefficients, without collecting
+    /// them in an intermediate vector.
+    ///
+    /// In Ntt and NttShoup representations, the iterator must yield all the
+    /// `moduli.len() * degree` coefficients, modulus by modulus. In PowerBasis
+    /// representation, it can also yield at most `degree` coefficients, which
+    /// are then reduced modulo each of the moduli.
+    pub fn from_coefficients_iter<I: IntoIterator<Item = u64>>(
+        iter: I,
+        ctx: &Arc<Context>,
+        representation: Representation,
+    ) -> Result<Self> {
+        let mut coefficients = Array2::zeros((ctx.q.len(), ctx.degree));
+        let mut iter = iter.into_iter();
+        let mut len = 0;
+        izip!(coefficients.iter_mut(), iter.by_ref()).for_each(|(c, v)| {
+            *c = v;
+            len += 1;
+        });
+
+        if iter.next().is_some() {
+            return Err(Error::TooManyValues(coefficients.len()));
+        }
+
+        match representation {
+            Representation::PowerBasis => {
+                if len <= ctx.degree {
+                    let (first, mut others) = coefficients.view_mut().split_at(Axis(0), 1);
+                    others
+                        .outer_iter_mut()
+                        .for_each(|mut row| row.assign(&first.row(0)));
+                } else if len != coefficients.len() {
+                    return Err(Error::TooManyValues(ctx.degree));
+                }
+                izip!(coefficients.outer_iter_mut(), ctx.q.iter())
+                    .for_each(|(row, qi)| qi.reduce_vec_strided(row));
+            }
+            _ => {
+                if len != coefficients.len() {
+                    return Err(Error::InvalidLength(len, coefficients.len()));
+                }
+            }
         }
+
+        Ok(Self {
+            ctx: ctx.clone(),
+            representation,
+            allow_variable_time_computations: false,
+            has_lazy_coefficients: false,
+            coefficients,
+            coefficients_shoup: OnceLock::new(),
+        })
     }
 
     /// Enable variable time computations when this polynomial is involved.
@@ -140,6 +430,53 @@ impl Poly {
         self.allow_variable_time_computations = false
     }
 
+    /// Returns whether variable time computations are allowed when this
+    /// polynomial is involved.
+    pub const fn allows_variable_time(&self) -> bool {
+        self.allow_variable_time_computations
+    }
+
+    /// Clone the polynomial, and allow variable time computations on the
+    /// clone; the polynomial itself is unchanged.
+    ///
+    /// # Safety
+    ///
+    /// As for [`Poly::allow_variable_time_computations`], this should only be
+    /// used when the polynomial holds public data.
+    pub unsafe fn clone_public(&self) -> Poly {
+        let mut p = self.clone();
+        p.allow_variable_time_computations = true;
+        p
+    }
+
+    /// Run `f` on this polynomial with variable time computations enabled,
+    /// and restore the previous setting afterwards, including when `f` panics.
+    ///
+    /// The caller must ensure that `f` only processes public data: as for
+    /// [`Poly::allow_variable_time_computations`], the computations in `f`
+    /// may leak the coefficients of the polynomial through timing. Scoping
+    /// the flag to `f` makes it impossible to forget to reset it.
+    pub fn with_variable_time<R>(&mut self, f: impl FnOnce(&mut Poly) -> R) -> R {
+        struct Guard<'a> {
+            poly: &'a mut Poly,
+            previous: bool,
+        }
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.poly.allow_variable_time_computations = self.previous
+            }
+        }
+
+        let previous = self.allow_variable_time_computations;
+        self.allow_variable_time_computations = true;
+        let guard = Guard {
+            poly: self,
+            previous,
+        };
+        f(guard.poly)
+    }
+
     /// Current representation of the polynomial.
     pub const fn representation(&self) -> &Representation {
         &self.representation
@@ -149,7 +486,7 @@ impl Poly {
     fn zeroize_shoup(&mut self) {
         if let Some(coeffs_shoup) = self
             .coefficients_shoup
-            .as_mut()
+            .get_mut()
             .and_then(|f| f.as_slice_mut())
         {
             coeffs_shoup.zeroize()
@@ -157,58 +494,238 @@ impl Poly {
     }
 
     /// Change the representation of the underlying polynomial.
+    ///
+    /// When the polynomial allows variable time computations, the variable
+    /// time NTTs are used; the resulting coefficients are identical to the
+    /// ones of the constant time NTTs. Whether variable time computations are
+    /// allowed is unchanged.
+    ///
+    /// Changing to NttShoup representation computes the Shoup coefficients
+    /// now, including when the polynomial is already in NttShoup
+    /// representation; otherwise, they are computed on the first
+    /// multiplication by the polynomial.
+    ///
+    /// Panics if the polynomial has lazy coefficients and `to` differs from
+    /// its current representation; see [`Poly::try_change_representation`].
     pub fn change_representation(&mut self, to: Representation) {
-        match self.representation {
-            Representation::PowerBasis => {
-                match to {
-                    Representation::Ntt => self.ntt_forward(),
-                    Representation::NttShoup => {
-                        self.ntt_forward();
-                        self.compute_coefficients_shoup();
-                    }
-                    Representation::PowerBasis => {} // no-op
+        self.try_change_representation(to).unwrap()
+    }
+
+    /// Change the representation of the underlying polynomial, without
+    /// panicking.
+    ///
+    /// Returns an error if the polynomial has lazy coefficients and `to`
+    /// differs from its current representation, since the NTTs and the Shoup
+    /// precomputations require reduced coefficients. Changing to the current
+    /// representation always succeeds.
+    pub fn try_change_representation(&mut self, to: Representation) -> Result<()> {
+        if self.has_lazy_coefficients && self.representation != to {
+            return Err(Error::InvalidTransition(self.representation.clone(), to));
+        }
+
+        match (&self.representation, &to) {
+            (Representation::PowerBasis, Representation::PowerBasis)
+            | (Representation::Ntt, Representation::Ntt) => {} // no-op
+            (Representation::NttShoup, Representation::NttShoup) => {
+                // Compute the Shoup coefficients now if they were not yet.
+                if self.coefficients_shoup.get().is_none() {
+                    self.compute_coefficients_shoup()
                 }
             }
-            Representation::Ntt => {
-                match to {
-                    Representation::PowerBasis => self.ntt_backward(),
-                    Representation::NttShoup => self.compute_coefficients_shoup(),
-                    Representation::Ntt => {} // no-op
-                }
+            (Representation::PowerBasis, Representation::Ntt) => self.ntt_forward(),
+            (Representation::PowerBasis, Representation::NttShoup) => {
+                self.ntt_forward();
+                self.compute_coefficients_shoup();
             }
-            Representation::NttShoup => {
-                if to != Representation::NttShoup {
-                    // We are not sure whether this polynomial was sensitive or not,
-                    // so for security, we zeroize the Shoup coefficients.
-                    self.zeroize_shoup();
-                    self.coefficients_shoup = None
-                }
-                match to {
-                    Representation::PowerBasis => self.ntt_backward(),
-                    Representation::Ntt => {}      // no-op
-                    Representation::NttShoup => {} // no-op
-                }
+            (Representation::Ntt, Representation::PowerBasis) => self.ntt_backward(),
+            (Representation::Ntt, Representation::NttShoup) => self.compute_coefficients_shoup(),
+            (Representation::NttShoup, Representation::PowerBasis) => {
+                self.drop_shoup();
+                self.ntt_backward();
             }
+            (Representation::NttShoup, Representation::Ntt) => self.drop_shoup(),
         }
 
         self.representation = to;
+        self.assert_consistent();
+        Ok(())
     }
 
-    /// Compute the Shoup representation of the coefficients.
-    fn compute_coefficients_shoup(&mut self) {
-        let mut coefficients_shoup = Array2::zeros((self.ctx.q.len(), self.ctx.degree));
+    /// Drop the Shoup coefficients.
+    fn drop_shoup(&mut self) {
+        // We are not sure whether this polynomial was sensitive or not,
+        // so for security, we zeroize the Shoup coefficients.
+        self.zeroize_shoup();
+        self.coefficients_shoup.take();
+    }
+
+    /// Change the representation of a polynomial to Ntt and multiply it by p.
+    ///
+    /// This is equivalent to `change_representation(Representation::Ntt)`
+    /// followed by `*self *= p`. When the polynomial is in PowerBasis
+    /// representation and p is in NttShoup representation, the final
+    /// reduction of the NTT is skipped since the Shoup multiplication accepts
+    /// unreduced inputs; the output coefficients are fully reduced.
+    pub fn change_representation_and_mul(&mut self, p: &Poly) {
+        if self.representation == Representation::PowerBasis
+            && p.representation == Representation::NttShoup
+        {
+            self.ntt_forward_lazy();
+            self.representation = Representation::Ntt;
+            self.has_lazy_coefficients = true;
+        } else {
+            self.change_representation(Representation::Ntt);
+        }
+        *self *= p;
+    }
+
+    /// Multiply a polynomial by p in any representation.
+    ///
+    /// The polynomials are converted to Ntt representation if needed, and the
+    /// product is converted back to the original representation of `self`;
+    /// the representation of p is left unchanged.
+    ///
+    /// Returns an error if the polynomials have different contexts or if any of
+    /// them has lazy coefficients.
+    pub fn mul_promoting(&mut self, p: &Poly) -> Result<()> {
+        if self.ctx != p.ctx {
+            return Err(Error::InvalidContext);
+        }
+        if self.has_lazy_coefficients || p.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+
+        let representation = self.representation.clone();
+        self.change_representation(Representation::Ntt);
+        if p.representation == Representation::PowerBasis {
+            let mut p_ntt = Zeroizing::new(p.clone());
+            p_ntt.change_representation(Representation::Ntt);
+            *self *= p_ntt.as_ref();
+        } else {
+            *self *= p;
+        }
+        self.change_representation(representation);
+        Ok(())
+    }
+
+    /// Add p to a polynomial, where the polynomials may be in Ntt or NttShoup
+    /// representations.
+    ///
+    /// If `self` is in NttShoup representation, it is first dropped to Ntt
+    /// representation, and its Shoup coefficients are zeroized. When
+    /// `recompute_shoup` is true, the polynomial is converted back to NttShoup
+    /// representation after the addition, which costs an additional modular
+    /// multiplication per coefficient; otherwise, it is left in Ntt
+    /// representation. Polynomials in PowerBasis representation are simply
+    /// added.
+    ///
+    /// Returns an error if the polynomials have different contexts, if any of
+    /// them has lazy coefficients, or if only one of them is in PowerBasis
+    /// representation.
+    pub fn add_assign_promoting(&mut self, p: &Poly, recompute_shoup: bool) -> Result<()> {
+        if self.ctx != p.ctx {
+            return Err(Error::InvalidContext);
+        }
+        if self.has_lazy_coefficients || p.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+        if (self.representation == Representation::PowerBasis)
+            != (p.representation == Representation::PowerBasis)
+        {
+            return Err(Error::IncorrectRepresentation(
+                p.representation.clone(),
+                self.representation.clone(),
+            ));
+        }
+
+        let was_ntt_shoup = self.representation == Representation::NttShoup;
+        if was_ntt_shoup {
+            self.change_representation(Representation::Ntt);
+        }
+
+        self.allow_variable_time_computations |= p.allow_variable_time_computations;
         izip!(
-            coefficients_shoup.outer_iter_mut(),
-            self.coefficients.outer_iter(),
+            self.coefficients.outer_iter_mut(),
+            p.coefficients.outer_iter(),
             self.ctx.q.iter()
         )
-        .for_each(|(mut v_shoup, v, qi)| {
-            v_shoup
-                .as_slice_mut()
-                .unwrap()
-                .copy_from_slice(&qi.shoup_vec(v.as_slice().unwrap()))
+        .for_each(|(v1, v2, qi)| {
+            if self.allow_variable_time_computations {
+                unsafe { qi.add_vec_strided_vt(v1, v2) }
+            } else {
+                qi.add_vec_strided(v1, v2)
+            }
+        });
+
+        if was_ntt_shoup && recompute_shoup {
+            self.change_representation(Representation::NttShoup);
+        }
+        Ok(())
+    }
+
+    /// Fully reduce the coefficients of a polynomial with lazy coefficients.
+    ///
+    /// This is a no-op if the coefficients are already reduced.
+    pub fn reduce(&mut self) {
+        if self.has_lazy_coefficients {
+            izip!(self.coefficients.outer_iter_mut(), self.ctx.q.iter())
+                .for_each(|(v, qi)| qi.reduce_vec_strided(v));
+            self.has_lazy_coefficients = false
+        }
+    }
+
+    /// Compute the Shoup representation of the coefficients now, instead of
+    /// on the first multiplication by the polynomial.
+    fn compute_coefficients_shoup(&mut self) {
+        // The Shoup coefficients are computed in place, so that no temporary
+        // copy of them is left unzeroized in memory; an existing buffer of the
+        // right shape is overwritten instead of being reallocated.
+        let shape = (self.ctx.q.len(), self.ctx.degree);
+        if self
+            .coefficients_shoup
+            .get()
+            .is_some_and(|c| c.dim() != shape)
+        {
+            self.drop_shoup()
+        }
+        if self.coefficients_shoup.get().is_none() {
+            let _ = self.coefficients_shoup.set(Array2::zeros(shape));
+        }
+        Self::fill_coefficients_shoup(
+            self.coefficients_shoup.get_mut().unwrap(),
+            &self.coefficients,
+            &self.ctx,
+        )
+    }
+
+    /// Recompute the Shoup coefficients after the coefficients changed, if
+    /// they were already computed.
+    fn refresh_coefficients_shoup(&mut self) {
+        if self.coefficients_shoup.get().is_some() {
+            self.compute_coefficients_shoup()
+        }
+    }
+
+    /// Returns the Shoup coefficients of a polynomial in NttShoup
+    /// representation, which are computed on the first call.
+    pub(crate) fn coefficients_shoup(&self) -> &Array2<u64> {
+        debug_assert_eq!(self.representation, Representation::NttShoup);
+        self.coefficients_shoup.get_or_init(|| {
+            let mut coefficients_shoup = Array2::zeros((self.ctx.q.len(), self.ctx.degree));
+            Self::fill_coefficients_shoup(&mut coefficients_shoup, &self.coefficients, &self.ctx);
+            coefficients_shoup
+        })
+    }
+
+    fn fill_coefficients_shoup(
+        coefficients_shoup: &mut Array2<u64>,
+        coefficients: &Array2<u64>,
+        ctx: &Context,
+    ) {
+        for_each_residue(coefficients_shoup, |i, mut v_shoup| {
+            izip!(v_shoup.iter_mut(), coefficients.row(i).iter())
+                .for_each(|(s, c)| *s = ctx.q[i].shoup(*c))
         });
-        self.coefficients_shoup = Some(coefficients_shoup)
     }
 
     /// Override the internal representation to a given representation.
@@ -217,86 +734,178 @@ impl Poly {
     ///
     /// Prefer the `change_representation` function to safely modify the
     /// polynomial representation. If the `to` representation is NttShoup, the
-    /// coefficients are still computed correctly to avoid being in an unstable
-    /// state. If we override a polynomial with Shoup coefficients, we zeroize
-    /// them.
+    /// Shoup coefficients are computed from the coefficients when first used,
+    /// to avoid being in an unstable state. If we override a polynomial with
+    /// Shoup coefficients, we zeroize them.
     pub unsafe fn override_representation(&mut self, to: Representation) {
-        if self.coefficients_shoup.is_some() {
-            self.zeroize_shoup();
-            self.coefficients_shoup = None
+        self.drop_shoup();
+        self.representation = to;
+    }
+
+    /// Panics if the polynomial violates the invariants of its internal
+    /// state, e.g., after a misuse of [`Poly::override_representation`]. This
+    /// is a no-op unless debug assertions are enabled.
+    ///
+    /// The invariants are that the coefficients have one row of `degree`
+    /// coefficients per modulus, that only a polynomial in Ntt representation
+    /// has lazy coefficients, that the Shoup coefficients, when computed,
+    /// belong to a polynomial in NttShoup representation and have the same
+    /// shape as the coefficients, and that the coefficients are reduced unless
+    /// they are lazy.
+    pub fn assert_consistent(&self) {
+        if cfg!(debug_assertions) {
+            if let Some(inconsistency) = self.inconsistency() {
+                panic!("Inconsistent polynomial: {inconsistency}")
+            }
         }
-        if to == Representation::NttShoup {
-            self.compute_coefficients_shoup()
+    }
+
+    /// Returns a description of the first violated invariant of
+    /// [`Poly::assert_consistent`], if any.
+    fn inconsistency(&self) -> Option<String> {
+        let shape = (self.ctx.q.len(), self.ctx.degree);
+        if self.coefficients.dim() != shape {
+            return Some(format!(
+                "the coefficients have shape {:?} instead of {shape:?}",
+                self.coefficients.dim()
+            ));
         }
-        self.representation = to;
+        if self.has_lazy_coefficients && self.representation != Representation::Ntt {
+            return Some(format!(
+                "lazy coefficients in {:?} representation",
+                self.representation
+            ));
+        }
+        if let Some(coefficients_shoup) = self.coefficients_shoup.get() {
+            if self.representation != Representation::NttShoup {
+                return Some(format!(
+                    "Shoup coefficients in {:?} representation",
+                    self.representation
+                ));
+            }
+            if coefficients_shoup.dim() != shape {
+                return Some(format!(
+                    "the Shoup coefficients have shape {:?} instead of {shape:?}",
+                    coefficients_shoup.dim()
+                ));
+            }
+        }
+        if !self.has_lazy_coefficients {
+            for (i, (v, qi)) in izip!(self.coefficients.outer_iter(), self.ctx.q.iter()).enumerate()
+            {
+                if let Some(c) = v.iter().find(|c| **c >= **qi) {
+                    return Some(format!(
+                        "the coefficient {c} of residue {i} is not reduced modulo {}",
+                        **qi
+                    ));
+                }
+            }
+        }
+        None
     }
 
     /// Generate a random polynomial.
+    ///
+    /// This is equivalent to `random_from_rng`.
     pub fn random<R: RngCore + CryptoRng>(
         ctx: &Arc<Context>,
         representation: Representation,
         rng: &mut R,
+    ) -> Self {
+        Self::random_from_rng(ctx, representation, rng)
+    }
+
+    /// Generate a random polynomial with the same context and representation
+    /// as `other`. As for [`Poly::zero_like`], variable time computations are
+    /// not allowed on the output.
+    pub fn random_like<R: RngCore + CryptoRng>(other: &Poly, rng: &mut R) -> Self {
+        Self::random(other.ctx(), other.representation().clone(), rng)
+    }
+
+    /// Generate a random polynomial from the random number generator `rng`.
+    ///
+    /// For each channel, a sub-seed is drawn from `rng`, and the channel is
+    /// filled with
+    /// [`Modulus::random_vec_from_rng`](crate::zq::Modulus::random_vec_from_rng)
+    /// from a `ChaCha8Rng` seeded with this sub-seed. The output is
+    /// deterministic given the state of `rng`, so the same generator can be
+    /// shared with other components; in particular, a `ChaCha8Rng` seeded with
+    /// `seed` reproduces [`Poly::random_from_seed`] with the same seed.
+    pub fn random_from_rng<R: RngCore + CryptoRng>(
+        ctx: &Arc<Context>,
+        representation: Representation,
+        rng: &mut R,
     ) -> Self {
         let mut p = Poly::zero(ctx, representation);
         izip!(p.coefficients.outer_iter_mut(), ctx.q.iter()).for_each(|(mut v, qi)| {
+            let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
+            rng.fill_bytes(&mut seed);
+            let mut channel_rng = ChaCha8Rng::from_seed(seed);
             v.as_slice_mut()
                 .unwrap()
-                .copy_from_slice(&qi.random_vec(ctx.degree, rng))
+                .copy_from_slice(&qi.random_vec_from_rng(ctx.degree, &mut channel_rng))
         });
-        if p.representation == Representation::NttShoup {
-            p.compute_coefficients_shoup()
-        }
         p
     }
 
+    /// Generate a pair of independent random polynomials in the same
+    /// representation, sampled from the same thread-local random number
+    /// generator. This is meant to standardize the setup of tests and
+    /// benchmarks.
+    #[cfg(any(test, feature = "bench"))]
+    pub fn random_pair(ctx: &Arc<Context>, representation: Representation) -> (Self, Self) {
+        let mut rng = rand::thread_rng();
+        (
+            Self::random(ctx, representation.clone(), &mut rng),
+            Self::random(ctx, representation, &mut rng),
+        )
+    }
+
     /// Generate a random polynomial deterministically from a seed.
+    ///
+    /// This is guaranteed to be equivalent to [`Poly::random_from_rng`] with
+    /// a `ChaCha8Rng` seeded by the seed, i.e., with
+    /// `ChaCha8Rng::from_seed(seed)`.
     pub fn random_from_seed(
         ctx: &Arc<Context>,
         representation: Representation,
         seed: <ChaCha8Rng as SeedableRng>::Seed,
     ) -> Self {
-        // Let's hash the seed into a ChaCha8Rng seed.
-        let mut hasher = Sha256::new();
-        hasher.update(seed);
-        let mut prng =
-            ChaCha8Rng::from_seed(<ChaCha8Rng as SeedableRng>::Seed::from(hasher.finalize()));
-        let mut p = Poly::zero(ctx, representation);
-        izip!(p.coefficients.outer_iter_mut(), ctx.q.iter()).for_each(|(mut v, qi)| {
-            v.as_slice_mut()
-                .unwrap()
-                .copy_from_slice(&qi.random_vec(ctx.degree, &mut prng))
-        });
-        if p.representation == Representation::NttShoup {
-            p.compute_coefficients_shoup()
-        }
-        p
+        Self::random_from_rng(ctx, representation, &mut Self::seeded_rng(seed))
+    }
+
+    /// Returns the `ChaCha8Rng` seeded by the seed.
+    fn seeded_rng(seed: <ChaCha8Rng as SeedableRng>::Seed) -> ChaCha8Rng {
+        ChaCha8Rng::from_seed(seed)
     }
 
     /// Generate a small polynomial and convert into the specified
     /// representation.
     ///
+    /// The polynomial is returned as a [`SensitivePoly`], which is zeroized
+    /// when dropped.
+    ///
     /// Returns an error if the variance does not belong to [1, ..., 16].
     pub fn small<T: RngCore + CryptoRng>(
         ctx: &Arc<Context>,
         representation: Representation,
         variance: usize,
         rng: &mut T,
-    ) -> Result<Self> {
+    ) -> Result<SensitivePoly> {
         if !(1..=16).contains(&variance) {
-            Err(Error::Default(
+            Err(Error::Sampling(
                 "The variance should be an integer between 1 and 16".to_string(),
             ))
         } else {
-            let coeffs = Zeroizing::new(
-                sample_vec_cbd(ctx.degree, variance, rng)
-                    .map_err(|e| Error::Default(e.to_string()))?,
-            );
+            let coeffs = sample_vec_cbd_from_rng(ctx.degree, variance, rng)
+                .map_err(|e| Error::Sampling(e.to_string()))?;
             let mut p = Poly::try_convert_from(
                 coeffs.as_ref() as &[i64],
                 ctx,
                 false,
                 Representation::PowerBasis,
-            )?;
+            )?
+            .into_sensitive();
             if representation != Representation::PowerBasis {
                 p.change_representation(representation);
             }
@@ -304,39 +913,139 @@ impl Poly {
         }
     }
 
+    /// Generate a polynomial with coefficients sampled uniformly in the
+    /// interval [-bound, bound] using rejection sampling, and convert it into
+    /// the specified representation.
+    ///
+    /// The polynomial is returned as a [`SensitivePoly`], which is zeroized
+    /// when dropped.
+    ///
+    /// Returns an error if the bound is zero, or if it is larger or equal to
+    /// half of the smallest modulus of the context.
+    pub fn random_bounded<T: RngCore + CryptoRng>(
+        ctx: &Arc<Context>,
+        representation: Representation,
+        bound: u64,
+        rng: &mut T,
+    ) -> Result<SensitivePoly> {
+        if bound == 0 {
+            return Err(Error::Sampling("The bound should be nonzero".to_string()));
+        }
+        if ctx.moduli.iter().any(|qi| bound >= qi / 2) {
+            return Err(Error::Sampling(
+                "The bound should be smaller than half of the smallest modulus".to_string(),
+            ));
+        }
+
+        // Sample uniformly in [0, 2 * bound] by rejecting the values larger than
+        // 2 * bound among the uniform values with the same number of bits.
+        let width = 2 * bound + 1;
+        let mask = u64::MAX >> width.leading_zeros();
+        let mut coeffs = Zeroizing::new(vec![0i64; ctx.degree]);
+        for c in coeffs.iter_mut() {
+            let mut x = rng.next_u64() & mask;
+            while x >= width {
+                x = rng.next_u64() & mask;
+            }
+            *c = x as i64 - bound as i64;
+        }
+
+        let mut p = Poly::try_convert_from(
+            coeffs.as_ref() as &[i64],
+            ctx,
+            false,
+            Representation::PowerBasis,
+        )?
+        .into_sensitive();
+        if representation != Representation::PowerBasis {
+            p.change_representation(representation);
+        }
+        Ok(p)
+    }
+
     /// Access the polynomial coefficients in RNS representation.
     pub fn coefficients(&self) -> ArrayView2<u64> {
         self.coefficients.view()
     }
 
+    /// Consumes the polynomial and returns its coefficients in RNS
+    /// representation, without copying them.
+    ///
+    /// For a polynomial in NttShoup representation, only the coefficients in
+    /// Ntt representation are returned; the Shoup coefficients are zeroized
+    /// and dropped.
+    pub fn into_coefficients(mut self) -> Array2<u64> {
+        self.zeroize_shoup();
+        self.coefficients
+    }
+
+    /// Consumes the polynomial and returns it as a [`SensitivePoly`], which
+    /// is zeroized when dropped.
+    pub fn into_sensitive(self) -> SensitivePoly {
+        Zeroizing::new(self)
+    }
+
     /// Computes the forward Ntt on the coefficients
     fn ntt_forward(&mut self) {
+        let ops = &self.ctx.ops;
         if self.allow_variable_time_computations {
-            izip!(self.coefficients.outer_iter_mut(), self.ctx.ops.iter())
-                .for_each(|(mut v, op)| unsafe { op.forward_vt(v.as_mut_ptr()) });
+            for_each_residue(&mut self.coefficients, |i, mut v| unsafe {
+                ops[i].forward_vt(v.as_mut_ptr())
+            });
         } else {
-            izip!(self.coefficients.outer_iter_mut(), self.ctx.ops.iter())
-                .for_each(|(mut v, op)| op.forward(v.as_slice_mut().unwrap()));
+            for_each_residue(&mut self.coefficients, |i, mut v| {
+                ops[i].forward(v.as_slice_mut().unwrap())
+            });
+        }
+    }
+
+    /// Computes the forward Ntt on the coefficients in a lazy fashion; the
+    /// output coefficients are not fully reduced.
+    fn ntt_forward_lazy(&mut self) {
+        let ops = &self.ctx.ops;
+        if self.allow_variable_time_computations {
+            for_each_residue(&mut self.coefficients, |i, mut v| unsafe {
+                ops[i].forward_vt_lazy(v.as_mut_ptr())
+            });
+        } else {
+            for_each_residue(&mut self.coefficients, |i, mut v| {
+                ops[i].forward_lazy(v.as_slice_mut().unwrap())
+            });
         }
     }
 
     /// Computes the backward Ntt on the coefficients
     fn ntt_backward(&mut self) {
+        let ops = &self.ctx.ops;
         if self.allow_variable_time_computations {
-            izip!(self.coefficients.outer_iter_mut(), self.ctx.ops.iter())
-                .for_each(|(mut v, op)| unsafe { op.backward_vt(v.as_mut_ptr()) });
+            for_each_residue(&mut self.coefficients, |i, mut v| unsafe {
+                ops[i].backward_vt(v.as_mut_ptr())
+            });
         } else {
-            izip!(self.coefficients.outer_iter_mut(), self.ctx.ops.iter())
-                .for_each(|(mut v, op)| op.backward(v.as_slice_mut().unwrap()));
+            for_each_residue(&mut self.coefficients, |i, mut v| {
+                ops[i].backward(v.as_slice_mut().unwrap())
+            });
         }
     }
 
+    /// Substitute x by x^(-1) = x^(2 * degree - 1) in a polynomial, which
+    /// corresponds to the complex conjugation of the slots in CKKS.
+    ///
+    /// This is equivalent to [`Poly::substitute`] with the exponent
+    /// 2 * degree - 1, and is its own inverse.
+    pub fn conjugate(&self) -> Result<Poly> {
+        self.substitute(&SubstitutionExponent::new(
+            &self.ctx,
+            2 * self.ctx.degree - 1,
+        )?)
+    }
+
     /// Substitute x by x^i in a polynomial.
     /// In PowerBasis representation, i can be any integer that is not a
     /// multiple of 2 * degree. In Ntt and NttShoup representation, i can be any
     /// odd integer that is not a multiple of 2 * degree.
     pub fn substitute(&self, i: &SubstitutionExponent) -> Result<Poly> {
-        let mut q = Poly::zero(&self.ctx, self.representation.clone());
+        let mut q = Poly::zero_like(self);
         if self.allow_variable_time_computations {
             unsafe { q.allow_variable_time_computations() }
         }
@@ -362,15 +1071,17 @@ impl Poly {
                         q_row[*j] = p_row[*k]
                     }
                 });
-                izip!(
-                    q.coefficients_shoup.as_mut().unwrap().outer_iter_mut(),
-                    self.coefficients_shoup.as_ref().unwrap().outer_iter()
-                )
-                .for_each(|(mut q_row, p_row)| {
-                    for (j, k) in izip!(self.ctx.bitrev.iter(), i.power_bitrev.iter()) {
-                        q_row[*j] = p_row[*k]
-                    }
-                });
+                if let Some(p_shoup) = self.coefficients_shoup.get() {
+                    let mut q_shoup = Array2::zeros(p_shoup.dim());
+                    izip!(q_shoup.outer_iter_mut(), p_shoup.outer_iter()).for_each(
+                        |(mut q_row, p_row)| {
+                            for (j, k) in izip!(self.ctx.bitrev.iter(), i.power_bitrev.iter()) {
+                                q_row[*j] = p_row[*k]
+                            }
+                        },
+                    );
+                    q.coefficients_shoup = OnceLock::from(q_shoup);
+                }
             }
             Representation::PowerBasis => {
                 let mut power = 0usize;
@@ -421,7 +1132,7 @@ impl Poly {
             representation: Representation::Ntt,
             allow_variable_time_computations: true,
             coefficients,
-            coefficients_shoup: None,
+            coefficients_shoup: OnceLock::new(),
             has_lazy_coefficients: true,
         }
     }
@@ -433,7 +1144,27 @@ impl Poly {
     /// Returns an error if there is no next context or if the representation
     /// is not PowerBasis.
     pub fn mod_switch_down_next(&mut self) -> Result<()> {
-        if self.ctx.next_context.is_none() {
+        self.mod_switch_down_next_inner(false).map(|_| ())
+    }
+
+    /// Modulus switch down the polynomial as in
+    /// [`Poly::mod_switch_down_next`], and returns the infinity norm of the
+    /// rounding error round(p / q_last) * q_last - p introduced by the switch.
+    ///
+    /// This is a diagnostic to measure the noise added by modulus switching;
+    /// note that the returned value depends on the coefficients of the
+    /// polynomial.
+    ///
+    /// Returns an error if there is no next context or if the representation
+    /// is not PowerBasis.
+    pub fn mod_switch_down_next_with_error(&mut self) -> Result<BigUint> {
+        self.mod_switch_down_next_inner(true).map(BigUint::from)
+    }
+
+    /// Modulus switch down the polynomial, and returns the infinity norm of the
+    /// rounding error when `with_error` is set, or 0 otherwise.
+    fn mod_switch_down_next_inner(&mut self, with_error: bool) -> Result<u64> {
+        if self.ctx.next_context().is_none() {
             return Err(Error::NoMoreContext);
         }
 
@@ -445,7 +1176,7 @@ impl Poly {
         }
 
         // Unwrap the next_context.
-        let next_context = self.ctx.next_context.as_ref().unwrap();
+        let next_context = self.ctx.next_context().unwrap();
 
         let q_len = self.ctx.q.len();
         let q_last = self.ctx.q.last().unwrap();
@@ -507,6 +1238,17 @@ impl Poly {
             });
         }
 
+        // The last row now contains r = (x + q_L/2) mod q_last, and the rounding
+        // error is round(x / q_last) * q_last - x = q_L/2 - r.
+        let error = if with_error {
+            q_last_poly
+                .iter()
+                .map(|r| q_last_div_2.abs_diff(*r))
+                .fold(0, u64::max)
+        } else {
+            0
+        };
+
         // Remove the last row, and update the context.
         if !self.allow_variable_time_computations {
             q_last_poly.as_slice_mut().unwrap().zeroize();
@@ -514,7 +1256,42 @@ impl Poly {
         self.coefficients.remove_index(Axis(0), q_len - 1);
         self.ctx = next_context.clone();
 
-        Ok(())
+        Ok(error)
+    }
+
+    /// Divide the polynomial by the last modulus in the chain, when it divides
+    /// all the coefficients exactly, and output the result in the next
+    /// context; the division is exact, without rounding, and is computed with
+    /// [`RnsContext::divide_exact_many`](crate::rns::RnsContext::divide_exact_many).
+    ///
+    /// Returns None if the last modulus does not divide all the coefficients,
+    /// or an error if there is no next context, if the representation is
+    /// NttShoup, or if the polynomial has lazy coefficients.
+    pub fn divide_exact_by_last_modulus(&self) -> Result<Option<Poly>> {
+        if self.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+        let next_context = self.ctx.next_context().ok_or(Error::NoMoreContext)?;
+        if self.representation == Representation::NttShoup {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::Ntt,
+            ));
+        }
+
+        // The division is linear, so it applies to the NTT of the coefficients.
+        let quotients = self
+            .ctx
+            .rns
+            .divide_exact_many(self.coefficients.view(), self.ctx.q.len() - 1)?;
+        Ok(quotients.map(|coefficients| Poly {
+            ctx: next_context.clone(),
+            representation: self.representation.clone(),
+            allow_variable_time_computations: self.allow_variable_time_computations,
+            coefficients,
+            coefficients_shoup: OnceLock::new(),
+            has_lazy_coefficients: false,
+        }))
     }
 
     /// Modulo switch down to a smaller context.
@@ -531,6 +1308,120 @@ impl Poly {
         Ok(())
     }
 
+    /// Add two polynomials at the deepest of their two levels: the polynomial
+    /// whose context is a parent of the context of the other one is modulus
+    /// switched down to it as in [`Poly::mod_switch_down_to`], and the
+    /// polynomials are then added.
+    ///
+    /// The polynomials must be in the same representation, either PowerBasis
+    /// or Ntt; the result is in this representation. Returns an error if the
+    /// representations are different or NttShoup, if a polynomial has lazy
+    /// coefficients, or if none of the contexts is a descendant of the other.
+    pub fn add_at_common_level(&self, other: &Poly) -> Result<Poly> {
+        if self.representation == Representation::NttShoup {
+            return Err(Error::IncorrectRepresentation(
+                Representation::NttShoup,
+                Representation::Ntt,
+            ));
+        }
+        if other.representation != self.representation {
+            return Err(Error::IncorrectRepresentation(
+                other.representation.clone(),
+                self.representation.clone(),
+            ));
+        }
+        if self.has_lazy_coefficients || other.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+
+        let (upper, lower) = if self.ctx.niterations_to(&other.ctx).is_ok() {
+            (self, other)
+        } else if other.ctx.niterations_to(&self.ctx).is_ok() {
+            (other, self)
+        } else {
+            return Err(Error::InvalidContext);
+        };
+
+        let mut sum = upper.clone();
+        sum.change_representation(Representation::PowerBasis);
+        sum.mod_switch_down_to(&lower.ctx)?;
+        sum.change_representation(lower.representation.clone());
+        sum += lower;
+        Ok(sum)
+    }
+
+    /// Switch up to a parent context, of which the context of the polynomial
+    /// is a descendant: the moduli dropped by the modulus switches are
+    /// reintroduced using the base extension of [`Extender::extend`], so the
+    /// coefficients lifted in the centered interval are unchanged. In
+    /// particular, this does not undo the division by the dropped moduli of a
+    /// modulus switch down.
+    ///
+    /// Returns an error if the context of the polynomial is not a descendant
+    /// of `parent`.
+    pub fn switch_up_to(&self, parent: &Arc<Context>) -> Result<Poly> {
+        parent.niterations_to(&self.ctx)?;
+        Extender::new(&self.ctx, parent)?.extend(self)
+    }
+
+    /// Approximate modulus switch down, which drops the last `levels` moduli
+    /// using a fast base conversion instead of an exact rounding.
+    ///
+    /// Denoting by Q the product of the dropped moduli, the exact modulus switch
+    /// computes round(x / Q) for each coefficient x; this function computes
+    /// floor(x / Q) - u instead, where 0 <= u < levels is the overflow of the
+    /// base conversion. Hence, the output coefficients are smaller than the
+    /// ones of [`Poly::mod_switch_down_to`] by an integer between 0 and
+    /// `levels` (included). Equivalently, when levels > 0, x - Q * y is in the
+    /// interval [0, levels * Q) where y is the output coefficient.
+    ///
+    /// The base conversion is [`RnsContext::switch_basis`] in approximate mode.
+    ///
+    /// Returns an error if there are not enough moduli in the context, or if
+    /// the polynomial is not in PowerBasis representation.
+    pub fn approx_mod_switch(&self, levels: usize) -> Result<Poly> {
+        if self.representation != Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::PowerBasis,
+            ));
+        }
+
+        let mut ctx = self.ctx.clone();
+        for _ in 0..levels {
+            ctx = ctx.next_context().ok_or(Error::NoMoreContext)?.clone();
+        }
+
+        if levels == 0 {
+            return Ok(self.clone());
+        }
+
+        // Approximate conversion of x mod Q into the remaining moduli.
+        let n = ctx.q.len();
+        let dropped = Arc::new(RnsContext::new(&self.ctx.moduli[n..])?);
+        let (kept_rows, dropped_rows) = self.coefficients.view().split_at(Axis(0), n);
+        let mut x_mod_q = dropped.switch_basis(&ctx.rns, dropped_rows, true)?;
+
+        let mut out = Poly::zero(&ctx, Representation::PowerBasis);
+        out.allow_variable_time_computations = self.allow_variable_time_computations;
+        for (qi, mut out_i, kept_i, x_i) in izip!(
+            ctx.q.iter(),
+            out.coefficients.outer_iter_mut(),
+            kept_rows.outer_iter(),
+            x_mod_q.outer_iter()
+        ) {
+            // Q^(-1) mod qi.
+            let q_inv = qi
+                .inv((dropped.modulus() % **qi).to_u64().unwrap())
+                .ok_or(Error::InvalidContext)?;
+            izip!(out_i.iter_mut(), kept_i.iter(), x_i.iter())
+                .for_each(|(o, k, x)| *o = qi.mul(qi.sub(*k, *x), q_inv));
+        }
+        x_mod_q.as_slice_mut().unwrap().zeroize();
+
+        Ok(out)
+    }
+
     /// Modulo switch to another context. The target context needs not to be
     /// related to the current context.
     pub fn mod_switch_to(&self, switcher: &Switcher) -> Result<Poly> {
@@ -542,11 +1433,447 @@ impl Poly {
         scaler.scale(self)
     }
 
+    /// Scale a polynomial using a scaler, and store the result in `out`
+    /// without allocating a new polynomial.
+    ///
+    /// Returns an error if `out` is not in the output context of the scaler,
+    /// or if it is not in the output representation, i.e., the representation
+    /// of the polynomial, or Ntt if the polynomial is in NttShoup
+    /// representation. When the polynomial is in PowerBasis representation,
+    /// this function does not allocate.
+    pub fn scale_into(&self, scaler: &Scaler, out: &mut Poly) -> Result<()> {
+        scaler.scale_into(self, out)
+    }
+
+    /// Scale a polynomial in place using a scaler; a polynomial in NttShoup
+    /// representation is output in Ntt representation.
+    ///
+    /// Returns an error if the input and output contexts of the scaler do not
+    /// have the same number of moduli.
+    pub fn scale_assign(&mut self, scaler: &Scaler) -> Result<()> {
+        scaler.scale_assign(self)
+    }
+
+    /// Scale a polynomial by `numerator / q` with rounding, where q is the
+    /// modulus of the polynomial's context, and project the result in the
+    /// context `ctx_result`. The coefficients are lifted in the centered
+    /// interval [-q/2, q/2) before scaling, so that each coefficient c is
+    /// mapped to round(numerator * c / q); this is the scaling used in the BFV
+    /// decryption.
+    ///
+    /// Returns an error if the polynomial is not in PowerBasis representation
+    /// or if the contexts have different degrees.
+    pub fn scale_round(&self, numerator: u64, ctx_result: &Arc<Context>) -> Result<Poly> {
+        if self.representation != Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::PowerBasis,
+            ));
+        }
+
+        let scaler = Scaler::new(
+            &self.ctx,
+            ctx_result,
+            ScalingFactor::new(&BigUint::from(numerator), self.ctx.modulus()),
+        )?;
+        self.scale(&scaler)
+    }
+
+    /// Convert a polynomial to another context, which needs not to be related
+    /// to the current context, using a fast RNS basis conversion. The
+    /// coefficients are lifted in the centered interval [-q/2, q/2); see
+    /// [`RnsConverter::convert`](crate::rns::RnsConverter::convert).
+    ///
+    /// Returns an error if the polynomial is not in PowerBasis representation
+    /// or if the contexts have different degrees.
+    pub fn convert_basis(&self, to: &Arc<Context>) -> Result<Poly> {
+        if self.representation != Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::PowerBasis,
+            ));
+        }
+        if self.ctx.degree != to.degree {
+            return Err(Error::InvalidContext);
+        }
+
+        let converter = RnsConverter::new(&self.ctx.rns, &to.rns);
+        let mut coefficients = Array2::<u64>::zeros((to.q.len(), to.degree));
+        izip!(
+            coefficients.axis_iter_mut(Axis(1)),
+            self.coefficients.axis_iter(Axis(1))
+        )
+        .for_each(|(new_column, column)| converter.convert(column, new_column));
+
+        Ok(Poly {
+            ctx: to.clone(),
+            representation: Representation::PowerBasis,
+            allow_variable_time_computations: self.allow_variable_time_computations,
+            coefficients,
+            coefficients_shoup: OnceLock::new(),
+            has_lazy_coefficients: false,
+        })
+    }
+
+    /// Lift the coefficients of the polynomial in the centered interval
+    /// (-q/2, q/2], where q is the modulus of the context; see
+    /// [`RnsContext::lift_centered`](crate::rns::RnsContext::lift_centered).
+    ///
+    /// The coefficients are lifted in the current representation of the
+    /// polynomial.
+    pub fn lift_centered(&self) -> Vec<BigInt> {
+        self.coefficients
+            .axis_iter(Axis(1))
+            .map(|c| self.ctx.rns.lift_centered(c))
+            .collect_vec()
+    }
+
+    /// Computes the infinity norm of the polynomial, i.e., the largest absolute
+    /// value of its coefficients lifted in the centered interval (-q/2, q/2]
+    /// as in [`Poly::lift_centered`]. The norm is computed on the coefficients
+    /// in PowerBasis representation.
+    pub fn inf_norm(&self) -> BigUint {
+        let norm = |p: &Poly| {
+            p.coefficients
+                .axis_iter(Axis(1))
+                .map(|c| self.ctx.rns.lift_centered(c).magnitude().clone())
+                .max()
+                .unwrap_or_default()
+        };
+        if self.representation == Representation::PowerBasis {
+            norm(self)
+        } else {
+            let mut p = Zeroizing::new(self.clone());
+            p.change_representation(Representation::PowerBasis);
+            norm(&p)
+        }
+    }
+
+    /// Sets to zero all the coefficients of degree larger than or equal to
+    /// `degree_bound`, in all the RNS channels; the polynomial is unchanged
+    /// when `degree_bound` is at least the degree of the ring.
+    ///
+    /// Returns an error if the polynomial is not in PowerBasis representation.
+    pub fn truncate(&mut self, degree_bound: usize) -> Result<()> {
+        if self.representation != Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::PowerBasis,
+            ));
+        }
+        if degree_bound < self.ctx.degree {
+            self.coefficients.slice_mut(s![.., degree_bound..]).fill(0);
+        }
+        Ok(())
+    }
+
+    /// Returns whether all the coefficients of degree larger than or equal to
+    /// `degree_bound` are zero. For polynomials in Ntt or NttShoup
+    /// representation, the coefficients are checked on a copy converted to
+    /// PowerBasis representation.
+    pub fn is_within_degree(&self, degree_bound: usize) -> bool {
+        let check = |p: &Poly| {
+            degree_bound >= self.ctx.degree
+                || p.coefficients
+                    .slice(s![.., degree_bound..])
+                    .iter()
+                    .fold(0, |acc, c| acc | c)
+                    == 0
+        };
+        if self.representation == Representation::PowerBasis {
+            check(self)
+        } else {
+            let mut p = Zeroizing::new(self.clone());
+            p.change_representation(Representation::PowerBasis);
+            check(&p)
+        }
+    }
+
+    /// Encode a bit vector as a polynomial with coefficients in {0, 1}: the
+    /// i-th coefficient in PowerBasis representation is 1 if and only if
+    /// `bits[i]` is true, and the coefficients after the bits are 0.
+    ///
+    /// Returns an error if there are more bits than the degree.
+    pub fn encode_bits(
+        bits: &[bool],
+        ctx: &Arc<Context>,
+        representation: Representation,
+    ) -> Result<Poly> {
+        if bits.len() > ctx.degree {
+            return Err(Error::TooManyValues(ctx.degree));
+        }
+        let mut p = Poly::zero(ctx, Representation::PowerBasis);
+        p.coefficients
+            .outer_iter_mut()
+            .for_each(|mut v| izip!(v.iter_mut(), bits).for_each(|(vj, bj)| *vj = u64::from(*bj)));
+        p.change_representation(representation);
+        Ok(p)
+    }
+
+    /// Decode the bit vector encoded in the coefficients of the polynomial in
+    /// PowerBasis representation, as in `encode_bits`. The bit vector has
+    /// length degree, i.e., the bits after the encoded ones are false. For
+    /// polynomials in Ntt or NttShoup representation, the coefficients are
+    /// decoded from a copy converted to PowerBasis representation.
+    ///
+    /// Returns [`Error::InvalidBit`] if a coefficient is neither 0 nor 1.
+    pub fn decode_bits(&self) -> Result<Vec<bool>> {
+        let decode = |p: &Poly| {
+            let first = p.coefficients.row(0);
+            (0..self.ctx.degree)
+                .map(|j| {
+                    if first[j] > 1 || p.coefficients.column(j).iter().any(|c| *c != first[j]) {
+                        Err(Error::InvalidBit { index: j })
+                    } else {
+                        Ok(first[j] == 1)
+                    }
+                })
+                .collect()
+        };
+        if self.representation == Representation::PowerBasis {
+            decode(self)
+        } else {
+            let mut p = Zeroizing::new(self.clone());
+            p.change_representation(Representation::PowerBasis);
+            decode(&p)
+        }
+    }
+
+    /// Returns the coefficients in RNS representation as a string, one RNS
+    /// channel per row, in the current representation of the polynomial.
+    ///
+    /// Unlike the `Debug` implementation, which redacts the coefficients,
+    /// this displays all of them; it is intended for debugging, and should
+    /// not be used on polynomials holding secret data.
+    pub fn dump_coefficients(&self) -> String {
+        self.coefficients
+            .outer_iter()
+            .map(|v| format!("{:?}", v.as_slice().unwrap()))
+            .join("\n")
+    }
+
+    /// Returns the largest residue in each RNS channel of the polynomial.
+    ///
+    /// The residues are read from the coefficients in the current
+    /// representation, without any conversion. This is computed in variable
+    /// time, and is intended for debugging, e.g., to inspect the distribution
+    /// of the coefficients.
+    pub fn channel_max(&self) -> Vec<u64> {
+        self.coefficients
+            .outer_iter()
+            .map(|v| v.iter().copied().max().unwrap_or_default())
+            .collect()
+    }
+
+    /// Returns the smallest residue in each RNS channel of the polynomial.
+    ///
+    /// As for [`Poly::channel_max`], this is computed in variable time on the
+    /// coefficients in the current representation, and is intended for
+    /// debugging.
+    pub fn channel_min(&self) -> Vec<u64> {
+        self.coefficients
+            .outer_iter()
+            .map(|v| v.iter().copied().min().unwrap_or_default())
+            .collect()
+    }
+
+    /// Returns whether the polynomial and `other` encode the same element of
+    /// R_q, regardless of their representations; unlike `==`, a polynomial
+    /// and its Ntt form have the same value.
+    ///
+    /// This is more expensive than `==` when one of the polynomials is in
+    /// PowerBasis representation and the other is not, since a copy of the
+    /// latter is then converted to PowerBasis representation.
+    pub fn same_value(&self, other: &Poly) -> bool {
+        if self.ctx != other.ctx {
+            return false;
+        }
+
+        // The Ntt and NttShoup representations have the same coefficients, and
+        // the NTT is a bijection, so polynomials in the same domain can be
+        // compared directly.
+        let in_ntt = |p: &Poly| p.representation != Representation::PowerBasis;
+        if in_ntt(self) == in_ntt(other) {
+            self.coefficients == other.coefficients
+        } else {
+            let (ntt, power_basis) = if in_ntt(self) {
+                (self, other)
+            } else {
+                (other, self)
+            };
+            let mut p = Zeroizing::new(ntt.clone());
+            p.change_representation(Representation::PowerBasis);
+            p.coefficients == power_basis.coefficients
+        }
+    }
+
+    /// Computes the base-2 logarithm of the infinity norm of the difference
+    /// between the polynomial and an `expected` polynomial, for instance to
+    /// measure the noise of a decrypted polynomial. Returns `-inf` when the
+    /// polynomials are equal.
+    ///
+    /// Returns an error if the polynomials do not have the same context or the
+    /// same representation.
+    pub fn log2_distance(&self, expected: &Poly) -> Result<f64> {
+        if self.ctx != expected.ctx {
+            return Err(Error::InvalidContext);
+        }
+        if self.representation != expected.representation {
+            return Err(Error::IncorrectRepresentation(
+                expected.representation.clone(),
+                self.representation.clone(),
+            ));
+        }
+
+        let mut difference = Zeroizing::new(self.clone());
+        let mut expected = Zeroizing::new(expected.clone());
+        difference.change_representation(Representation::PowerBasis);
+        expected.change_representation(Representation::PowerBasis);
+        *difference -= &expected;
+
+        let norm = difference.inf_norm();
+        if norm.is_zero() {
+            Ok(f64::NEG_INFINITY)
+        } else {
+            Ok(norm.to_f64().unwrap().log2())
+        }
+    }
+
+    /// Computes the multiplicative inverse of the polynomial, when it is
+    /// invertible in R_q. In Ntt representation, this is the coefficient-wise
+    /// modular inverse of the evaluations of the polynomial.
+    ///
+    /// Returns an error if the polynomial is not in Ntt representation, if it
+    /// has lazy coefficients, or if any of its evaluations is zero, i.e., if
+    /// the polynomial is not invertible.
+    pub fn try_inverse(&self) -> Result<Poly> {
+        if self.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+        if self.representation != Representation::Ntt {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::Ntt,
+            ));
+        }
+
+        let invertible = self
+            .coefficients
+            .iter()
+            .fold(Choice::from(1), |acc, c| acc & !c.ct_eq(&0));
+        if !bool::from(invertible) {
+            return Err(Error::NotInvertible);
+        }
+
+        // The evaluations are inverted with a single modular inversion per
+        // modulus; this only fails if an evaluation is zero.
+        let mut inverse = self.clone();
+        for (mut v, qi) in izip!(inverse.coefficients.outer_iter_mut(), self.ctx.q.iter()) {
+            let v_inverse = if self.allow_variable_time_computations {
+                unsafe { qi.inv_vec_vt(v.as_slice().unwrap()) }
+            } else {
+                qi.inv_vec(v.as_slice().unwrap())
+            };
+            let v_inverse = Zeroizing::new(v_inverse.ok_or(Error::NotInvertible)?);
+            v.as_slice_mut().unwrap().copy_from_slice(&v_inverse);
+        }
+        Ok(inverse)
+    }
+
+    /// Decompose the polynomial into one digit per modulus q_i of the context,
+    /// as in the RNS digit decomposition used for relinearization: the i-th
+    /// digit is the centered residue of the polynomial modulo q_i, embedded
+    /// back into the context. The polynomial is recovered as the sum of the
+    /// digits multiplied by the corresponding garner coefficients; see
+    /// [`RnsContext::get_garner`](crate::rns::RnsContext::get_garner).
+    ///
+    /// Returns an error if the polynomial is not in PowerBasis representation.
+    pub fn decompose(&self) -> Result<Vec<Poly>> {
+        if self.representation != Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                self.representation.clone(),
+                Representation::PowerBasis,
+            ));
+        }
+
+        izip!(self.coefficients.outer_iter(), self.ctx.q.iter())
+            .map(|(coeffs, qi)| {
+                let coeffs = coeffs.as_slice().unwrap();
+                let centered = Zeroizing::new(if self.allow_variable_time_computations {
+                    unsafe { qi.center_vec_vt(coeffs) }
+                } else {
+                    qi.center_vec(coeffs)
+                });
+                Poly::try_convert_from(
+                    centered.as_ref() as &[i64],
+                    &self.ctx,
+                    self.allow_variable_time_computations,
+                    Representation::PowerBasis,
+                )
+            })
+            .collect()
+    }
+
+    /// Lift the coefficients of the polynomial modulo the product of the first
+    /// `num_moduli` moduli of the context, without lifting modulo the full
+    /// modulus; see
+    /// [`RnsContext::lift_partial`](crate::rns::RnsContext::lift_partial).
+    ///
+    /// Returns an error if `num_moduli` is 0 or larger than the number of
+    /// moduli of the context.
+    pub fn coefficients_mod_prefix(&self, num_moduli: usize) -> Result<Vec<BigUint>> {
+        let rns = self.ctx.rns.sub_context(num_moduli)?;
+        Ok(self
+            .coefficients
+            .slice(s![..num_moduli, ..])
+            .axis_iter(Axis(1))
+            .map(|c| rns.lift(c))
+            .collect_vec())
+    }
+
     /// Returns the context of the underlying polynomial
     pub fn ctx(&self) -> &Arc<Context> {
         &self.ctx
     }
 
+    /// Returns the degree of the polynomial ring, i.e., the number of
+    /// coefficients of the polynomial.
+    pub fn degree(&self) -> usize {
+        self.ctx.degree
+    }
+
+    /// Negates the polynomial in place if `choice` is set, and leaves it
+    /// unchanged otherwise.
+    ///
+    /// This function always runs in constant time, even when variable time
+    /// computations are allowed for this polynomial: both the polynomial and
+    /// its negation are computed, and the result is selected without branching
+    /// on `choice`. Lazy coefficients are first reduced, in constant time, so
+    /// that the output never has lazy coefficients.
+    pub fn conditional_negate(&mut self, choice: Choice) {
+        self.reduce();
+        izip!(self.coefficients.outer_iter_mut(), self.ctx.q.iter()).for_each(|(mut v, qi)| {
+            v.iter_mut().for_each(|vij| {
+                let neg = qi.neg(*vij);
+                vij.conditional_assign(&neg, choice)
+            })
+        });
+        if let Some(coefficients_shoup) = self.coefficients_shoup.get_mut() {
+            // For a prime p and 0 < a < p, shoup(p - a) = 2^64 - 1 - shoup(a),
+            // and shoup(0) = 0.
+            izip!(
+                coefficients_shoup.outer_iter_mut(),
+                self.coefficients.outer_iter()
+            )
+            .for_each(|(mut v_shoup, v)| {
+                izip!(v_shoup.iter_mut(), v.iter()).for_each(|(vij_shoup, vij)| {
+                    let neg_shoup = u64::conditional_select(&!*vij_shoup, &0, vij.ct_eq(&0));
+                    vij_shoup.conditional_assign(&neg_shoup, choice)
+                })
+            });
+        }
+    }
+
     /// Multiplies a polynomial in PowerBasis representation by x^(-power).
     pub fn multiply_inverse_power_of_x(&mut self, power: usize) -> Result<()> {
         if self.representation != Representation::PowerBasis {
@@ -558,7 +1885,7 @@ impl Poly {
 
         let shift = ((self.ctx.degree << 1) - power) % (self.ctx.degree << 1);
         let mask = self.ctx.degree - 1;
-        let original_coefficients = self.coefficients.clone();
+        let mut original_coefficients = self.coefficients.clone();
         izip!(
             self.coefficients.outer_iter_mut(),
             original_coefficients.outer_iter(),
@@ -574,21 +1901,29 @@ impl Poly {
                 }
             }
         });
+        original_coefficients.as_slice_mut().unwrap().zeroize();
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{switcher::Switcher, Context, Poly, Representation};
-    use crate::{rq::SubstitutionExponent, zq::Modulus};
+    use super::{switcher::Switcher, traits::TryConvertFrom, Context, Poly, Representation};
+    use crate::{
+        rq::SubstitutionExponent,
+        test_allocator::leaked_canaries,
+        zq::{primes::generate_prime, Modulus},
+    };
+    use fhe_traits::{DeserializeWithContext, Serialize};
     use fhe_util::variance;
-    use itertools::Itertools;
-    use num_bigint::BigUint;
-    use num_traits::{One, Zero};
+    use itertools::{izip, Itertools};
+    use ndarray::{s, Array2};
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::{One, ToPrimitive, Zero};
     use rand::{thread_rng, Rng, SeedableRng};
     use rand_chacha::ChaCha8Rng;
     use std::{error::Error, sync::Arc};
+    use subtle::Choice;
 
     // Moduli to be used in tests.
     const MODULI: &[u64; 5] = &[
@@ -687,87 +2022,1209 @@ mod tests {
     }
 
     #[test]
-    fn coefficients() -> Result<(), Box<dyn Error>> {
-        let mut rng = thread_rng();
-        for _ in 0..50 {
-            for modulus in MODULI {
-                let ctx = Arc::new(Context::new(&[*modulus], 16)?);
-                let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
-                let p_coefficients = Vec::<u64>::from(&p);
-                assert_eq!(p_coefficients, p.coefficients().as_slice().unwrap())
-            }
+    fn zero_like_and_random_like() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for modulus in MODULI {
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let mut p = Poly::random(&ctx, representation.clone(), &mut rng);
+                unsafe { p.allow_variable_time_computations() }
+                assert_eq!(p.ctx(), &ctx);
+                assert_eq!(p.degree(), 16);
+                assert_eq!(p.representation(), &representation);
+
+                let zero = Poly::zero_like(&p);
+                assert_eq!(zero, Poly::zero(&ctx, representation.clone()));
+                assert!(!zero.allows_variable_time());
+
+                let q = Poly::random_like(&p, &mut rng);
+                assert_eq!(q.ctx(), &ctx);
+                assert_eq!(q.representation(), &representation);
+                assert_eq!(
+                    q.coefficients_shoup.get().is_some(),
+                    p.coefficients_shoup.get().is_some()
+                );
+                assert!(!q.allows_variable_time());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_from_rng() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
+        thread_rng().fill(&mut seed);
+
+        // A generator seeded with the seed reproduces `random_from_seed`.
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let mut rng = ChaCha8Rng::from_seed(seed);
+            assert_eq!(
+                Poly::random_from_rng(&ctx, representation.clone(), &mut rng),
+                Poly::random_from_seed(&ctx, representation, seed)
+            );
+        }
+
+        // Each channel is filled from a sub-seed drawn from the shared
+        // generator, which then produces the next polynomial.
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        let p = Poly::random_from_rng(&ctx, Representation::Ntt, &mut rng);
+        let q = Poly::random_from_rng(&ctx, Representation::Ntt, &mut rng);
+        assert_ne!(p, q);
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        for (i, qi) in ctx.q.iter().enumerate() {
+            let mut sub_seed = <ChaCha8Rng as SeedableRng>::Seed::default();
+            rng.fill(&mut sub_seed);
+            assert_eq!(
+                p.coefficients.row(i).to_vec(),
+                qi.random_vec_from_rng(16, &mut ChaCha8Rng::from_seed(sub_seed))
+            );
+        }
+        assert_eq!(Poly::random(&ctx, Representation::Ntt, &mut rng), q);
+
+        Ok(())
+    }
+
+    #[test]
+    fn coefficients() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            for modulus in MODULI {
+                let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+                let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+                let p_coefficients = Vec::<u64>::from(&p);
+                assert_eq!(p_coefficients, p.coefficients().as_slice().unwrap())
+            }
+
+            let ctx = Arc::new(Context::new(MODULI, 16)?);
+            let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+            let p_coefficients = Vec::<u64>::from(&p);
+            assert_eq!(p_coefficients, p.coefficients().as_slice().unwrap())
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn modulus() -> Result<(), Box<dyn Error>> {
+        for modulus in MODULI {
+            let modulus_biguint = BigUint::from(*modulus);
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+            assert_eq!(ctx.modulus(), &modulus_biguint)
+        }
+
+        let mut modulus_biguint = BigUint::one();
+        MODULI.iter().for_each(|m| modulus_biguint *= *m);
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        assert_eq!(ctx.modulus(), &modulus_biguint);
+
+        Ok(())
+    }
+
+    #[test]
+    fn variable_time_flag() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let representations = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ];
+
+        for from in &representations {
+            let mut p = Poly::random(&ctx, from.clone(), &mut rng);
+            assert!(!p.allows_variable_time());
+
+            // Cloning copies the flag.
+            assert!(!p.clone().allows_variable_time());
+
+            // Cloning for public data sets the flag on the clone only.
+            let q = unsafe { p.clone_public() };
+            assert!(q.allows_variable_time());
+            assert!(!p.allows_variable_time());
+            assert_eq!(q.coefficients, p.coefficients);
+            assert!(unsafe { q.clone_public() }.allows_variable_time());
+
+            // Changing the representation preserves the flag, in both states.
+            for variable_time in [false, true] {
+                if variable_time {
+                    unsafe { p.allow_variable_time_computations() }
+                } else {
+                    p.disallow_variable_time_computations()
+                }
+                for to in &representations {
+                    p.change_representation(to.clone());
+                    assert_eq!(p.allows_variable_time(), variable_time);
+                    assert_eq!(p.clone().allows_variable_time(), variable_time);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn allow_variable_time_computations() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for modulus in MODULI {
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+            let mut p = Poly::random(&ctx, Representation::default(), &mut rng);
+            assert!(!p.allow_variable_time_computations);
+
+            unsafe { p.allow_variable_time_computations() }
+            assert!(p.allow_variable_time_computations);
+
+            let q = p.clone();
+            assert!(q.allow_variable_time_computations);
+
+            p.disallow_variable_time_computations();
+            assert!(!p.allow_variable_time_computations);
+        }
+
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let mut p = Poly::random(&ctx, Representation::default(), &mut rng);
+        assert!(!p.allow_variable_time_computations);
+
+        unsafe { p.allow_variable_time_computations() }
+        assert!(p.allow_variable_time_computations);
+
+        let q = p.clone();
+        assert!(q.allow_variable_time_computations);
+
+        // Allowing variable time propagates.
+        let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        unsafe { p.allow_variable_time_computations() }
+        let mut q = Poly::random(&ctx, Representation::Ntt, &mut rng);
+
+        assert!(!q.allow_variable_time_computations);
+        q *= &p;
+        assert!(q.allow_variable_time_computations);
+
+        q.disallow_variable_time_computations();
+        q += &p;
+        assert!(q.allow_variable_time_computations);
+
+        q.disallow_variable_time_computations();
+        q -= &p;
+        assert!(q.allow_variable_time_computations);
+
+        q = -&p;
+        assert!(q.allow_variable_time_computations);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_variable_time() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let mut p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let q = p.clone();
+
+        // The flag is set in the closure, and reset afterwards.
+        let r = p.with_variable_time(|p| {
+            assert!(p.allow_variable_time_computations);
+            p.change_representation(Representation::Ntt);
+            p.clone()
+        });
+        assert!(!p.allow_variable_time_computations);
+        assert!(r.allow_variable_time_computations);
+        let mut q_ntt = q.clone();
+        q_ntt.change_representation(Representation::Ntt);
+        assert_eq!(p, q_ntt);
+
+        // The previous value of the flag is restored.
+        unsafe { p.allow_variable_time_computations() }
+        p.with_variable_time(|p| p.disallow_variable_time_computations());
+        assert!(p.allow_variable_time_computations);
+        p.disallow_variable_time_computations();
+
+        // The flag is also reset when the closure panics.
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            p.with_variable_time(|_| panic!("panic in the closure"))
+        }));
+        assert!(result.is_err());
+        assert!(!p.allow_variable_time_computations);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sensitive_poly() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(&MODULI[1..2], 16)?);
+        let canary = 0x0123456789abcdef;
+        let canaries = [canary, ctx.q[0].shoup(canary)];
+        let canary_poly = || {
+            Poly::try_convert_from(
+                Array2::from_elem((1, 16), canary),
+                &ctx,
+                false,
+                Representation::Ntt,
+            )
+            .unwrap()
+        };
+
+        // The memory of a polynomial is not cleared when it is dropped.
+        let leaks = leaked_canaries(canaries, || {
+            let mut p = canary_poly();
+            p.change_representation(Representation::NttShoup);
+            drop(p)
+        });
+        assert!(leaks > 0);
+
+        // The memory of a sensitive polynomial is cleared, including the Shoup
+        // coefficients.
+        let leaks = leaked_canaries(canaries, || {
+            let mut p = canary_poly().into_sensitive();
+            p.change_representation(Representation::NttShoup);
+            p.change_representation(Representation::Ntt);
+            p.change_representation(Representation::NttShoup);
+            assert_eq!(p.coefficients_shoup.get().unwrap()[[0, 0]], canaries[1]);
+            drop(p)
+        });
+        assert_eq!(leaks, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn zeroized_intermediates() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(&MODULI[1..2], 16)?);
+        let canary = 0x0123456789abcdef;
+        let canaries = [canary, ctx.q[0].shoup(canary)];
+        let p = Poly::try_convert_from(
+            Array2::from_elem((1, 16), canary),
+            &ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        let mut p_ntt = p.clone();
+        p_ntt.change_representation(Representation::NttShoup);
+
+        // The copies converted to PowerBasis representation are cleared.
+        let leaks = leaked_canaries(canaries, || {
+            assert!(p_ntt.same_value(&p));
+            assert!(p_ntt.log2_distance(&p_ntt).unwrap().is_infinite());
+        });
+        assert_eq!(leaks, 0);
+
+        // The copy of the coefficients made when multiplying by a power of x is
+        // cleared.
+        let mut q = p.clone();
+        let leaks = leaked_canaries(canaries, || q.multiply_inverse_power_of_x(3).unwrap());
+        assert_eq!(leaks, 0);
+
+        // The residues of a constant multiplier are cleared.
+        let zero = Poly::zero(&ctx, Representation::NttShoup).into_sensitive();
+        let multiplier = BigUint::from(canary);
+        let leaks = leaked_canaries(canaries, || {
+            let q = (zero.as_ref() * &multiplier).into_sensitive();
+            drop(q)
+        });
+        assert_eq!(leaks, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_round() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let t = MODULI[0];
+        let ctx = Arc::new(Context::new(&MODULI[1..], 16)?);
+        let ctx_t = Arc::new(Context::new(&[t], 16)?);
+        let delta = ctx.modulus() / t;
+
+        for _ in 0..20 {
+            // Encode a random message m as delta * m + e with a small error e.
+            let m = ctx_t.q[0].random_vec(16, &mut rng);
+            let c = m.iter().map(|mi| &delta * *mi).collect_vec();
+            let mut c =
+                Poly::try_convert_from(c.as_slice(), &ctx, false, Representation::PowerBasis)?;
+            c += Poly::small(&ctx, Representation::PowerBasis, 16, &mut rng)?.as_ref();
+
+            let m_scaled = c.scale_round(t, &ctx_t)?;
+            assert_eq!(m_scaled.representation, Representation::PowerBasis);
+            assert_eq!(Vec::<u64>::from(&m_scaled), m);
+        }
+
+        // Negative values are lifted in the centered interval.
+        let minus_one = Poly::try_convert_from(&[-1i64], &ctx, false, Representation::PowerBasis)?;
+        assert_eq!(
+            minus_one.scale_round(t, &ctx_t)?,
+            Poly::zero(&ctx_t, Representation::PowerBasis)
+        );
+
+        let mut c = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        c.change_representation(Representation::Ntt);
+        assert!(c.scale_round(t, &ctx_t).is_err());
+        c.change_representation(Representation::PowerBasis);
+        assert!(c
+            .scale_round(t, &Arc::new(Context::new(&[t], 32)?))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_basis() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&MODULI[..2], 16)?);
+        let ctx_to = Arc::new(Context::new(&MODULI[2..], 16)?);
+
+        for _ in 0..10 {
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            let q = p.convert_basis(&ctx_to)?;
+            assert_eq!(q.ctx(), &ctx_to);
+            assert_eq!(q.representation, Representation::PowerBasis);
+            let expected = Poly::try_convert_from(
+                p.lift_centered()
+                    .iter()
+                    .map(|c| {
+                        let modulus = BigInt::from(ctx_to.modulus().clone());
+                        (((c % &modulus) + &modulus) % &modulus)
+                            .to_biguint()
+                            .unwrap()
+                    })
+                    .collect_vec()
+                    .as_slice(),
+                &ctx_to,
+                false,
+                Representation::PowerBasis,
+            )?;
+            assert_eq!(q, expected);
+        }
+
+        // Small signed values are preserved.
+        let small = Poly::small(&ctx, Representation::PowerBasis, 16, &mut rng)?;
+        assert_eq!(
+            small.convert_basis(&ctx_to)?.lift_centered(),
+            small.lift_centered()
+        );
+
+        let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        assert!(p.convert_basis(&ctx_to).is_err());
+        p.change_representation(Representation::PowerBasis);
+        assert!(p
+            .convert_basis(&Arc::new(Context::new(&MODULI[2..], 32)?))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn divide_exact_by_last_modulus() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let next = ctx.next_context().unwrap();
+        let q_last = BigUint::from(*MODULI.last().unwrap());
+
+        for representation in [Representation::PowerBasis, Representation::Ntt] {
+            // Multiples of the last modulus are divided exactly.
+            let k = Poly::random(next, Representation::PowerBasis, &mut rng);
+            let multiple = Vec::<BigUint>::from(&k)
+                .iter()
+                .map(|ki| ki * &q_last)
+                .collect_vec();
+            let mut p = Poly::try_convert_from(
+                multiple.as_slice(),
+                &ctx,
+                false,
+                Representation::PowerBasis,
+            )?;
+            let mut expected = k.clone();
+            p.change_representation(representation.clone());
+            expected.change_representation(representation.clone());
+            assert_eq!(p.divide_exact_by_last_modulus()?, Some(expected));
+
+            // The polynomial matches the RNS division of each coefficient.
+            let p = Poly::try_convert_from(
+                multiple.as_slice(),
+                &ctx,
+                false,
+                Representation::PowerBasis,
+            )?;
+            let divided = p.divide_exact_by_last_modulus()?.unwrap();
+            for (c, d) in izip!(p.coefficients.columns(), divided.coefficients.columns()) {
+                assert_eq!(ctx.rns.divide_exact(c, MODULI.len() - 1), Some(d.to_vec()));
+            }
+
+            // Other polynomials are not silently rounded.
+            let mut p =
+                &p + &Poly::try_convert_from(&[1i64], &ctx, false, Representation::PowerBasis)?;
+            p.change_representation(representation.clone());
+            assert_eq!(p.divide_exact_by_last_modulus()?, None);
+        }
+
+        let p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        assert!(p.divide_exact_by_last_modulus().is_err());
+        let lazy = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &[1u64; 16],
+                &ctx,
+            )
+        };
+        assert_eq!(
+            lazy.divide_exact_by_last_modulus(),
+            Err(crate::Error::LazyCoefficients)
+        );
+        let ctx = Arc::new(Context::new(&MODULI[..1], 16)?);
+        let p = Poly::zero(&ctx, Representation::PowerBasis);
+        assert_eq!(
+            p.divide_exact_by_last_modulus(),
+            Err(crate::Error::NoMoreContext)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncate() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&MODULI[..3], 16)?);
+
+        for degree_bound in [0, 1, 5, 15, 16, 20] {
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            let mut q = p.clone();
+            q.truncate(degree_bound)?;
+            assert!(q.is_within_degree(degree_bound));
+            for (q_row, p_row) in izip!(q.coefficients.outer_iter(), p.coefficients.outer_iter()) {
+                for (j, (qj, pj)) in izip!(q_row.iter(), p_row.iter()).enumerate() {
+                    if j < degree_bound {
+                        assert_eq!(qj, pj);
+                    } else {
+                        assert_eq!(*qj, 0);
+                    }
+                }
+            }
+
+            let mut q_ntt = q.clone();
+            q_ntt.change_representation(Representation::Ntt);
+            assert!(q_ntt.is_within_degree(degree_bound));
+            assert_eq!(
+                q_ntt.truncate(degree_bound),
+                Err(crate::Error::IncorrectRepresentation(
+                    Representation::Ntt,
+                    Representation::PowerBasis
+                ))
+            );
+        }
+
+        // A single nonzero high coefficient in one channel is detected.
+        let mut coefficients = Array2::zeros((3, 16));
+        coefficients[[0, 2]] = 1;
+        coefficients[[2, 9]] = 1;
+        let mut p = Poly::try_convert_from(coefficients, &ctx, false, Representation::PowerBasis)?;
+        assert!(p.is_within_degree(10));
+        assert!(!p.is_within_degree(9));
+        assert!(!p.is_within_degree(3));
+        p.change_representation(Representation::NttShoup);
+        assert!(!p.is_within_degree(9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_decode_bits() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&MODULI[..3], 16)?);
+
+        for bits in [
+            vec![true; 16],
+            vec![false; 16],
+            (0..16).map(|_| rng.gen::<bool>()).collect_vec(),
+            vec![true, false, true, true, false],
+        ] {
+            let mut expected = bits.clone();
+            expected.resize(16, false);
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let p = Poly::encode_bits(&bits, &ctx, representation.clone())?;
+                assert_eq!(p.representation(), &representation);
+                assert_eq!(p.decode_bits()?, expected);
+            }
+        }
+
+        // There cannot be more bits than the degree.
+        assert_eq!(
+            Poly::encode_bits(&[true; 17], &ctx, Representation::PowerBasis),
+            Err(crate::Error::TooManyValues(16))
+        );
+
+        // Non-binary coefficients, or coefficients with different residues,
+        // are rejected.
+        let p = Poly::try_convert_from(
+            &[0u64, 1, 2] as &[u64],
+            &ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        assert_eq!(p.decode_bits(), Err(crate::Error::InvalidBit { index: 2 }));
+        let mut coefficients = Array2::zeros((3, 16));
+        coefficients[[1, 4]] = 1;
+        let p = Poly::try_convert_from(coefficients, &ctx, false, Representation::PowerBasis)?;
+        assert_eq!(p.decode_bits(), Err(crate::Error::InvalidBit { index: 4 }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn channel_max_min() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&MODULI[..3], 16)?);
+
+        let mut coefficients = Array2::from_elem((3, 16), 100u64);
+        coefficients[[0, 3]] = 1152;
+        coefficients[[0, 7]] = 0;
+        coefficients[[1, 15]] = MODULI[1] - 1;
+        coefficients[[1, 0]] = 7;
+        coefficients[[2, 9]] = 1 << 40;
+        for representation in [Representation::PowerBasis, Representation::Ntt] {
+            let p =
+                Poly::try_convert_from(coefficients.clone(), &ctx, false, representation.clone())?;
+            assert_eq!(p.channel_max(), vec![1152, MODULI[1] - 1, 1 << 40]);
+            assert_eq!(p.channel_min(), vec![0, 7, 100]);
+        }
+
+        // The residues are read in the current representation.
+        let mut p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        p.change_representation(Representation::Ntt);
+        for (i, row) in p.coefficients().outer_iter().enumerate() {
+            assert_eq!(p.channel_max()[i], *row.iter().max().unwrap());
+            assert_eq!(p.channel_min()[i], *row.iter().min().unwrap());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn inf_norm_and_log2_distance() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        let coefficients = [3i64, -5, 0, 1, -2];
+        let p = Poly::try_convert_from(&coefficients, &ctx, false, Representation::PowerBasis)?;
+        assert_eq!(p.inf_norm(), BigUint::from(5u64));
+        assert!(Poly::zero(&ctx, Representation::Ntt).inf_norm().is_zero());
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+            assert_eq!(p.log2_distance(&p)?, f64::NEG_INFINITY);
+
+            let mut one =
+                Poly::try_convert_from(&[0i64, 0, -1], &ctx, false, Representation::PowerBasis)?;
+            one.change_representation(Representation::Ntt);
+            let mut q = p.clone();
+            q.change_representation(Representation::Ntt);
+            q += &one;
+            q.change_representation(representation.clone());
+            assert!(q.log2_distance(&p)?.abs() < 1e-9);
+
+            let mut big =
+                Poly::try_convert_from(&[1i64 << 40], &ctx, false, Representation::PowerBasis)?;
+            big.change_representation(representation.clone());
+            assert!(
+                (big.log2_distance(&Poly::zero(&ctx, representation.clone()))? - 40.0).abs() < 1e-9
+            );
+        }
+
+        // The contexts and representations must match.
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let q = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        assert!(p.log2_distance(&q).is_err());
+        let other_ctx = Arc::new(Context::new(&MODULI[..1], 16)?);
+        let q = Poly::random(&other_ctx, Representation::PowerBasis, &mut rng);
+        assert_eq!(p.log2_distance(&q), Err(crate::Error::InvalidContext));
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_value() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for _ in 0..20 {
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            let mut p_ntt = p.clone();
+            p_ntt.change_representation(Representation::Ntt);
+            let mut p_ntt_shoup = p.clone();
+            p_ntt_shoup.change_representation(Representation::NttShoup);
+
+            for (a, b) in [(&p, &p_ntt), (&p_ntt, &p_ntt_shoup), (&p_ntt_shoup, &p)] {
+                assert!(a != b);
+                assert!(a.same_value(b));
+                assert!(b.same_value(a));
+            }
+            assert!(p.same_value(&p));
+
+            let q = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            assert!(!q.same_value(&p));
+            assert!(!q.same_value(&p_ntt));
+            assert!(!p_ntt_shoup.same_value(&q));
+
+            // Polynomials in different contexts do not have the same value.
+            let ctx2 = Arc::new(Context::new(&MODULI[..2], 16)?);
+            let mut p2 = p.clone();
+            p2.mod_switch_down_to(&ctx2)?;
+            let p2_lifted = Poly::try_convert_from(
+                Vec::<BigUint>::from(&p2).as_slice(),
+                &ctx,
+                false,
+                Representation::PowerBasis,
+            )?;
+            assert!(!p2.same_value(&p2_lifted));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn try_inverse() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for modulus in MODULI {
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+            let mut one =
+                Poly::try_convert_from(&[1u64] as &[u64], &ctx, false, Representation::PowerBasis)?;
+            one.change_representation(Representation::Ntt);
+
+            for _ in 0..20 {
+                // Replace the zero evaluations to make the polynomial invertible.
+                let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+                p.coefficients.mapv_inplace(|c| c.max(1));
+                let inverse = p.try_inverse()?;
+                assert_eq!(&p * &inverse, one);
+                assert_eq!(inverse.try_inverse()?, p);
+            }
+
+            let zero = Poly::zero(&ctx, Representation::Ntt);
+            assert_eq!(zero.try_inverse(), Err(crate::Error::NotInvertible));
+            let lazy = unsafe {
+                Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                    &[1u64; 16],
+                    &ctx,
+                )
+            };
+            assert_eq!(lazy.try_inverse(), Err(crate::Error::LazyCoefficients));
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            assert_eq!(
+                p.try_inverse().unwrap_err(),
+                crate::Error::IncorrectRepresentation(
+                    Representation::PowerBasis,
+                    Representation::Ntt
+                )
+            );
+        }
+
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        p.coefficients.mapv_inplace(|c| c.max(1));
+        let mut one =
+            Poly::try_convert_from(&[1u64] as &[u64], &ctx, false, Representation::PowerBasis)?;
+        one.change_representation(Representation::Ntt);
+        assert_eq!(&p * &p.try_inverse()?, one);
+        p.coefficients[[2, 5]] = 0;
+        assert_eq!(p.try_inverse(), Err(crate::Error::NotInvertible));
+
+        Ok(())
+    }
+
+    #[test]
+    fn decompose() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for moduli in [&MODULI[..1], &MODULI[..]] {
+            let ctx = Arc::new(Context::new(moduli, 16)?);
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+
+            let digits = p.decompose()?;
+            assert_eq!(digits.len(), moduli.len());
+
+            let mut recomposed = Poly::zero(&ctx, Representation::PowerBasis);
+            for (i, (digit, qi)) in izip!(&digits, moduli).enumerate() {
+                // Each digit is the centered residue modulo qi.
+                let half = BigInt::from(*qi >> 1);
+                assert!(digit
+                    .lift_centered()
+                    .iter()
+                    .all(|c| c.magnitude() <= half.magnitude()));
+                assert_eq!(digit.coefficients.row(i), p.coefficients.row(i));
+
+                recomposed += &(ctx.rns.get_garner(i).unwrap() * digit);
+            }
+            assert_eq!(recomposed, p);
+        }
+
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        assert!(p.decompose().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn coefficients_mod_prefix() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+
+        for j in 1..=MODULI.len() {
+            let ctx_prefix = Arc::new(Context::new(&MODULI[..j], 16)?);
+            let p_prefix = Poly::try_convert_from(
+                p.coefficients.slice(s![..j, ..]).to_owned(),
+                &ctx_prefix,
+                false,
+                Representation::PowerBasis,
+            )?;
+            assert_eq!(
+                p.coefficients_mod_prefix(j)?,
+                Vec::<BigUint>::from(&p_prefix)
+            );
+        }
+        assert_eq!(
+            p.coefficients_mod_prefix(MODULI.len())?,
+            Vec::<BigUint>::from(&p)
+        );
+
+        assert!(p.coefficients_mod_prefix(0).is_err());
+        assert!(p.coefficients_mod_prefix(MODULI.len() + 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn lift_centered() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for modulus in MODULI {
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            let expected = Vec::<BigUint>::from(&p)
+                .into_iter()
+                .map(|c| {
+                    if &c << 1 > *ctx.modulus() {
+                        BigInt::from(c) - BigInt::from(ctx.modulus().clone())
+                    } else {
+                        BigInt::from(c)
+                    }
+                })
+                .collect_vec();
+            assert_eq!(p.lift_centered(), expected);
+        }
+
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let small = (0..16i64).map(|i| i - 8).collect_vec();
+        let p = Poly::try_convert_from(small.as_slice(), &ctx, false, Representation::PowerBasis)?;
+        assert_eq!(
+            p.lift_centered(),
+            small.iter().map(|i| BigInt::from(*i)).collect_vec()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_assign_promoting() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        for p_representation in [Representation::Ntt, Representation::NttShoup] {
+            let p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+            let mut q = Poly::random(&ctx, p_representation.clone(), &mut rng);
+
+            let mut expected = p.clone();
+            expected.change_representation(Representation::Ntt);
+            let mut q_ntt = q.clone();
+            q_ntt.change_representation(Representation::Ntt);
+            expected += &q_ntt;
+
+            let mut r = p.clone();
+            r.add_assign_promoting(&q, false)?;
+            assert_eq!(r, expected);
+
+            expected.change_representation(Representation::NttShoup);
+            let mut r = p.clone();
+            r.add_assign_promoting(&q, true)?;
+            assert_eq!(r, expected);
+
+            // Variable time is contagious, as in the AddAssign operator.
+            unsafe { q.allow_variable_time_computations() }
+            let mut r = p.clone();
+            r.add_assign_promoting(&q, true)?;
+            assert_eq!(r.coefficients, expected.coefficients);
+            assert!(r.allow_variable_time_computations);
+        }
+
+        let mut p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        let q = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        assert!(p.add_assign_promoting(&q, true).is_err());
+        let mut r = q.clone();
+        r.add_assign_promoting(&q, true)?;
+        assert_eq!(r, &q + &q);
+
+        let other_ctx = Arc::new(Context::new(&MODULI[..1], 16)?);
+        let q = Poly::random(&other_ctx, Representation::Ntt, &mut rng);
+        assert!(p.add_assign_promoting(&q, true).is_err());
+
+        let lazy = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &[1u64; 16],
+                &ctx,
+            )
+        };
+        assert_eq!(
+            p.add_assign_promoting(&lazy, true),
+            Err(crate::Error::LazyCoefficients)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_pair() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let (p, q) = Poly::random_pair(&ctx, representation.clone());
+            assert_eq!(p.representation, representation);
+            assert_eq!(q.representation, representation);
+            assert_ne!(p, q);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn monomial() -> Result<(), Box<dyn Error>> {
+        for modulus in MODULI {
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+
+            // Compute x^e by successive multiplications by x.
+            let mut x_coefficients = [0u64; 16];
+            x_coefficients[1] = 1;
+            let mut x = Poly::try_convert_from(
+                x_coefficients.as_slice(),
+                &ctx,
+                false,
+                Representation::PowerBasis,
+            )?;
+            x.change_representation(Representation::Ntt);
+            let mut x_e = Poly::try_convert_from(&[1u64], &ctx, false, Representation::PowerBasis)?;
+            x_e.change_representation(Representation::Ntt);
+
+            for e in 0..4 * 16 {
+                let mut x_e_power_basis = x_e.clone();
+                x_e_power_basis.change_representation(Representation::PowerBasis);
+                assert_eq!(
+                    Poly::monomial(&ctx, Representation::PowerBasis, e, 1),
+                    x_e_power_basis
+                );
+                assert_eq!(Poly::monomial(&ctx, Representation::Ntt, e, 1), x_e);
+                assert_eq!(
+                    Poly::monomial(&ctx, Representation::PowerBasis, e, -1),
+                    -&x_e_power_basis
+                );
+                x_e *= &x;
+            }
+        }
+
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let expected =
+            Poly::try_convert_from(&[0i64, 0, 0, -42], &ctx, false, Representation::PowerBasis)?;
+        assert_eq!(
+            Poly::monomial(&ctx, Representation::PowerBasis, 3, -42),
+            expected
+        );
+        assert_eq!(
+            Poly::monomial(&ctx, Representation::PowerBasis, 19, 42),
+            expected
+        );
+        assert_eq!(
+            Poly::monomial(&ctx, Representation::PowerBasis, 35, -42),
+            expected
+        );
+        assert_eq!(
+            Poly::monomial(&ctx, Representation::NttShoup, 5, 0),
+            Poly::zero(&ctx, Representation::NttShoup)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_coefficients_iter() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let full = ctx.q.len() * ctx.degree;
+
+        // From a range, with reduction modulo each of the moduli.
+        let p = Poly::from_coefficients_iter(
+            1u64 << 62..(1 << 62) + 16,
+            &ctx,
+            Representation::PowerBasis,
+        )?;
+        let v = (1u64 << 62..(1 << 62) + 16).collect_vec();
+        assert_eq!(
+            p,
+            Poly::try_convert_from(v.as_slice(), &ctx, false, Representation::PowerBasis)?
+        );
+        let p = Poly::from_coefficients_iter(0..5, &ctx, Representation::PowerBasis)?;
+        assert_eq!(
+            p,
+            Poly::try_convert_from(&[0u64, 1, 2, 3, 4], &ctx, false, Representation::PowerBasis)?
+        );
+
+        // From the coefficients of random polynomials.
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let q = Poly::random(&ctx, representation.clone(), &mut rng);
+            let v = Vec::<u64>::from(&q);
+            let it: std::vec::IntoIter<u64> = v.clone().into_iter();
+            let p = Poly::from_coefficients_iter(it, &ctx, representation.clone())?;
+            assert_eq!(p, q);
+            assert_eq!(
+                p,
+                Poly::try_convert_from(v.as_slice(), &ctx, false, representation.clone())?
+            );
+
+            assert!(
+                Poly::from_coefficients_iter(0..full as u64 + 1, &ctx, representation.clone())
+                    .is_err()
+            );
+            assert!(
+                Poly::from_coefficients_iter(0..full as u64 - 1, &ctx, representation.clone())
+                    .is_err()
+            );
+        }
+
+        assert!(Poly::from_coefficients_iter(0..17, &ctx, Representation::PowerBasis).is_err());
+        assert!(Poly::from_coefficients_iter(0..16, &ctx, Representation::Ntt).is_err());
+        assert_eq!(
+            Poly::from_coefficients_iter(std::iter::empty(), &ctx, Representation::PowerBasis)?,
+            Poly::zero(&ctx, Representation::PowerBasis)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn representation_strings() -> Result<(), Box<dyn Error>> {
+        for (representation, name) in [
+            (Representation::PowerBasis, "power-basis"),
+            (Representation::Ntt, "ntt"),
+            (Representation::NttShoup, "ntt-shoup"),
+        ] {
+            assert_eq!(representation.to_string(), name);
+            assert_eq!(<&str>::from(&representation), name);
+            assert_eq!(name.parse::<Representation>()?, representation);
+            assert_eq!(Representation::try_from(name)?, representation);
+            assert_eq!(
+                name.to_uppercase().parse::<Representation>()?,
+                representation
+            );
+            assert_eq!(
+                representation.to_string().parse::<Representation>()?,
+                representation
+            );
+            assert_eq!(<&str>::from(representation), name);
+        }
+
+        for s in ["", "powerbasis", "NttShoup", "ntt ", "garbage"] {
+            assert_eq!(
+                s.parse::<Representation>(),
+                Err(crate::Error::UnknownRepresentation(s.to_string()))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<(), Box<dyn Error>> {
+        let ctx = Arc::new(Context::new(&[1153], 16)?);
+        let mut p = Poly::try_convert_from(
+            vec![1u64, 0, 1152, 5, 0, 0, 0, 0, 7],
+            &ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        assert_eq!(format!("{p}"), "1 - 1*x^2 + 5*x^3 + ...");
+        assert_eq!(format!("{p:.16}"), "1 - 1*x^2 + 5*x^3 + 7*x^8");
+        assert_eq!(format!("{p:.3}"), "1 - 1*x^2 + ...");
+
+        p.change_representation(Representation::NttShoup);
+        assert_eq!(format!("{p:.16}"), "1 - 1*x^2 + 5*x^3 + 7*x^8");
+
+        let p = Poly::try_convert_from(vec![0u64, 1152], &ctx, false, Representation::PowerBasis)?;
+        assert_eq!(format!("{p}"), "-1*x + ...");
+        assert_eq!(
+            format!("{:.16}", Poly::zero(&ctx, Representation::Ntt)),
+            "0"
+        );
+
+        let debug = format!("{p:?}");
+        assert!(debug.contains("representation: PowerBasis"));
+        assert!(debug.contains("degree: 16"));
+        assert!(debug.contains("has_coefficients_shoup: false"));
+        assert!(debug.contains("coefficients: <redacted>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_is_redacted() -> Result<(), Box<dyn Error>> {
+        let degree = 4096;
+        let moduli = [
+            generate_prime(62, 2 * degree as u64, 1 << 62).unwrap(),
+            generate_prime(50, 2 * degree as u64, 1 << 50).unwrap(),
+        ];
+        let ctx = Arc::new(Context::new(&moduli, degree)?);
+        let canary = 123456789u64;
+        let p = Poly::try_convert_from(
+            Array2::from_elem((2, degree), canary),
+            &ctx,
+            false,
+            Representation::Ntt,
+        )?;
+
+        // The output does not depend on the degree, and hides the coefficients.
+        let debug = format!("{p:?}");
+        assert!(debug.len() < 512);
+        assert!(debug.contains("Ntt"));
+        assert!(!debug.contains(&canary.to_string()));
+
+        // The coefficients can still be dumped explicitly.
+        let dump = p.dump_coefficients();
+        assert_eq!(dump.matches(&canary.to_string()).count(), 2 * degree);
 
-            let ctx = Arc::new(Context::new(MODULI, 16)?);
-            let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
-            let p_coefficients = Vec::<u64>::from(&p);
-            assert_eq!(p_coefficients, p.coefficients().as_slice().unwrap())
-        }
         Ok(())
     }
 
     #[test]
-    fn modulus() -> Result<(), Box<dyn Error>> {
+    fn change_representation_and_mul() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
         for modulus in MODULI {
-            let modulus_biguint = BigUint::from(*modulus);
             let ctx = Arc::new(Context::new(&[*modulus], 16)?);
-            assert_eq!(ctx.modulus(), &modulus_biguint)
+            for _ in 0..100 {
+                let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+                let q = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+
+                let mut expected = p.clone();
+                expected.change_representation(Representation::Ntt);
+                expected *= &q;
+
+                let mut r = p.clone();
+                r.change_representation_and_mul(&q);
+                assert_eq!(r, expected);
+                assert!(!r.has_lazy_coefficients);
+
+                let mut r = p.clone();
+                unsafe { r.allow_variable_time_computations() }
+                r.change_representation_and_mul(&q);
+                assert_eq!(r.coefficients(), expected.coefficients());
+
+                // Multiplying by a polynomial in Ntt representation is not lazy.
+                let mut q = q.clone();
+                q.change_representation(Representation::Ntt);
+                let mut r = p.clone();
+                r.change_representation_and_mul(&q);
+                assert_eq!(r, expected);
+            }
         }
-
-        let mut modulus_biguint = BigUint::one();
-        MODULI.iter().for_each(|m| modulus_biguint *= *m);
-        let ctx = Arc::new(Context::new(MODULI, 16)?);
-        assert_eq!(ctx.modulus(), &modulus_biguint);
-
         Ok(())
     }
 
     #[test]
-    fn allow_variable_time_computations() -> Result<(), Box<dyn Error>> {
+    fn mul_promoting() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
-        for modulus in MODULI {
-            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
-            let mut p = Poly::random(&ctx, Representation::default(), &mut rng);
-            assert!(!p.allow_variable_time_computations);
-
-            unsafe { p.allow_variable_time_computations() }
-            assert!(p.allow_variable_time_computations);
-
-            let q = p.clone();
-            assert!(q.allow_variable_time_computations);
-
-            p.disallow_variable_time_computations();
-            assert!(!p.allow_variable_time_computations);
-        }
-
         let ctx = Arc::new(Context::new(MODULI, 16)?);
-        let mut p = Poly::random(&ctx, Representation::default(), &mut rng);
-        assert!(!p.allow_variable_time_computations);
-
-        unsafe { p.allow_variable_time_computations() }
-        assert!(p.allow_variable_time_computations);
+        let representations = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ];
+        for _ in 0..20 {
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            let q = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+
+            let mut expected = p.clone();
+            let mut q_ntt = q.clone();
+            expected.change_representation(Representation::Ntt);
+            q_ntt.change_representation(Representation::Ntt);
+            expected *= &q_ntt;
+            expected.change_representation(Representation::PowerBasis);
+
+            for self_representation in &representations {
+                for p_representation in &representations {
+                    let mut r = p.clone();
+                    r.change_representation(self_representation.clone());
+                    let mut s = q.clone();
+                    s.change_representation(p_representation.clone());
+
+                    r.mul_promoting(&s)?;
+                    assert_eq!(&r.representation, self_representation);
+                    assert_eq!(s.representation, *p_representation);
+                    assert_eq!(
+                        r.coefficients_shoup.get().is_some(),
+                        *self_representation == Representation::NttShoup
+                    );
+
+                    let mut e = expected.clone();
+                    e.change_representation(self_representation.clone());
+                    assert_eq!(r, e);
+                }
+            }
+        }
 
-        let q = p.clone();
-        assert!(q.allow_variable_time_computations);
+        let other_ctx = Arc::new(Context::new(&MODULI[..1], 16)?);
+        let mut p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let q = Poly::random(&other_ctx, Representation::PowerBasis, &mut rng);
+        assert!(p.mul_promoting(&q).is_err());
 
-        // Allowing variable time propagates.
-        let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
-        unsafe { p.allow_variable_time_computations() }
-        let mut q = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        let lazy = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &[1u64; 16],
+                &ctx,
+            )
+        };
+        assert_eq!(p.mul_promoting(&lazy), Err(crate::Error::LazyCoefficients));
 
-        assert!(!q.allow_variable_time_computations);
-        q *= &p;
-        assert!(q.allow_variable_time_computations);
+        Ok(())
+    }
 
-        q.disallow_variable_time_computations();
-        q += &p;
-        assert!(q.allow_variable_time_computations);
+    #[test]
+    fn reduce() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
 
-        q.disallow_variable_time_computations();
-        q -= &p;
-        assert!(q.allow_variable_time_computations);
+        let mut q = p.clone();
+        q.reduce();
+        assert_eq!(p, q);
 
-        q = -&p;
-        assert!(q.allow_variable_time_computations);
+        let mut expected = p.clone();
+        expected.change_representation(Representation::Ntt);
+        q.ntt_forward_lazy();
+        q.representation = Representation::Ntt;
+        q.has_lazy_coefficients = true;
+        q.reduce();
+        assert!(!q.has_lazy_coefficients);
+        assert_eq!(q.coefficients(), expected.coefficients());
 
         Ok(())
     }
@@ -784,21 +3241,21 @@ mod tests {
         p.change_representation(Representation::PowerBasis);
         assert_eq!(p.representation, Representation::PowerBasis);
         assert_eq!(p.representation(), &Representation::PowerBasis);
-        assert!(p.coefficients_shoup.is_none());
+        assert!(p.coefficients_shoup.get().is_none());
         let q = p.clone();
 
         p.change_representation(Representation::Ntt);
         assert_eq!(p.representation, Representation::Ntt);
         assert_eq!(p.representation(), &Representation::Ntt);
         assert_ne!(p.coefficients, q.coefficients);
-        assert!(p.coefficients_shoup.is_none());
+        assert!(p.coefficients_shoup.get().is_none());
         let q_ntt = p.clone();
 
         p.change_representation(Representation::NttShoup);
         assert_eq!(p.representation, Representation::NttShoup);
         assert_eq!(p.representation(), &Representation::NttShoup);
         assert_ne!(p.coefficients, q.coefficients);
-        assert!(p.coefficients_shoup.is_some());
+        assert!(p.coefficients_shoup.get().is_some());
         let q_ntt_shoup = p.clone();
 
         p.change_representation(Representation::PowerBasis);
@@ -816,6 +3273,119 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn change_representation_vt() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for degree in [16, 32, 64] {
+            let ctx = Arc::new(Context::new(MODULI, degree)?);
+            let all_representations = [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ];
+            for from in &all_representations {
+                for to in &all_representations {
+                    let mut p = Poly::random(&ctx, from.clone(), &mut rng);
+                    let mut p_vt = p.clone();
+                    unsafe { p_vt.allow_variable_time_computations() }
+
+                    p.change_representation(to.clone());
+                    p_vt.change_representation(to.clone());
+                    assert_eq!(p.coefficients, p_vt.coefficients);
+                    assert_eq!(p.coefficients_shoup, p_vt.coefficients_shoup);
+                    assert_eq!(p.representation, p_vt.representation);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_coefficients_shoup() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        // The Shoup coefficients are not computed when creating a polynomial in
+        // NttShoup representation, but by `change_representation`.
+        let p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        assert!(p.coefficients_shoup.get().is_none());
+        let mut q = p.clone();
+        q.change_representation(Representation::NttShoup);
+        assert!(q.coefficients_shoup.get().is_some());
+
+        // Equality and serialization do not depend on whether the Shoup
+        // coefficients were computed.
+        assert_eq!(p, q);
+        assert_eq!(p.to_bytes(), q.to_bytes());
+        assert_eq!(Poly::from_bytes(&p.to_bytes(), &ctx)?, q);
+
+        // The first multiplication computes the same Shoup coefficients.
+        let mut r = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        let mut s = r.clone();
+        r *= &p;
+        s *= &q;
+        assert_eq!(r, s);
+        assert_eq!(p.coefficients_shoup.get(), q.coefficients_shoup.get());
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_change_representation() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let all_representations = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ];
+
+        // All nine transitions succeed on polynomials with reduced coefficients,
+        // and agree with the panicking method.
+        for from in &all_representations {
+            for to in &all_representations {
+                let p = Poly::random(&ctx, from.clone(), &mut rng);
+                let mut q = p.clone();
+                q.try_change_representation(to.clone())?;
+                let mut expected = p.clone();
+                expected.change_representation(to.clone());
+                assert_eq!(q, expected);
+                assert_eq!(q.representation(), to);
+                assert_eq!(
+                    q.coefficients_shoup.get().is_some(),
+                    to == &Representation::NttShoup
+                );
+
+                q.try_change_representation(from.clone())?;
+                assert_eq!(q, p);
+            }
+        }
+
+        // With lazy coefficients, only the no-op transition succeeds.
+        let coefficients = Poly::random(&ctx, Representation::PowerBasis, &mut rng)
+            .coefficients
+            .row(0)
+            .to_vec();
+        let mut p = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &coefficients,
+                &ctx,
+            )
+        };
+        let q = p.clone();
+        p.try_change_representation(Representation::Ntt)?;
+        assert_eq!(p, q);
+        for to in [Representation::PowerBasis, Representation::NttShoup] {
+            assert_eq!(
+                p.try_change_representation(to.clone()),
+                Err(crate::Error::InvalidTransition(Representation::Ntt, to))
+            );
+            assert_eq!(p, q);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn override_representation() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -829,22 +3399,24 @@ mod tests {
         assert_eq!(p.representation, Representation::Ntt);
         assert_eq!(p.representation(), &p.representation);
         assert_eq!(p.coefficients, q.coefficients);
-        assert!(p.coefficients_shoup.is_none());
+        assert!(p.coefficients_shoup.get().is_none());
 
         unsafe { p.override_representation(Representation::NttShoup) }
         assert_eq!(p.representation, Representation::NttShoup);
         assert_eq!(p.representation(), &p.representation);
         assert_eq!(p.coefficients, q.coefficients);
-        assert!(p.coefficients_shoup.is_some());
+        // The Shoup coefficients are computed when first used.
+        assert!(p.coefficients_shoup.get().is_none());
 
         unsafe { p.override_representation(Representation::PowerBasis) }
         assert_eq!(p, q);
 
         unsafe { p.override_representation(Representation::NttShoup) }
-        assert!(p.coefficients_shoup.is_some());
+        p.coefficients_shoup();
+        assert!(p.coefficients_shoup.get().is_some());
 
         unsafe { p.override_representation(Representation::Ntt) }
-        assert!(p.coefficients_shoup.is_none());
+        assert!(p.coefficients_shoup.get().is_none());
 
         Ok(())
     }
@@ -890,6 +3462,99 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn random_bounded() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        assert_eq!(
+            Poly::random_bounded(&ctx, Representation::PowerBasis, 0, &mut rng)
+                .unwrap_err()
+                .to_string(),
+            "The bound should be nonzero"
+        );
+        assert!(Poly::random_bounded(&ctx, Representation::PowerBasis, 575, &mut rng).is_ok());
+        assert_eq!(
+            Poly::random_bounded(&ctx, Representation::PowerBasis, 576, &mut rng)
+                .unwrap_err()
+                .to_string(),
+            "The bound should be smaller than half of the smallest modulus"
+        );
+
+        // All the values in the interval are sampled, and only those.
+        let ctx = Arc::new(Context::new(&MODULI[1..], 1 << 12)?);
+        for bound in [1u64, 5, 100] {
+            let p = Poly::random_bounded(&ctx, Representation::PowerBasis, bound, &mut rng)?;
+            let v = p
+                .lift_centered()
+                .iter()
+                .map(|c| c.to_i64().unwrap())
+                .collect_vec();
+            assert!(v.iter().all(|c| c.unsigned_abs() <= bound));
+            assert!((-(bound as i64)..=bound as i64).all(|x| v.contains(&x)));
+        }
+
+        let bound = 1u64 << 60;
+        let p = Poly::random_bounded(&ctx, Representation::Ntt, bound, &mut rng)?;
+        assert_eq!(p.representation, Representation::Ntt);
+        let norm = p.inf_norm();
+        assert!(norm <= BigUint::from(bound));
+        assert!(norm > BigUint::from(bound >> 8));
+
+        // The same seed yields the same polynomial.
+        let seed = [1u8; 32];
+        let p = Poly::random_bounded(
+            &ctx,
+            Representation::PowerBasis,
+            bound,
+            &mut ChaCha8Rng::from_seed(seed),
+        )?;
+        let q = Poly::random_bounded(
+            &ctx,
+            Representation::PowerBasis,
+            bound,
+            &mut ChaCha8Rng::from_seed(seed),
+        )?;
+        assert_eq!(p, q);
+
+        Ok(())
+    }
+
+    #[test]
+    fn conjugate() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for degree in [16, 32] {
+            let ctx = Arc::new(Context::new(MODULI, degree)?);
+            let exponent = SubstitutionExponent::new(&ctx, 2 * degree - 1)?;
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let p = Poly::random(&ctx, representation, &mut rng);
+                let q = p.conjugate()?;
+                assert_eq!(q, p.substitute(&exponent)?);
+                assert_eq!(q.conjugate()?, p);
+            }
+
+            // In PowerBasis representation, c_0 is unchanged and c_j is
+            // mapped to -c_(degree - j).
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            let q = p.conjugate()?;
+            for (p_row, q_row, qi) in izip!(
+                p.coefficients.outer_iter(),
+                q.coefficients.outer_iter(),
+                ctx.q.iter()
+            ) {
+                assert_eq!(q_row[0], p_row[0]);
+                for j in 1..degree {
+                    assert_eq!(q_row[degree - j], qi.neg(p_row[j]));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn substitute() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -1009,9 +3674,9 @@ mod tests {
             let mut reference = Vec::<BigUint>::from(&p);
             let mut current_ctx = ctx.clone();
             assert_eq!(p.ctx, current_ctx);
-            while current_ctx.next_context.is_some() {
+            while current_ctx.next_context().is_some() {
                 let denominator = current_ctx.modulus().clone();
-                current_ctx = current_ctx.next_context.as_ref().unwrap().clone();
+                current_ctx = current_ctx.next_context().unwrap().clone();
                 let numerator = current_ctx.modulus().clone();
                 assert!(p.mod_switch_down_next().is_ok());
                 assert_eq!(p.ctx, current_ctx);
@@ -1032,6 +3697,146 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mod_switch_down_next_with_error() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        assert_eq!(
+            p.mod_switch_down_next_with_error().unwrap_err(),
+            crate::Error::IncorrectRepresentation(Representation::Ntt, Representation::PowerBasis)
+        );
+
+        for _ in 0..20 {
+            let mut p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            while p.ctx.next_context().is_some() {
+                let q_last = BigUint::from(*p.ctx.moduli.last().unwrap());
+                let reference = Vec::<BigUint>::from(&p);
+                let mut expected_switch = p.clone();
+                expected_switch.mod_switch_down_next()?;
+
+                let error = p.mod_switch_down_next_with_error()?;
+                assert_eq!(p, expected_switch);
+                assert!(error <= &q_last >> 1);
+
+                // The rounding error computed on the lifted coefficients.
+                let expected_error = reference
+                    .iter()
+                    .map(|x| {
+                        let rounded = ((x + (&q_last >> 1)) / &q_last) * &q_last;
+                        if &rounded >= x {
+                            rounded - x
+                        } else {
+                            x - rounded
+                        }
+                    })
+                    .max()
+                    .unwrap();
+                assert_eq!(error, expected_error);
+            }
+            assert_eq!(
+                p.mod_switch_down_next_with_error().unwrap_err(),
+                crate::Error::NoMoreContext
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn approx_mod_switch() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+
+        let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        assert_eq!(
+            p.approx_mod_switch(1).unwrap_err(),
+            crate::Error::IncorrectRepresentation(Representation::Ntt, Representation::PowerBasis)
+        );
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        assert_eq!(
+            p.approx_mod_switch(MODULI.len()).unwrap_err(),
+            crate::Error::NoMoreContext
+        );
+
+        for _ in 0..20 {
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            for levels in 0..MODULI.len() {
+                let target_ctx = ctx.context_at_level(levels)?;
+                let approx = p.approx_mod_switch(levels)?;
+                assert_eq!(approx.ctx, target_ctx);
+                assert_eq!(approx.representation, Representation::PowerBasis);
+
+                let mut exact = p.clone();
+                exact.mod_switch_down_to(&target_ctx)?;
+
+                // The exact result exceeds the approximate one by at most levels.
+                let modulus = target_ctx.modulus();
+                for (e, a) in izip!(Vec::<BigUint>::from(&exact), Vec::<BigUint>::from(&approx)) {
+                    let diff = (e + modulus - a) % modulus;
+                    assert!(diff <= BigUint::from(levels));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_at_common_level() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let next = ctx.next_context().unwrap().clone();
+
+        for representation in [Representation::PowerBasis, Representation::Ntt] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+            let q = Poly::random(&next, representation.clone(), &mut rng);
+
+            let mut expected = p.clone();
+            expected.change_representation(Representation::PowerBasis);
+            expected.mod_switch_down_next()?;
+            expected.change_representation(representation.clone());
+            expected += &q;
+
+            let sum = p.add_at_common_level(&q)?;
+            assert_eq!(sum.ctx(), &next);
+            assert_eq!(sum.representation(), &representation);
+            assert_eq!(sum, expected);
+            assert_eq!(q.add_at_common_level(&p)?, expected);
+
+            // At the same level, this is the usual addition.
+            assert_eq!(p.add_at_common_level(&p)?, &p + &p);
+        }
+
+        // The representations must match, and the contexts must be related.
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let q = Poly::random(&next, Representation::Ntt, &mut rng);
+        assert_eq!(
+            p.add_at_common_level(&q),
+            Err(crate::Error::IncorrectRepresentation(
+                Representation::Ntt,
+                Representation::PowerBasis
+            ))
+        );
+        let p_ntt = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        let lazy = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &[1u64; 16],
+                &next,
+            )
+        };
+        assert_eq!(
+            p_ntt.add_at_common_level(&lazy),
+            Err(crate::Error::LazyCoefficients)
+        );
+        let other = Arc::new(Context::new(&MODULI[1..], 16)?);
+        let q = Poly::random(&other, Representation::PowerBasis, &mut rng);
+        assert_eq!(p.add_at_common_level(&q), Err(crate::Error::InvalidContext));
+
+        Ok(())
+    }
+
     #[test]
     fn mod_switch_down_to() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -1058,6 +3863,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn switch_up_to() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let parent = Arc::new(Context::new(MODULI, 16)?);
+
+        for level in 0..MODULI.len() {
+            let child = parent.context_at_level(level)?;
+            let dropped_modulus = parent.modulus() / child.modulus();
+
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                // Switching down a small polynomial scaled by the dropped moduli
+                // and switching back up recovers the small polynomial.
+                let small = Poly::small(&parent, Representation::PowerBasis, 16, &mut rng)?;
+                let mut p = small.as_ref() * &dropped_modulus;
+                p.mod_switch_down_to(&child)?;
+                p.change_representation(representation.clone());
+
+                let mut q = p.switch_up_to(&parent)?;
+                assert_eq!(q.ctx, parent);
+                if representation == Representation::PowerBasis {
+                    assert_eq!(q.representation, Representation::PowerBasis);
+                } else {
+                    assert_eq!(q.representation, Representation::Ntt);
+                }
+                q.change_representation(Representation::PowerBasis);
+                assert_eq!(&q, small.as_ref());
+            }
+        }
+
+        // The context of the polynomial must be a descendant of the parent.
+        let child = parent.context_at_level(1)?;
+        let p = Poly::random(&parent, Representation::PowerBasis, &mut rng);
+        assert_eq!(p.switch_up_to(&child), Err(crate::Error::InvalidContext));
+        let other = Arc::new(Context::new(&MODULI[1..], 16)?);
+        let p = Poly::random(&child, Representation::PowerBasis, &mut rng);
+        assert!(p.switch_up_to(&other).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn mod_switch_to() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -1117,4 +3966,110 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn conditional_negate() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for modulus in MODULI {
+            let ctx = Arc::new(Context::new(&[*modulus], 16)?);
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let p = Poly::random(&ctx, representation, &mut rng);
+
+                let mut q = p.clone();
+                q.conditional_negate(Choice::from(0));
+                assert_eq!(q, p);
+
+                q.conditional_negate(Choice::from(1));
+                let mut expected = -&p;
+                if p.representation == Representation::NttShoup {
+                    unsafe { expected.override_representation(Representation::NttShoup) }
+                }
+                assert_eq!(q, expected);
+            }
+        }
+
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let mut q = Poly::zero(&ctx, Representation::PowerBasis);
+        q.conditional_negate(Choice::from(1));
+        assert_eq!(q, Poly::zero(&ctx, Representation::PowerBasis));
+        q = p.clone();
+        q.conditional_negate(Choice::from(1));
+        assert_eq!(q, -&p);
+
+        // Lazy coefficients are reduced instead of causing a panic.
+        let mut expected = p.clone();
+        expected.change_representation(Representation::Ntt);
+        for choice in [0, 1] {
+            let mut q = p.clone();
+            q.ntt_forward_lazy();
+            q.representation = Representation::Ntt;
+            q.has_lazy_coefficients = true;
+            q.conditional_negate(Choice::from(choice));
+            assert!(!q.has_lazy_coefficients);
+            if choice == 1 {
+                assert_eq!(q, -&expected);
+            } else {
+                assert_eq!(q, expected);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_consistent() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let representations = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ];
+
+        for representation in representations.clone() {
+            let mut p = Poly::random(&ctx, representation, &mut rng);
+            p.assert_consistent();
+            assert!(p.inconsistency().is_none());
+            for to in representations.clone() {
+                p.change_representation(to);
+                assert!(p.inconsistency().is_none());
+            }
+        }
+        let coefficients = vec![1u64; 16];
+        let lazy = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &coefficients,
+                &ctx,
+            )
+        };
+        assert!(lazy.inconsistency().is_none());
+
+        // Overriding the representation of a polynomial with lazy coefficients
+        // leaves it inconsistent.
+        for to in [Representation::PowerBasis, Representation::NttShoup] {
+            let mut p = lazy.clone();
+            unsafe { p.override_representation(to) }
+            assert!(p.inconsistency().is_some());
+            if cfg!(debug_assertions) {
+                assert!(std::panic::catch_unwind(|| p.assert_consistent()).is_err());
+            }
+        }
+
+        // So are unreduced coefficients and Shoup coefficients outside of the
+        // NttShoup representation.
+        let mut p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        p.coefficients[[1, 3]] = MODULI[1];
+        assert!(p.inconsistency().is_some());
+        let mut p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        p.change_representation(Representation::NttShoup);
+        p.representation = Representation::Ntt;
+        assert!(p.inconsistency().is_some());
+
+        Ok(())
+    }
 }
@@ -1,16 +1,35 @@
 //! Implementation of operations over polynomials.
 
-use super::{traits::TryConvertFrom, Poly, Representation};
+use super::{for_each_residue, Context, Poly, Representation};
 use crate::{Error, Result};
-use itertools::{izip, Itertools};
+use itertools::izip;
 use ndarray::Array2;
 use num_bigint::BigUint;
 use std::{
+    cell::RefCell,
     cmp::min,
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    sync::{Arc, OnceLock},
 };
 use zeroize::Zeroize;
 
+thread_local! {
+    /// Buffer for the residues of the constants multiplied to polynomials.
+    static RESIDUES: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Panics if the contexts are different. The pointers are compared first, so
+/// that the common case of polynomials sharing the same context is cheap.
+#[track_caller]
+fn assert_same_context(ctx: &Arc<Context>, other: &Arc<Context>) {
+    assert!(
+        Arc::ptr_eq(ctx, other) || ctx == other,
+        "Incompatible contexts: moduli {:?} and {:?}",
+        ctx.moduli,
+        other.moduli
+    );
+}
+
 impl AddAssign<&Poly> for Poly {
     fn add_assign(&mut self, p: &Poly) {
         assert!(!self.has_lazy_coefficients && !p.has_lazy_coefficients);
@@ -23,25 +42,16 @@ impl AddAssign<&Poly> for Poly {
             self.representation, p.representation,
             "Incompatible representations"
         );
-        debug_assert_eq!(self.ctx, p.ctx, "Incompatible contexts");
+        assert_same_context(&self.ctx, &p.ctx);
         self.allow_variable_time_computations |= p.allow_variable_time_computations;
+        let q = &self.ctx.q;
         if self.allow_variable_time_computations {
-            izip!(
-                self.coefficients.outer_iter_mut(),
-                p.coefficients.outer_iter(),
-                self.ctx.q.iter()
-            )
-            .for_each(|(mut v1, v2, qi)| unsafe {
-                qi.add_vec_vt(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
+            for_each_residue(&mut self.coefficients, |i, v1| unsafe {
+                q[i].add_vec_strided_vt(v1, p.coefficients.row(i))
             });
         } else {
-            izip!(
-                self.coefficients.outer_iter_mut(),
-                p.coefficients.outer_iter(),
-                self.ctx.q.iter()
-            )
-            .for_each(|(mut v1, v2, qi)| {
-                qi.add_vec(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
+            for_each_residue(&mut self.coefficients, |i, v1| {
+                q[i].add_vec_strided(v1, p.coefficients.row(i))
             });
         }
     }
@@ -76,25 +86,16 @@ impl SubAssign<&Poly> for Poly {
             self.representation, p.representation,
             "Incompatible representations"
         );
-        debug_assert_eq!(self.ctx, p.ctx, "Incompatible contexts");
+        assert_same_context(&self.ctx, &p.ctx);
         self.allow_variable_time_computations |= p.allow_variable_time_computations;
+        let q = &self.ctx.q;
         if self.allow_variable_time_computations {
-            izip!(
-                self.coefficients.outer_iter_mut(),
-                p.coefficients.outer_iter(),
-                self.ctx.q.iter()
-            )
-            .for_each(|(mut v1, v2, qi)| unsafe {
-                qi.sub_vec_vt(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
+            for_each_residue(&mut self.coefficients, |i, v1| unsafe {
+                q[i].sub_vec_strided_vt(v1, p.coefficients.row(i))
             });
         } else {
-            izip!(
-                self.coefficients.outer_iter_mut(),
-                p.coefficients.outer_iter(),
-                self.ctx.q.iter()
-            )
-            .for_each(|(mut v1, v2, qi)| {
-                qi.sub_vec(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
+            for_each_residue(&mut self.coefficients, |i, v1| {
+                q[i].sub_vec_strided(v1, p.coefficients.row(i))
             });
         }
     }
@@ -129,60 +130,38 @@ impl MulAssign<&Poly> for Poly {
                 "Multiplication requires an Ntt representation."
             );
         }
-        debug_assert_eq!(self.ctx, p.ctx, "Incompatible contexts");
+        assert_same_context(&self.ctx, &p.ctx);
         self.allow_variable_time_computations |= p.allow_variable_time_computations;
 
+        let q = &self.ctx.q;
         match p.representation {
             Representation::Ntt => {
                 if self.allow_variable_time_computations {
-                    unsafe {
-                        izip!(
-                            self.coefficients.outer_iter_mut(),
-                            p.coefficients.outer_iter(),
-                            self.ctx.q.iter()
-                        )
-                        .for_each(|(mut v1, v2, qi)| {
-                            qi.mul_vec_vt(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap());
-                        });
-                    }
+                    for_each_residue(&mut self.coefficients, |i, v1| unsafe {
+                        q[i].mul_vec_strided_vt(v1, p.coefficients.row(i))
+                    });
                 } else {
-                    izip!(
-                        self.coefficients.outer_iter_mut(),
-                        p.coefficients.outer_iter(),
-                        self.ctx.q.iter()
-                    )
-                    .for_each(|(mut v1, v2, qi)| {
-                        qi.mul_vec(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
+                    for_each_residue(&mut self.coefficients, |i, v1| {
+                        q[i].mul_vec_strided(v1, p.coefficients.row(i))
                     });
                 }
             }
             Representation::NttShoup => {
+                let p_shoup = p.coefficients_shoup();
                 if self.allow_variable_time_computations {
-                    izip!(
-                        self.coefficients.outer_iter_mut(),
-                        p.coefficients.outer_iter(),
-                        p.coefficients_shoup.as_ref().unwrap().outer_iter(),
-                        self.ctx.q.iter()
-                    )
-                    .for_each(|(mut v1, v2, v2_shoup, qi)| unsafe {
-                        qi.mul_shoup_vec_vt(
+                    for_each_residue(&mut self.coefficients, |i, mut v1| unsafe {
+                        q[i].mul_shoup_vec_vt(
                             v1.as_slice_mut().unwrap(),
-                            v2.as_slice().unwrap(),
-                            v2_shoup.as_slice().unwrap(),
+                            p.coefficients.row(i).as_slice().unwrap(),
+                            p_shoup.row(i).as_slice().unwrap(),
                         )
                     });
                 } else {
-                    izip!(
-                        self.coefficients.outer_iter_mut(),
-                        p.coefficients.outer_iter(),
-                        p.coefficients_shoup.as_ref().unwrap().outer_iter(),
-                        self.ctx.q.iter()
-                    )
-                    .for_each(|(mut v1, v2, v2_shoup, qi)| {
-                        qi.mul_shoup_vec(
+                    for_each_residue(&mut self.coefficients, |i, mut v1| {
+                        q[i].mul_shoup_vec(
                             v1.as_slice_mut().unwrap(),
-                            v2.as_slice().unwrap(),
-                            v2_shoup.as_slice().unwrap(),
+                            p.coefficients.row(i).as_slice().unwrap(),
+                            p_shoup.row(i).as_slice().unwrap(),
                         )
                     });
                 }
@@ -197,35 +176,34 @@ impl MulAssign<&Poly> for Poly {
 
 impl MulAssign<&BigUint> for Poly {
     fn mul_assign(&mut self, p: &BigUint) {
-        let v: Vec<BigUint> = vec![p.clone()];
-        let mut q = Poly::try_convert_from(
-            v.as_ref() as &[BigUint],
-            &self.ctx,
-            self.allow_variable_time_computations,
-            self.representation.clone(),
-        )
-        .unwrap();
-        q.change_representation(Representation::Ntt);
-        if self.allow_variable_time_computations {
-            unsafe {
+        assert!(!self.has_lazy_coefficients);
+        // Multiplying by a constant is the same in all the representations.
+        // The residues of the constant are computed in a buffer reused across
+        // calls, which is zeroized after use since the constant may be secret.
+        RESIDUES.with_borrow_mut(|rests| {
+            rests.resize(self.ctx.q.len(), 0);
+            self.ctx.rns.project_into(p, rests);
+            if self.allow_variable_time_computations {
                 izip!(
                     self.coefficients.outer_iter_mut(),
-                    q.coefficients.outer_iter(),
+                    rests.iter(),
                     self.ctx.q.iter()
                 )
-                .for_each(|(mut v1, v2, qi)| {
-                    qi.mul_vec_vt(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
+                .for_each(|(mut v, r, qi)| unsafe {
+                    qi.scalar_mul_vec_vt(v.as_slice_mut().unwrap(), *r)
                 });
+            } else {
+                izip!(
+                    self.coefficients.outer_iter_mut(),
+                    rests.iter(),
+                    self.ctx.q.iter()
+                )
+                .for_each(|(mut v, r, qi)| qi.scalar_mul_vec(v.as_slice_mut().unwrap(), *r));
             }
-        } else {
-            izip!(
-                self.coefficients.outer_iter_mut(),
-                q.coefficients.outer_iter(),
-                self.ctx.q.iter()
-            )
-            .for_each(|(mut v1, v2, qi)| {
-                qi.mul_vec(v1.as_slice_mut().unwrap(), v2.as_slice().unwrap())
-            });
+            rests.zeroize();
+        });
+        if self.representation == Representation::NttShoup {
+            self.refresh_coefficients_shoup()
         }
     }
 }
@@ -236,15 +214,18 @@ impl Mul<&Poly> for &Poly {
         match self.representation {
             Representation::NttShoup => {
                 // TODO: To test, and do the same thing for add, sub, and neg
-                let mut q = p.clone();
+                // The Shoup coefficients of p are not needed for the product,
+                // so they are not cloned.
+                let mut q = Poly {
+                    ctx: p.ctx.clone(),
+                    representation: p.representation.clone(),
+                    allow_variable_time_computations: p.allow_variable_time_computations,
+                    coefficients: p.coefficients.clone(),
+                    coefficients_shoup: OnceLock::new(),
+                    has_lazy_coefficients: p.has_lazy_coefficients,
+                };
                 if q.representation == Representation::NttShoup {
-                    q.coefficients_shoup
-                        .as_mut()
-                        .unwrap()
-                        .as_slice_mut()
-                        .unwrap()
-                        .zeroize();
-                    unsafe { q.override_representation(Representation::Ntt) }
+                    q.representation = Representation::Ntt
                 }
                 q *= self;
                 q
@@ -280,12 +261,13 @@ impl Neg for &Poly {
     fn neg(self) -> Poly {
         assert!(!self.has_lazy_coefficients);
         let mut out = self.clone();
+        let q = &out.ctx.q;
         if self.allow_variable_time_computations {
-            izip!(out.coefficients.outer_iter_mut(), out.ctx.q.iter())
-                .for_each(|(mut v1, qi)| unsafe { qi.neg_vec_vt(v1.as_slice_mut().unwrap()) });
+            for_each_residue(&mut out.coefficients, |i, v1| unsafe {
+                q[i].neg_vec_strided_vt(v1)
+            });
         } else {
-            izip!(out.coefficients.outer_iter_mut(), out.ctx.q.iter())
-                .for_each(|(mut v1, qi)| qi.neg_vec(v1.as_slice_mut().unwrap()));
+            for_each_residue(&mut out.coefficients, |i, v1| q[i].neg_vec_strided(v1));
         }
         out
     }
@@ -296,58 +278,113 @@ impl Neg for Poly {
 
     fn neg(mut self) -> Poly {
         assert!(!self.has_lazy_coefficients);
+        let q = &self.ctx.q;
         if self.allow_variable_time_computations {
-            izip!(self.coefficients.outer_iter_mut(), self.ctx.q.iter())
-                .for_each(|(mut v1, qi)| unsafe { qi.neg_vec_vt(v1.as_slice_mut().unwrap()) });
+            for_each_residue(&mut self.coefficients, |i, v1| unsafe {
+                q[i].neg_vec_strided_vt(v1)
+            });
         } else {
-            izip!(self.coefficients.outer_iter_mut(), self.ctx.q.iter())
-                .for_each(|(mut v1, qi)| qi.neg_vec(v1.as_slice_mut().unwrap()));
+            for_each_residue(&mut self.coefficients, |i, v1| q[i].neg_vec_strided(v1));
         }
         self
     }
 }
 
-/// Computes the Fused-Mul-Add operation `out[i] += x[i] * y[i]`
-unsafe fn fma(out: &mut [u128], x: &[u64], y: &[u64]) {
-    let n = out.len();
-    assert_eq!(x.len(), n);
-    assert_eq!(y.len(), n);
+impl Poly {
+    /// Computes `self + p` like the `Add` operator, but returns an error
+    /// instead of panicking if the polynomials have lazy coefficients, if the
+    /// representations are different or NttShoup, or if the contexts are
+    /// different.
+    pub fn checked_add(&self, p: &Poly) -> Result<Poly> {
+        self.check_add(p)?;
+        Ok(self + p)
+    }
+
+    /// Computes `self - p` like the `Sub` operator, but returns an error
+    /// instead of panicking, as in [`Poly::checked_add`].
+    pub fn checked_sub(&self, p: &Poly) -> Result<Poly> {
+        self.check_add(p)?;
+        Ok(self - p)
+    }
 
-    macro_rules! fma_at {
-        ($idx:expr) => {
-            *out.get_unchecked_mut($idx) +=
-                (*x.get_unchecked($idx) as u128) * (*y.get_unchecked($idx) as u128);
+    /// Computes `self * p` like the `Mul` operator, but returns an error
+    /// instead of panicking if the representations do not allow the
+    /// multiplication, if `p` has lazy coefficients, or if the contexts are
+    /// different.
+    pub fn checked_mul(&self, p: &Poly) -> Result<Poly> {
+        // With an NttShoup left operand, the product is computed as `p * self`
+        // with `p` in Ntt representation.
+        let (lhs, rhs) = if self.representation == Representation::NttShoup {
+            (p, self)
+        } else {
+            (self, p)
+        };
+        let lhs_representation = match lhs.representation {
+            Representation::NttShoup => Representation::Ntt,
+            ref representation => representation.clone(),
         };
+
+        if rhs.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+        if lhs.has_lazy_coefficients && lhs_representation == Representation::Ntt {
+            if rhs.representation != Representation::NttShoup {
+                return Err(Error::IncorrectRepresentation(
+                    rhs.representation.clone(),
+                    Representation::NttShoup,
+                ));
+            }
+        } else if lhs_representation != Representation::Ntt {
+            return Err(Error::IncorrectRepresentation(
+                lhs_representation,
+                Representation::Ntt,
+            ));
+        }
+        if rhs.representation == Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                Representation::PowerBasis,
+                Representation::Ntt,
+            ));
+        }
+        check_same_context(&self.ctx, &p.ctx)?;
+
+        Ok(self * p)
     }
 
-    let r = n / 16;
-    for i in 0..r {
-        fma_at!(16 * i);
-        fma_at!(16 * i + 1);
-        fma_at!(16 * i + 2);
-        fma_at!(16 * i + 3);
-        fma_at!(16 * i + 4);
-        fma_at!(16 * i + 5);
-        fma_at!(16 * i + 6);
-        fma_at!(16 * i + 7);
-        fma_at!(16 * i + 8);
-        fma_at!(16 * i + 9);
-        fma_at!(16 * i + 10);
-        fma_at!(16 * i + 11);
-        fma_at!(16 * i + 12);
-        fma_at!(16 * i + 13);
-        fma_at!(16 * i + 14);
-        fma_at!(16 * i + 15);
-    }
-
-    for i in 0..n % 16 {
-        fma_at!(16 * r + i);
+    /// Returns an error if `p` cannot be added to or subtracted from `self`.
+    fn check_add(&self, p: &Poly) -> Result<()> {
+        if self.has_lazy_coefficients || p.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+        if self.representation == Representation::NttShoup {
+            return Err(Error::IncorrectRepresentation(
+                Representation::NttShoup,
+                Representation::Ntt,
+            ));
+        }
+        if p.representation != self.representation {
+            return Err(Error::IncorrectRepresentation(
+                p.representation.clone(),
+                self.representation.clone(),
+            ));
+        }
+        check_same_context(&self.ctx, &p.ctx)
+    }
+}
+
+/// Returns an error if the contexts are different, as in
+/// `assert_same_context`.
+fn check_same_context(ctx: &Arc<Context>, other: &Arc<Context>) -> Result<()> {
+    if Arc::ptr_eq(ctx, other) || ctx == other {
+        Ok(())
+    } else {
+        Err(Error::InvalidContext)
     }
 }
 
 /// Compute the dot product between two iterators of polynomials.
-/// Returna an error if the iterator counts are 0, or if any of the polynomial
-/// is not in Ntt or NttShoup representation.
+/// Returns [`Error::EmptyInput`] if either iterator is empty, or an error if
+/// any of the polynomial is not in Ntt or NttShoup representation.
 pub fn dot_product<'a, 'b, I, J>(p: I, q: J) -> Result<Poly>
 where
     I: Iterator<Item = &'a Poly> + Clone,
@@ -362,113 +399,200 @@ where
 
     let count = min(p.clone().count(), q.clone().count());
     if count == 0 {
-        return Err(Error::Default("At least one iterator is empty".to_string()));
+        return Err(Error::EmptyInput);
     }
 
     let p_first = p.clone().next().unwrap();
-
-    // Initialize the accumulator
-    let mut acc: Array2<u128> = Array2::zeros((p_first.ctx.q.len(), p_first.ctx.degree));
-    let acc_ptr = acc.as_mut_ptr();
-
-    // Current number of products accumulated
-    let mut num_acc = vec![1u128; p_first.ctx.q.len()];
-    let num_acc_ptr = num_acc.as_mut_ptr();
-
-    // Maximum number of products that can be accumulated
-    let max_acc = p_first
-        .ctx
-        .q
-        .iter()
-        .map(|qi| 1u128 << (2 * (*qi).leading_zeros()))
-        .collect_vec();
-    let max_acc_ptr = max_acc.as_ptr();
-
-    let q_ptr = p_first.ctx.q.as_ptr();
-    let degree = p_first.ctx.degree as isize;
-
-    let min_of_max = max_acc.iter().min().unwrap();
-
-    let out_slice = acc.as_slice_mut().unwrap();
-    if count as u128 > *min_of_max {
-        for (pi, qi) in izip!(p, q) {
-            let pij = pi.coefficients();
-            let qij = qi.coefficients();
-            let pi_slice = pij.as_slice().unwrap();
-            let qi_slice = qij.as_slice().unwrap();
-            unsafe {
-                fma(out_slice, pi_slice, qi_slice);
-
-                for j in 0..p_first.ctx.q.len() as isize {
-                    let qj = &*q_ptr.offset(j);
-                    *num_acc_ptr.offset(j) += 1;
-                    if *num_acc_ptr.offset(j) == *max_acc_ptr.offset(j) {
-                        if p_first.allow_variable_time_computations {
-                            for i in j * degree..(j + 1) * degree {
-                                *acc_ptr.offset(i) = qj.reduce_u128_vt(*acc_ptr.offset(i)) as u128;
-                            }
-                        } else {
-                            for i in j * degree..(j + 1) * degree {
-                                *acc_ptr.offset(i) = qj.reduce_u128(*acc_ptr.offset(i)) as u128;
-                            }
-                        }
-                        *num_acc_ptr.offset(j) = 1;
-                    }
+    let ctx = &p_first.ctx;
+    let variable_time = p_first.allow_variable_time_computations;
+
+    // Accumulate the products without reduction, and reduce the accumulator
+    // only when it could overflow.
+    let mut acc: Array2<u128> = Array2::zeros((ctx.q.len(), ctx.degree));
+    let mut num_acc = vec![0u128; ctx.q.len()];
+    for (pi, qi) in izip!(p, q) {
+        izip!(
+            acc.outer_iter_mut(),
+            pi.coefficients.outer_iter(),
+            qi.coefficients.outer_iter(),
+            ctx.q.iter(),
+            num_acc.iter_mut()
+        )
+        .for_each(|(mut accj, pij, qij, m, num_accj)| {
+            let accj = accj.as_slice_mut().unwrap();
+            if *num_accj == m.fma_max_terms() {
+                if variable_time {
+                    accj.iter_mut()
+                        .for_each(|a| *a = unsafe { m.reduce_u128_vt(*a) } as u128)
+                } else {
+                    accj.iter_mut().for_each(|a| *a = m.reduce_u128(*a) as u128)
                 }
+                *num_accj = 1;
             }
-        }
-    } else {
-        // We don't need to check the condition on the max, it should shave off a few
-        // cycles.
-        for (pi, qi) in izip!(p, q) {
-            let pij = pi.coefficients();
-            let qij = qi.coefficients();
-            let pi_slice = pij.as_slice().unwrap();
-            let qi_slice = qij.as_slice().unwrap();
-            unsafe { fma(out_slice, pi_slice, qi_slice) }
-        }
+            if variable_time {
+                unsafe { m.fma_vec_vt(accj, pij.as_slice().unwrap(), qij.as_slice().unwrap()) }
+            } else {
+                m.fma_vec(accj, pij.as_slice().unwrap(), qij.as_slice().unwrap())
+            }
+            *num_accj += 1;
+        });
     }
+
     // Last reduction to create the coefficients
-    let mut coeffs: Array2<u64> = Array2::zeros((p_first.ctx.q.len(), p_first.ctx.degree));
-    izip!(
-        coeffs.outer_iter_mut(),
-        acc.outer_iter(),
-        p_first.ctx.q.iter()
-    )
-    .for_each(|(mut coeffsj, accj, m)| {
-        if p_first.allow_variable_time_computations {
-            izip!(coeffsj.iter_mut(), accj.iter())
-                .for_each(|(cj, accjk)| *cj = unsafe { m.reduce_u128_vt(*accjk) });
-        } else {
-            izip!(coeffsj.iter_mut(), accj.iter())
-                .for_each(|(cj, accjk)| *cj = m.reduce_u128(*accjk));
-        }
-    });
+    let mut coeffs: Array2<u64> = Array2::zeros((ctx.q.len(), ctx.degree));
+    izip!(coeffs.outer_iter_mut(), acc.outer_iter(), ctx.q.iter()).for_each(
+        |(mut coeffsj, accj, m)| {
+            let accj = accj.as_slice().unwrap();
+            coeffsj
+                .as_slice_mut()
+                .unwrap()
+                .copy_from_slice(&if variable_time {
+                    unsafe { m.reduce_vec_u128_vt(accj) }
+                } else {
+                    m.reduce_vec_u128(accj)
+                })
+        },
+    );
 
     Ok(Poly {
-        ctx: p_first.ctx.clone(),
+        ctx: ctx.clone(),
         representation: Representation::Ntt,
-        allow_variable_time_computations: p_first.allow_variable_time_computations,
+        allow_variable_time_computations: variable_time,
         coefficients: coeffs,
-        coefficients_shoup: None,
+        coefficients_shoup: OnceLock::new(),
         has_lazy_coefficients: false,
     })
 }
 
+/// Compute the sum of the products a\[i\] * b\[i\] of two slices of
+/// polynomials, as needed in the inner loop of key switching.
+///
+/// Returns an error if the slices are empty or of different lengths, if any of
+/// the polynomials is not in Ntt or NttShoup representation or has lazy
+/// coefficients, or if the polynomials do not all share the same context.
+/// When the `rayon` feature is enabled, the products are computed in parallel
+/// and summed with a tree reduction.
+pub fn mul_accumulate(a: &[Poly], b: &[Poly]) -> Result<Poly> {
+    if a.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    if a.len() != b.len() {
+        return Err(Error::InvalidLength(b.len(), a.len()));
+    }
+
+    let ctx = &a[0].ctx;
+    for p in a.iter().chain(b.iter()) {
+        if p.has_lazy_coefficients {
+            return Err(Error::LazyCoefficients);
+        }
+        if p.representation == Representation::PowerBasis {
+            return Err(Error::IncorrectRepresentation(
+                p.representation.clone(),
+                Representation::Ntt,
+            ));
+        }
+        if !Arc::ptr_eq(&p.ctx, ctx) && p.ctx != *ctx {
+            return Err(Error::InvalidContext);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        Ok(a.par_iter()
+            .zip(b.par_iter())
+            .map(|(ai, bi)| ai * bi)
+            .reduce_with(|mut acc, p| {
+                acc += &p;
+                acc
+            })
+            .unwrap())
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    dot_product(a.iter(), b.iter())
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::{izip, Itertools};
-    use rand::thread_rng;
+    use num_bigint::BigUint;
+    use rand::{thread_rng, Rng};
 
-    use super::dot_product;
+    use super::{dot_product, mul_accumulate};
     use crate::{
-        rq::{Context, Poly, Representation},
+        rq::{traits::TryConvertFrom, Context, Poly, Representation},
+        test_allocator::allocations,
         zq::Modulus,
     };
-    use std::{error::Error, sync::Arc};
+    use std::{error::Error, panic::catch_unwind, sync::Arc};
 
     static MODULI: &[u64; 3] = &[1153, 4611686018326724609, 4611686018309947393];
 
+    #[test]
+    fn checked_ops() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let other_ctx = Arc::new(Context::new(&MODULI[..2], 16)?);
+
+        let mut polys = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ]
+        .into_iter()
+        .map(|representation| Poly::random(&ctx, representation, &mut rng))
+        .collect_vec();
+        polys.push(unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &[1u64; 16],
+                &ctx,
+            )
+        });
+        polys.push(Poly::random(&other_ctx, Representation::Ntt, &mut rng));
+
+        // The checked operations fail exactly when the operators panic, and
+        // otherwise compute the same result.
+        for p in &polys {
+            for q in &polys {
+                let checked_results = [p.checked_add(q), p.checked_sub(q), p.checked_mul(q)];
+                let results = [
+                    catch_unwind(|| p + q),
+                    catch_unwind(|| p - q),
+                    catch_unwind(|| p * q),
+                ];
+                for (checked, result) in izip!(checked_results, results) {
+                    match result {
+                        Ok(r) => assert_eq!(checked?, r),
+                        Err(_) => assert!(checked.is_err()),
+                    }
+                }
+            }
+        }
+
+        assert_eq!(
+            polys[0].checked_add(&polys[1]),
+            Err(crate::Error::IncorrectRepresentation(
+                Representation::Ntt,
+                Representation::PowerBasis
+            ))
+        );
+        assert_eq!(
+            polys[1].checked_mul(&polys[4]),
+            Err(crate::Error::InvalidContext)
+        );
+        assert_eq!(
+            polys[1].checked_add(&polys[3]),
+            Err(crate::Error::LazyCoefficients)
+        );
+        assert_eq!(
+            polys[1].checked_mul(&polys[3]),
+            Err(crate::Error::LazyCoefficients)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn add() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -618,6 +742,92 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mul_biguint() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for _ in 0..100 {
+            let c = BigUint::from_bytes_le(&rng.gen::<[u8; 24]>());
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let p = Poly::random(&ctx, representation.clone(), &mut rng);
+                let r = &p * &c;
+                assert_eq!(r.representation, representation);
+
+                let mut expected = Poly::try_convert_from(
+                    std::slice::from_ref(&c),
+                    &ctx,
+                    false,
+                    Representation::PowerBasis,
+                )?;
+                expected.change_representation(Representation::Ntt);
+                let mut a = p.clone();
+                unsafe { a.override_representation(Representation::Ntt) };
+                a *= &expected;
+                unsafe { a.override_representation(representation) };
+                assert_eq!(r, a);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn mul_allocations() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        let c = BigUint::from_bytes_le(&rng.gen::<[u8; 24]>());
+
+        // Multiplying by a constant reuses the buffers after the first call.
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let mut p = Poly::random(&ctx, representation, &mut rng);
+            p *= &c;
+            let before = allocations();
+            for _ in 0..10 {
+                p *= &c;
+            }
+            assert_eq!(allocations(), before);
+        }
+
+        // The Shoup coefficients, once computed, are updated in place.
+        let mut p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        p.coefficients_shoup();
+        p *= &c;
+        let before = allocations();
+        for _ in 0..10 {
+            p *= &c;
+        }
+        assert_eq!(allocations(), before);
+        let mut expected = p.clone();
+        expected.change_representation(Representation::Ntt);
+        expected.change_representation(Representation::NttShoup);
+        assert_eq!(p.coefficients_shoup(), expected.coefficients_shoup());
+
+        // With an NttShoup left operand, only the coefficients of the product
+        // are allocated.
+        let mut p = Poly::random(&ctx, Representation::NttShoup, &mut rng);
+        p.change_representation(Representation::NttShoup);
+        for representation in [Representation::Ntt, Representation::NttShoup] {
+            let q = Poly::random(&ctx, representation, &mut rng);
+            let before = allocations();
+            let r = &p * &q;
+            assert_eq!(allocations(), before + 1);
+            assert_eq!(r.representation, Representation::Ntt);
+            let mut expected = q.clone();
+            expected.change_representation(Representation::Ntt);
+            expected *= &p;
+            assert_eq!(r, expected);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn neg() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -659,6 +869,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn incompatible_contexts() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&MODULI[..2], 16)?);
+        let other_ctx = Arc::new(Context::new(&MODULI[1..], 16)?);
+
+        // Equal contexts in different allocations are compatible.
+        let same_ctx = Arc::new(Context::new(&MODULI[..2], 16)?);
+        let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        let q = Poly::random(&same_ctx, Representation::Ntt, &mut rng);
+        let _ = &(&p + &q) - &(&p * &q);
+
+        // The contexts are checked in release builds, even though the
+        // polynomials have the same shape.
+        let q = Poly::random(&other_ctx, Representation::Ntt, &mut rng);
+        for r in [
+            catch_unwind(|| &p + &q),
+            catch_unwind(|| &p - &q),
+            catch_unwind(|| &p * &q),
+        ] {
+            let message = r.unwrap_err().downcast::<String>().unwrap();
+            assert_eq!(
+                *message,
+                format!(
+                    "Incompatible contexts: moduli {:?} and {:?}",
+                    &MODULI[..2],
+                    &MODULI[1..]
+                )
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_dot_product() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -695,7 +940,79 @@ mod tests {
                 izip!(&p, &q).for_each(|(pi, qi)| expected += &(pi * qi));
                 assert_eq!(r, expected);
             }
+
+            let p = [Poly::random(&ctx, Representation::Ntt, &mut rng)];
+            assert_eq!(
+                dot_product(p.iter(), [].iter()),
+                Err(crate::Error::EmptyInput)
+            );
         }
         Ok(())
     }
+
+    #[test]
+    fn test_mul_accumulate() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(MODULI, 16)?);
+        for len in 1..20 {
+            let p = (0..len)
+                .map(|_| Poly::random(&ctx, Representation::Ntt, &mut rng))
+                .collect_vec();
+            let mut q = (0..len)
+                .map(|_| Poly::random(&ctx, Representation::Ntt, &mut rng))
+                .collect_vec();
+
+            let expected = izip!(&p, &q).fold(
+                Poly::zero(&ctx, Representation::Ntt),
+                |mut acc, (pi, qi)| {
+                    acc += &(pi * qi);
+                    acc
+                },
+            );
+            assert_eq!(mul_accumulate(&p, &q)?, expected);
+
+            q.iter_mut()
+                .for_each(|qi| qi.change_representation(Representation::NttShoup));
+            assert_eq!(mul_accumulate(&p, &q)?, expected);
+        }
+
+        let p = vec![Poly::random(&ctx, Representation::Ntt, &mut rng); 2];
+        assert_eq!(mul_accumulate(&[], &[]), Err(crate::Error::EmptyInput));
+        assert_eq!(
+            mul_accumulate(&p, &p[..1]),
+            Err(crate::Error::InvalidLength(1, 2))
+        );
+
+        // Lazy coefficients are rejected, instead of being accumulated
+        // without reduction or panicking.
+        let lazy = unsafe {
+            Poly::create_constant_ntt_polynomial_with_lazy_coefficients_and_variable_time(
+                &[1u64; 16],
+                &ctx,
+            )
+        };
+        assert_eq!(
+            mul_accumulate(&[lazy.clone(), p[0].clone()], &p),
+            Err(crate::Error::LazyCoefficients)
+        );
+        assert_eq!(
+            mul_accumulate(&p, &[p[0].clone(), lazy]),
+            Err(crate::Error::LazyCoefficients)
+        );
+
+        let q = vec![Poly::random(&ctx, Representation::PowerBasis, &mut rng); 2];
+        assert_eq!(
+            mul_accumulate(&p, &q),
+            Err(crate::Error::IncorrectRepresentation(
+                Representation::PowerBasis,
+                Representation::Ntt
+            ))
+        );
+
+        let other_ctx = Arc::new(Context::new(&MODULI[..1], 16)?);
+        let q = vec![Poly::random(&other_ctx, Representation::Ntt, &mut rng); 2];
+        assert_eq!(mul_accumulate(&p, &q), Err(crate::Error::InvalidContext));
+
+        Ok(())
+    }
 }
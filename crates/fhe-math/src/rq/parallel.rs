@@ -0,0 +1,106 @@
+//! Iteration over the residues of polynomials, in parallel when the `rayon`
+//! feature is enabled.
+
+use ndarray::{Array2, ArrayViewMut1};
+#[cfg(feature = "rayon")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default minimum number of coefficients of a polynomial, i.e., degree times
+/// number of moduli, from which its residues are processed in parallel.
+#[cfg(feature = "rayon")]
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 1 << 16;
+
+#[cfg(feature = "rayon")]
+static PARALLEL_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_PARALLEL_THRESHOLD);
+
+/// Returns the minimum number of coefficients of a polynomial from which its
+/// residues are processed in parallel.
+#[cfg(feature = "rayon")]
+pub fn parallel_threshold() -> usize {
+    PARALLEL_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the minimum number of coefficients of a polynomial, i.e., degree times
+/// number of moduli, from which its residues are processed in parallel. Below
+/// this threshold, the overhead of the threads exceeds the gain.
+#[cfg(feature = "rayon")]
+pub fn set_parallel_threshold(threshold: usize) {
+    PARALLEL_THRESHOLD.store(threshold, Ordering::Relaxed)
+}
+
+/// Apply `f` to each residue of the coefficients, i.e., each row, along with
+/// the index of its modulus.
+///
+/// When the `rayon` feature is enabled and there are at least
+/// [`parallel_threshold`] coefficients, the rows are processed in parallel;
+/// each row is processed by a single thread in the same way, so the output is
+/// the same and the computations on a row remain constant time.
+pub(crate) fn for_each_residue<F>(coefficients: &mut Array2<u64>, f: F)
+where
+    F: Fn(usize, ArrayViewMut1<u64>) + Send + Sync,
+{
+    #[cfg(feature = "rayon")]
+    if coefficients.len() >= parallel_threshold() {
+        use ndarray::{parallel::prelude::*, Axis};
+        coefficients
+            .axis_iter_mut(Axis(0))
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(i, row)| f(i, row));
+        return;
+    }
+
+    coefficients
+        .outer_iter_mut()
+        .enumerate()
+        .for_each(|(i, row)| f(i, row));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::for_each_residue;
+    use ndarray::Array2;
+
+    #[test]
+    fn for_each_residue_indices() {
+        let mut coefficients = Array2::zeros((3, 8));
+        for_each_residue(&mut coefficients, |i, mut row| row.fill(i as u64));
+        for (i, row) in coefficients.outer_iter().enumerate() {
+            assert!(row.iter().all(|c| *c == i as u64));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_is_identical() -> Result<(), Box<dyn std::error::Error>> {
+        use super::{set_parallel_threshold, DEFAULT_PARALLEL_THRESHOLD};
+        use crate::rq::{Context, Poly, Representation};
+        use rand::thread_rng;
+        use std::sync::Arc;
+
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(
+            &[1153, 4611686018326724609, 4611686018309947393],
+            16,
+        )?);
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let q = Poly::random(&ctx, Representation::Ntt, &mut rng);
+        let compute = || {
+            let mut r = p.clone();
+            r.change_representation(Representation::Ntt);
+            r *= &q;
+            r = &(&r + &q) - &(-&q);
+            r.change_representation(Representation::NttShoup);
+            r
+        };
+
+        set_parallel_threshold(usize::MAX);
+        let sequential = compute();
+        set_parallel_threshold(0);
+        let parallel = compute();
+        set_parallel_threshold(DEFAULT_PARALLEL_THRESHOLD);
+        assert_eq!(sequential, parallel);
+
+        Ok(())
+    }
+}
@@ -4,12 +4,13 @@
 
 use super::{Context, Poly, Representation};
 use crate::{
-    rns::{RnsScaler, ScalingFactor},
+    rns::{RnsScaler, Rounding, ScalingFactor},
     Error, Result,
 };
 use itertools::izip;
-use ndarray::{s, Array2, Axis};
-use std::sync::Arc;
+use ndarray::{s, Array2, ArrayView1, Axis};
+use std::{borrow::Cow, sync::Arc};
+use zeroize::{Zeroize, Zeroizing};
 
 /// Context extender.
 #[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -21,14 +22,58 @@ pub struct Scaler {
 }
 
 impl Scaler {
-    /// Create a scaler from a context `from` to a context `to`.
+    /// Create a scaler from a context `from` to a context `to`, rounding to the
+    /// nearest integer.
     pub fn new(from: &Arc<Context>, to: &Arc<Context>, factor: ScalingFactor) -> Result<Self> {
+        Self::new_with_rounding(from, to, factor, Rounding::Nearest)
+    }
+
+    /// Create a scaler from a context `from` to a context `to`, with the
+    /// specified rounding mode.
+    ///
+    /// Returns an error if the rounding mode is not supported in constant time
+    /// for this scaling factor; see [`RnsScaler::new_with_rounding`].
+    pub fn new_with_rounding(
+        from: &Arc<Context>,
+        to: &Arc<Context>,
+        factor: ScalingFactor,
+        rounding: Rounding,
+    ) -> Result<Self> {
+        let is_one = factor.is_one;
+        let scaler = RnsScaler::new_with_rounding(&from.rns, &to.rns, factor, rounding)?;
+        Self::from_rns_scaler(from, to, is_one, scaler)
+    }
+
+    /// Create a scaler from a context `from` to a context `to`, with the
+    /// specified rounding mode, which is exact for any scaling factor; see
+    /// [`RnsScaler::new_with_rounding_vt`].
+    ///
+    /// # Safety
+    ///
+    /// The scaling may run in variable time, so this should only be used to
+    /// scale polynomials which hold public data.
+    pub unsafe fn new_with_rounding_vt(
+        from: &Arc<Context>,
+        to: &Arc<Context>,
+        factor: ScalingFactor,
+        rounding: Rounding,
+    ) -> Result<Self> {
+        let is_one = factor.is_one;
+        let scaler = RnsScaler::new_with_rounding_vt(&from.rns, &to.rns, factor, rounding);
+        Self::from_rns_scaler(from, to, is_one, scaler)
+    }
+
+    fn from_rns_scaler(
+        from: &Arc<Context>,
+        to: &Arc<Context>,
+        is_one: bool,
+        scaler: RnsScaler,
+    ) -> Result<Self> {
         if from.degree != to.degree {
-            return Err(Error::Default("Incompatible degrees".to_string()));
+            return Err(Error::InvalidContext);
         }
-
         let mut number_common_moduli = 0;
-        if factor.is_one {
+        if is_one {
             for (qi, pi) in izip!(from.q.iter(), to.q.iter()) {
                 if qi == pi {
                     number_common_moduli += 1
@@ -38,8 +83,6 @@ impl Scaler {
             }
         }
 
-        let scaler = RnsScaler::new(&from.rns, &to.rns, factor);
-
         Ok(Self {
             from: from.clone(),
             to: to.clone(),
@@ -51,85 +94,196 @@ impl Scaler {
     /// Scale a polynomial
     pub(crate) fn scale(&self, p: &Poly) -> Result<Poly> {
         if p.ctx.as_ref() != self.from.as_ref() {
-            Err(Error::Default(
-                "The input polynomial does not have the correct context".to_string(),
-            ))
-        } else {
-            let mut representation = p.representation.clone();
-            if representation == Representation::NttShoup {
-                representation = Representation::Ntt;
-            }
+            return Err(Error::InvalidContext);
+        }
+        let mut out = Poly::zero(&self.to, Self::output_representation(p));
+        self.scale_into(p, &mut out)?;
+        Ok(out)
+    }
+
+    /// Scale a polynomial and store the result in `out`, which must be in the
+    /// context `to` and in the representation of the output, i.e., in Ntt
+    /// representation if the input is in NttShoup representation.
+    ///
+    /// When the input polynomial is in PowerBasis representation, this does
+    /// not allocate.
+    pub(crate) fn scale_into(&self, p: &Poly, out: &mut Poly) -> Result<()> {
+        if p.ctx.as_ref() != self.from.as_ref() {
+            return Err(Error::InvalidContext);
+        }
+        if out.ctx.as_ref() != self.to.as_ref()
+            || out.coefficients.dim() != (self.to.q.len(), self.to.degree)
+        {
+            return Err(Error::InvalidContext);
+        }
+        let representation = Self::output_representation(p);
+        if out.representation != representation {
+            return Err(Error::IncorrectRepresentation(
+                out.representation.clone(),
+                representation,
+            ));
+        }
+
+        let new_coefficients = &mut out.coefficients;
 
-            let mut new_coefficients = Array2::<u64>::zeros((self.to.q.len(), self.to.degree));
+        if self.number_common_moduli > 0 {
+            new_coefficients
+                .slice_mut(s![..self.number_common_moduli, ..])
+                .assign(&p.coefficients.slice(s![..self.number_common_moduli, ..]));
+        }
 
-            if self.number_common_moduli > 0 {
+        if self.number_common_moduli < self.to.q.len() {
+            let p_coefficients_powerbasis = Self::power_basis_coefficients(p);
+            // Conversion
+            izip!(
                 new_coefficients
-                    .slice_mut(s![..self.number_common_moduli, ..])
-                    .assign(&p.coefficients.slice(s![..self.number_common_moduli, ..]));
+                    .slice_mut(s![self.number_common_moduli.., ..])
+                    .axis_iter_mut(Axis(1)),
+                p_coefficients_powerbasis.axis_iter(Axis(1))
+            )
+            .for_each(|(new_column, column)| {
+                self.scaler
+                    .scale(column, new_column, self.number_common_moduli)
+            });
+            Self::zeroize_owned(p_coefficients_powerbasis);
+            // Forward NTT on the second half
+            if p.representation != Representation::PowerBasis {
+                self.forward_uncommon(new_coefficients, p.allow_variable_time_computations);
             }
+        }
 
-            if self.number_common_moduli < self.to.q.len() {
-                if p.representation == Representation::PowerBasis {
-                    izip!(
-                        new_coefficients
-                            .slice_mut(s![self.number_common_moduli.., ..])
-                            .axis_iter_mut(Axis(1)),
-                        p.coefficients.axis_iter(Axis(1))
-                    )
-                    .for_each(|(new_column, column)| {
-                        self.scaler
-                            .scale(column, new_column, self.number_common_moduli)
-                    });
-                } else if self.number_common_moduli < self.to.q.len() {
-                    let mut p_coefficients_powerbasis = p.coefficients.clone();
-                    // Backward NTT
-                    if p.allow_variable_time_computations {
-                        izip!(p_coefficients_powerbasis.outer_iter_mut(), p.ctx.ops.iter())
-                            .for_each(|(mut v, op)| unsafe { op.backward_vt(v.as_mut_ptr()) });
-                    } else {
-                        izip!(p_coefficients_powerbasis.outer_iter_mut(), p.ctx.ops.iter())
-                            .for_each(|(mut v, op)| op.backward(v.as_slice_mut().unwrap()));
-                    }
-                    // Conversion
-                    izip!(
-                        new_coefficients
-                            .slice_mut(s![self.number_common_moduli.., ..])
-                            .axis_iter_mut(Axis(1)),
-                        p_coefficients_powerbasis.axis_iter(Axis(1))
+        out.allow_variable_time_computations = p.allow_variable_time_computations;
+        out.has_lazy_coefficients = false;
+        Ok(())
+    }
+
+    /// Scale a batch of polynomials, such as the components of a ciphertext.
+    /// The output is identical to scaling each polynomial separately; when the
+    /// `rayon` feature is enabled, the polynomials are scaled in parallel.
+    ///
+    /// Returns an error if any of the polynomials is not in the context `from`.
+    pub fn scale_batch(&self, polys: &[Poly]) -> Result<Vec<Poly>> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            polys.par_iter().map(|p| self.scale(p)).collect()
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        {
+            polys.iter().map(|p| self.scale(p)).collect()
+        }
+    }
+
+    /// Scale a polynomial in place; this requires the contexts `from` and `to`
+    /// to have the same number of moduli. A polynomial in NttShoup
+    /// representation is output in Ntt representation.
+    ///
+    /// Only a buffer of the size of the number of moduli is allocated.
+    pub(crate) fn scale_assign(&self, p: &mut Poly) -> Result<()> {
+        if p.ctx.as_ref() != self.from.as_ref() {
+            return Err(Error::InvalidContext);
+        }
+        if self.from.q.len() != self.to.q.len() {
+            return Err(Error::InvalidContext);
+        }
+        if p.representation == Representation::NttShoup {
+            unsafe { p.override_representation(Representation::Ntt) }
+        }
+
+        if self.number_common_moduli < self.to.q.len() {
+            let is_ntt = p.representation == Representation::Ntt;
+            // Backward NTT
+            if is_ntt {
+                if p.allow_variable_time_computations {
+                    izip!(p.coefficients.outer_iter_mut(), p.ctx.ops.iter())
+                        .for_each(|(mut v, op)| unsafe { op.backward_vt(v.as_mut_ptr()) });
+                } else {
+                    izip!(p.coefficients.outer_iter_mut(), p.ctx.ops.iter())
+                        .for_each(|(mut v, op)| op.backward(v.as_slice_mut().unwrap()));
+                }
+            }
+            // Conversion, one coefficient at a time
+            let mut rests = Zeroizing::new(vec![0u64; self.from.q.len()]);
+            p.coefficients
+                .axis_iter_mut(Axis(1))
+                .for_each(|mut column| {
+                    izip!(rests.iter_mut(), column.iter()).for_each(|(r, c)| *r = *c);
+                    self.scaler.scale(
+                        ArrayView1::from(rests.as_slice()),
+                        column.slice_mut(s![self.number_common_moduli..]),
+                        self.number_common_moduli,
                     )
-                    .for_each(|(new_column, column)| {
-                        self.scaler
-                            .scale(column, new_column, self.number_common_moduli)
-                    });
-                    // Forward NTT on the second half
-                    if p.allow_variable_time_computations {
-                        izip!(
-                            new_coefficients
-                                .slice_mut(s![self.number_common_moduli.., ..])
-                                .outer_iter_mut(),
-                            &self.to.ops[self.number_common_moduli..]
-                        )
+                });
+            // Forward NTT; the common moduli use the same operators in both contexts
+            if is_ntt {
+                if p.allow_variable_time_computations {
+                    izip!(p.coefficients.outer_iter_mut(), self.to.ops.iter())
                         .for_each(|(mut v, op)| unsafe { op.forward_vt(v.as_mut_ptr()) });
-                    } else {
-                        izip!(
-                            new_coefficients
-                                .slice_mut(s![self.number_common_moduli.., ..])
-                                .outer_iter_mut(),
-                            &self.to.ops[self.number_common_moduli..]
-                        )
+                } else {
+                    izip!(p.coefficients.outer_iter_mut(), self.to.ops.iter())
                         .for_each(|(mut v, op)| op.forward(v.as_slice_mut().unwrap()));
-                    }
                 }
             }
+        }
 
-            Ok(Poly {
-                ctx: self.to.clone(),
-                representation,
-                allow_variable_time_computations: p.allow_variable_time_computations,
-                coefficients: new_coefficients,
-                coefficients_shoup: None,
-                has_lazy_coefficients: false,
-            })
+        p.ctx = self.to.clone();
+        Ok(())
+    }
+
+    // The representation of the scaled polynomial.
+    fn output_representation(p: &Poly) -> Representation {
+        if p.representation == Representation::NttShoup {
+            Representation::Ntt
+        } else {
+            p.representation.clone()
+        }
+    }
+
+    // The coefficients of the polynomial in PowerBasis representation.
+    fn power_basis_coefficients(p: &Poly) -> Cow<'_, Array2<u64>> {
+        if p.representation == Representation::PowerBasis {
+            Cow::Borrowed(&p.coefficients)
+        } else {
+            let mut p_coefficients_powerbasis = p.coefficients.clone();
+            // Backward NTT
+            if p.allow_variable_time_computations {
+                izip!(p_coefficients_powerbasis.outer_iter_mut(), p.ctx.ops.iter())
+                    .for_each(|(mut v, op)| unsafe { op.backward_vt(v.as_mut_ptr()) });
+            } else {
+                izip!(p_coefficients_powerbasis.outer_iter_mut(), p.ctx.ops.iter())
+                    .for_each(|(mut v, op)| op.backward(v.as_slice_mut().unwrap()));
+            }
+            Cow::Owned(p_coefficients_powerbasis)
+        }
+    }
+
+    // Clears the coefficients returned by `power_basis_coefficients` when they
+    // were computed from the polynomial.
+    fn zeroize_owned(coefficients: Cow<'_, Array2<u64>>) {
+        if let Cow::Owned(mut coefficients) = coefficients {
+            coefficients.as_slice_mut().unwrap().zeroize()
+        }
+    }
+
+    // Forward NTT on the rows that are not common to both contexts.
+    fn forward_uncommon(&self, coefficients: &mut Array2<u64>, variable_time: bool) {
+        if variable_time {
+            izip!(
+                coefficients
+                    .slice_mut(s![self.number_common_moduli.., ..])
+                    .outer_iter_mut(),
+                &self.to.ops[self.number_common_moduli..]
+            )
+            .for_each(|(mut v, op)| unsafe { op.forward_vt(v.as_mut_ptr()) });
+        } else {
+            izip!(
+                coefficients
+                    .slice_mut(s![self.number_common_moduli.., ..])
+                    .outer_iter_mut(),
+                &self.to.ops[self.number_common_moduli..]
+            )
+            .for_each(|(mut v, op)| op.forward(v.as_slice_mut().unwrap()));
         }
     }
 }
@@ -137,10 +291,15 @@ impl Scaler {
 #[cfg(test)]
 mod tests {
     use super::{Scaler, ScalingFactor};
-    use crate::rq::{Context, Poly, Representation};
+    use crate::test_allocator::{allocations, leaked_canaries};
+    use crate::{
+        rns::Rounding,
+        rq::{switcher::Switcher, traits::TryConvertFrom, Context, Poly, Representation},
+    };
     use itertools::Itertools;
-    use num_bigint::BigUint;
-    use num_traits::{One, Zero};
+    use ndarray::Array2;
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::Signed;
     use rand::thread_rng;
     use std::error::Error;
 
@@ -157,57 +316,261 @@ mod tests {
         4611686018257518593,
     ];
 
+    // Exact value of x * n / d rounded with the specified rounding mode.
+    fn scale_reference(x: &BigInt, n: &BigUint, d: &BigUint, rounding: Rounding) -> BigInt {
+        // Floor of a / b for b > 0.
+        let div_floor = |a: BigInt, b: BigInt| -> BigInt {
+            if a.is_negative() {
+                let a: BigInt = -a + &b - 1u64;
+                -(a / b)
+            } else {
+                a / b
+            }
+        };
+        let xn = x * BigInt::from(n.clone());
+        let d = BigInt::from(d.clone());
+        match rounding {
+            Rounding::Floor => div_floor(xn, d),
+            Rounding::Ceiling => -div_floor(-xn, d),
+            Rounding::Nearest => {
+                let magnitude = div_floor(2 * xn.abs() + &d, 2 * d);
+                if x.is_negative() {
+                    -magnitude
+                } else {
+                    magnitude
+                }
+            }
+        }
+    }
+
     #[test]
     fn scaler() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
         let ntests = 100;
         let from = Context::new_arc(Q, 16)?;
         let to = Context::new_arc(P, 16)?;
+        let from_modulus = BigInt::from(from.modulus().clone());
+        let to_modulus = BigInt::from(to.modulus().clone());
 
         for numerator in &[1u64, 2, 3, 100, 1000, 4611686018326724610] {
             for denominator in &[1u64, 2, 3, 4, 100, 101, 1000, 1001, 4611686018326724610] {
                 let n = BigUint::from(*numerator);
                 let d = BigUint::from(*denominator);
 
-                let scaler = Scaler::new(&from, &to, ScalingFactor::new(&n, &d))?;
-
-                for _ in 0..ntests {
-                    let mut poly = Poly::random(&from, Representation::PowerBasis, &mut rng);
-                    let poly_biguint = Vec::<BigUint>::from(&poly);
-
-                    let scaled_poly = scaler.scale(&poly)?;
-                    let scaled_biguint = Vec::<BigUint>::from(&scaled_poly);
-
-                    let expected = poly_biguint
-                        .iter()
-                        .map(|i| {
-                            if i >= &(from.modulus() >> 1usize) {
-                                if &d & BigUint::one() == BigUint::zero() {
-                                    to.modulus()
-                                        - (&(&(from.modulus() - i) * &n + ((&d >> 1usize) - 1u64))
-                                            / &d)
-                                            % to.modulus()
-                                } else {
-                                    to.modulus()
-                                        - (&(&(from.modulus() - i) * &n + (&d >> 1)) / &d)
-                                            % to.modulus()
+                for rounding in [Rounding::Floor, Rounding::Nearest, Rounding::Ceiling] {
+                    let scaler = Scaler::new_with_rounding(
+                        &from,
+                        &to,
+                        ScalingFactor::new(&n, &d),
+                        rounding,
+                    )?;
+
+                    for _ in 0..ntests {
+                        let mut poly = Poly::random(&from, Representation::PowerBasis, &mut rng);
+                        let poly_biguint = Vec::<BigUint>::from(&poly);
+
+                        let scaled_poly = scaler.scale(&poly)?;
+                        let scaled_biguint = Vec::<BigUint>::from(&scaled_poly);
+
+                        let expected = poly_biguint
+                            .iter()
+                            .map(|i| {
+                                let mut x = BigInt::from(i.clone());
+                                if i >= &(from.modulus() >> 1usize) {
+                                    x -= &from_modulus;
                                 }
-                            } else {
-                                ((i * &n + (&d >> 1)) / &d) % to.modulus()
-                            }
-                        })
-                        .collect_vec();
-                    assert_eq!(expected, scaled_biguint);
-
-                    poly.change_representation(Representation::Ntt);
-                    let mut scaled_poly = scaler.scale(&poly)?;
-                    scaled_poly.change_representation(Representation::PowerBasis);
-                    let scaled_biguint = Vec::<BigUint>::from(&scaled_poly);
-                    assert_eq!(expected, scaled_biguint);
+                                let y = scale_reference(&x, &n, &d, rounding) % &to_modulus;
+                                ((y + &to_modulus) % &to_modulus).to_biguint().unwrap()
+                            })
+                            .collect_vec();
+                        assert_eq!(expected, scaled_biguint);
+
+                        poly.change_representation(Representation::Ntt);
+                        let mut scaled_poly = scaler.scale(&poly)?;
+                        scaled_poly.change_representation(Representation::PowerBasis);
+                        let scaled_biguint = Vec::<BigUint>::from(&scaled_poly);
+                        assert_eq!(expected, scaled_biguint);
+                    }
                 }
             }
         }
 
+        // The floor and the ceiling are only exact in variable time for a large
+        // denominator.
+        let d = from.modulus().clone();
+        for rounding in [Rounding::Floor, Rounding::Ceiling] {
+            let factor = ScalingFactor::new(&BigUint::from(1u64), &d);
+            assert_eq!(
+                Scaler::new_with_rounding(&from, &to, factor.clone(), rounding),
+                Err(crate::Error::UnsupportedRounding(rounding))
+            );
+            let scaler = unsafe { Scaler::new_with_rounding_vt(&from, &to, factor, rounding)? };
+            for _ in 0..ntests {
+                let poly = Poly::random(&from, Representation::PowerBasis, &mut rng);
+                let expected = Vec::<BigUint>::from(&poly)
+                    .iter()
+                    .map(|i| {
+                        let mut x = BigInt::from(i.clone());
+                        if i >= &(from.modulus() >> 1usize) {
+                            x -= &from_modulus;
+                        }
+                        let y = scale_reference(&x, &BigUint::from(1u64), &d, rounding);
+                        ((y + &to_modulus) % &to_modulus).to_biguint().unwrap()
+                    })
+                    .collect_vec();
+                assert_eq!(expected, Vec::<BigUint>::from(&scaler.scale(&poly)?));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_into_and_assign() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let from = Context::new_arc(Q, 16)?;
+        let to = Context::new_arc(P, 16)?;
+        let smaller = Context::new_arc(&P[..2], 16)?;
+
+        for factor in [
+            ScalingFactor::one(),
+            ScalingFactor::new(&BigUint::from(3u64), &BigUint::from(1001u64)),
+        ] {
+            let scaler = Scaler::new(&from, &to, factor.clone())?;
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                let poly = Poly::random(&from, representation.clone(), &mut rng);
+                let expected = scaler.scale(&poly)?;
+
+                let mut out = Poly::random(&to, expected.representation.clone(), &mut rng);
+                poly.scale_into(&scaler, &mut out)?;
+                assert_eq!(out, expected);
+
+                let mut poly_assign = poly.clone();
+                poly_assign.scale_assign(&scaler)?;
+                assert_eq!(poly_assign, expected);
+            }
+
+            // The output must be in the output context and representation.
+            let poly = Poly::random(&from, Representation::PowerBasis, &mut rng);
+            let mut out = Poly::zero(&from, Representation::PowerBasis);
+            assert!(poly.scale_into(&scaler, &mut out).is_err());
+            let mut out = Poly::zero(&smaller, Representation::PowerBasis);
+            assert!(poly.scale_into(&scaler, &mut out).is_err());
+            let mut out = Poly::zero(&to, Representation::Ntt);
+            assert!(poly.scale_into(&scaler, &mut out).is_err());
+            let mut out = Poly::zero(&to, Representation::PowerBasis);
+            assert!(Poly::zero(&to, Representation::PowerBasis)
+                .scale_into(&scaler, &mut out)
+                .is_err());
+
+            // Scaling in place requires the same number of moduli.
+            let scaler = Scaler::new(&from, &smaller, factor)?;
+            let mut poly = Poly::random(&from, Representation::PowerBasis, &mut rng);
+            assert!(poly.scale_assign(&scaler).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_batch() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let from = Context::new_arc(Q, 16)?;
+        let to = Context::new_arc(P, 16)?;
+        let representations = [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ];
+
+        for factor in [
+            ScalingFactor::one(),
+            ScalingFactor::new(&BigUint::from(3u64), &BigUint::from(1001u64)),
+        ] {
+            let scaler = Scaler::new(&from, &to, factor)?;
+            for size in 0..5 {
+                let polys = (0..size)
+                    .map(|i| Poly::random(&from, representations[i % 3].clone(), &mut rng))
+                    .collect_vec();
+                let expected = polys
+                    .iter()
+                    .map(|p| scaler.scale(p))
+                    .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(scaler.scale_batch(&polys)?, expected);
+            }
+
+            let polys = vec![
+                Poly::random(&from, Representation::PowerBasis, &mut rng),
+                Poly::random(&to, Representation::PowerBasis, &mut rng),
+            ];
+            assert!(scaler.scale_batch(&polys).is_err());
+        }
+
+        // The switcher uses the same batch scaling.
+        let switcher = Switcher::new(&from, &to)?;
+        let polys = (0..2)
+            .map(|_| Poly::random(&from, Representation::Ntt, &mut rng))
+            .collect_vec();
+        let expected = polys
+            .iter()
+            .map(|p| p.mod_switch_to(&switcher))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(switcher.switch_batch(&polys)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_into_does_not_allocate() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let from = Context::new_arc(Q, 16)?;
+        let to = Context::new_arc(P, 16)?;
+        let scaler = Scaler::new(
+            &from,
+            &to,
+            ScalingFactor::new(&BigUint::from(3u64), &BigUint::from(1001u64)),
+        )?;
+        let poly = Poly::random(&from, Representation::PowerBasis, &mut rng);
+        let mut out = Poly::zero(&to, Representation::PowerBasis);
+
+        let before = allocations();
+        let _ = poly.scale(&scaler)?;
+        assert!(allocations() > before);
+
+        let before = allocations();
+        poly.scale_into(&scaler, &mut out)?;
+        assert_eq!(allocations(), before);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scale_zeroizes_intermediates() -> Result<(), Box<dyn Error>> {
+        let from = Context::new_arc(Q, 16)?;
+        let to = Context::new_arc(P, 16)?;
+        let scaler = Scaler::new(&from, &to, ScalingFactor::one())?;
+        let canary = 0x0123456789abcdef;
+        let canaries = [canary, canary];
+        let mut p = Poly::try_convert_from(
+            Array2::from_elem((Q.len(), 16), canary),
+            &from,
+            false,
+            Representation::PowerBasis,
+        )?;
+        p.change_representation(Representation::Ntt);
+
+        // The coefficients converted back to PowerBasis representation are
+        // cleared once the polynomial is scaled.
+        let leaks = leaked_canaries(canaries, || {
+            drop(scaler.scale(&p).unwrap());
+            drop(scaler.scale_batch(&[p.clone(), p.clone()]).unwrap());
+        });
+        assert_eq!(leaks, 0);
+
         Ok(())
     }
 }
@@ -1,15 +1,41 @@
 //! Implementation of serialization and deserialization.
 
-use std::sync::Arc;
+use std::{cell::RefCell, sync::Arc};
 
-use super::{traits::TryConvertFrom, Context, Poly};
-use crate::{proto::rq::Rq, Error};
+use super::{traits::TryConvertFrom, Context, Poly, Representation};
+use crate::{proto::rq::Rq, zq::Modulus, Error};
 use fhe_traits::{DeserializeWithContext, Serialize};
+use fhe_util::transcode_to_bytes_into;
+use itertools::izip;
 use prost::Message;
+use subtle::{Choice, ConstantTimeEq, ConstantTimeLess};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Bound on the absolute value of the coefficients of the polynomials that can
+/// be encoded with `Poly::to_small_bytes`.
+const SMALL_BYTES_BOUND: i64 = 7;
+
+thread_local! {
+    /// Buffer for the residues converted to PowerBasis representation before
+    /// being serialized.
+    static RESIDUE: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
 
 impl Serialize for Poly {
     fn to_bytes(&self) -> Vec<u8> {
-        Rq::from(self).encode_to_vec()
+        self.to_bytes_with_packing(true)
+    }
+}
+
+/// Number of bits of the serialization of each coefficient modulo `qi`: the
+/// number of bits of qi - 1 when the coefficients are bit-packed, and this
+/// number rounded up to a multiple of 8 when they are byte-aligned.
+pub(super) fn coefficient_nbits(qi: &Modulus, byte_aligned: bool) -> usize {
+    let nbits = 64 - (**qi - 1).leading_zeros() as usize;
+    if byte_aligned {
+        nbits.next_multiple_of(8)
+    } else {
+        nbits
     }
 }
 
@@ -18,19 +44,366 @@ impl DeserializeWithContext for Poly {
     type Context = Context;
 
     fn from_bytes(bytes: &[u8], ctx: &Arc<Context>) -> Result<Self, Self::Error> {
+        Poly::from_bytes_with_variable_time(bytes, ctx, false)
+    }
+}
+
+impl Poly {
+    /// Appends the serialization of the coefficients of the polynomial to
+    /// `out`: the residues modulo each modulus, in PowerBasis representation,
+    /// serialized as in [`Modulus::serialize_vec`](crate::zq::Modulus::serialize_vec)
+    /// and concatenated. These are the same bytes as the coefficients of the
+    /// protobuf message of [`Serialize::to_bytes`].
+    ///
+    /// The polynomial is not cloned to change its representation; the only
+    /// allocation is to grow `out` when its capacity is too small.
+    ///
+    /// Panics if the polynomial has lazy coefficients.
+    pub fn serialize_into(&self, out: &mut Vec<u8>) {
+        self.serialize_into_with_packing(out, true)
+    }
+
+    /// Serializes the polynomial as [`Serialize::to_bytes`] when `packed` is
+    /// set, i.e., with the coefficients modulo q_i bit-packed using exactly
+    /// ceil(log2(q_i)) bits each.
+    ///
+    /// Otherwise, each coefficient is written on a whole number of bytes, so
+    /// that the serialization is larger unless the number of bits of every
+    /// modulus is a multiple of 8. The serialization records the layout, so
+    /// that [`Poly::from_bytes`] decodes both.
+    ///
+    /// Panics if the polynomial has lazy coefficients.
+    pub fn to_bytes_with_packing(&self, packed: bool) -> Vec<u8> {
+        let mut proto = Rq::from_poly_without_coefficients(self);
+        proto.byte_aligned = !packed;
+        self.serialize_into_with_packing(&mut proto.coefficients, packed);
+        proto.encode_to_vec()
+    }
+
+    fn serialize_into_with_packing(&self, out: &mut Vec<u8>, packed: bool) {
+        assert!(!self.has_lazy_coefficients);
+
+        let degree = self.ctx.degree;
+        let length = |qi: &Modulus| coefficient_nbits(qi, !packed) * degree / 8;
+        let mut start = out.len();
+        out.resize(start + self.ctx.q.iter().map(length).sum::<usize>(), 0);
+
+        // The residues are converted in a buffer reused across calls, which is
+        // zeroized after use since the polynomial may be secret.
+        RESIDUE.with_borrow_mut(|residue| {
+            for (v, qi, op) in izip!(
+                self.coefficients.outer_iter(),
+                self.ctx.q.iter(),
+                self.ctx.ops.iter()
+            ) {
+                let end = start + length(qi);
+                let nbits = coefficient_nbits(qi, !packed);
+                if self.representation == Representation::PowerBasis {
+                    transcode_to_bytes_into(v.as_slice().unwrap(), nbits, &mut out[start..end]);
+                } else {
+                    residue.clear();
+                    residue.extend_from_slice(v.as_slice().unwrap());
+                    if self.allow_variable_time_computations {
+                        unsafe { op.backward_vt(residue.as_mut_ptr()) }
+                    } else {
+                        op.backward(residue)
+                    }
+                    transcode_to_bytes_into(residue, nbits, &mut out[start..end]);
+                }
+                start = end;
+            }
+            residue.zeroize();
+        });
+    }
+
+    /// Exports the coefficients in the layout of the coefficient data of
+    /// Microsoft SEAL 4.1, i.e., the buffer returned by `data()` for a
+    /// `Plaintext`, or for one polynomial of a `Ciphertext`: full-width
+    /// little-endian u64 words, modulus-major, with `degree` words per modulus.
+    /// This is not the stream written by SEAL's `save`, which adds a
+    /// `SEALHeader` and may compress the data.
+    ///
+    /// In PowerBasis representation, the words are the coefficients in
+    /// increasing degree. In Ntt and NttShoup representations, the words are
+    /// the evaluations in natural order instead of the bit-reversed order used
+    /// by this crate; note that they match SEAL's values only if the same
+    /// primitive roots of unity are used, so cross-validation is exact in
+    /// PowerBasis representation.
+    ///
+    /// Panics if the polynomial has lazy coefficients.
+    pub fn to_seal_bytes(&self) -> Vec<u8> {
+        assert!(!self.has_lazy_coefficients);
+
+        let mut out = Vec::with_capacity(8 * self.coefficients.len());
+        for v in self.coefficients.outer_iter() {
+            if self.representation == Representation::PowerBasis {
+                v.iter()
+                    .for_each(|c| out.extend_from_slice(&c.to_le_bytes()));
+            } else {
+                self.ctx
+                    .bitrev
+                    .iter()
+                    .for_each(|j| out.extend_from_slice(&v[*j].to_le_bytes()));
+            }
+        }
+        out
+    }
+
+    /// Imports coefficients in the layout of [`Poly::to_seal_bytes`] as a
+    /// polynomial in representation `representation`.
+    ///
+    /// Returns an error if the number of bytes is not 8 times the degree times
+    /// the number of moduli, or if a word is not reduced modulo its modulus.
+    pub fn from_seal_bytes(
+        bytes: &[u8],
+        ctx: &Arc<Context>,
+        representation: Representation,
+    ) -> Result<Self, Error> {
+        let expected_length = 8 * ctx.degree * ctx.q.len();
+        if bytes.len() != expected_length {
+            return Err(Error::Serialization(format!(
+                "Invalid SEAL coefficient data: got {} bytes, expected {expected_length}",
+                bytes.len()
+            )));
+        }
+
+        let mut p = Poly::zero(ctx, representation);
+        for (mut v, qi, chunk) in izip!(
+            p.coefficients.outer_iter_mut(),
+            ctx.q.iter(),
+            bytes.chunks_exact(8 * ctx.degree)
+        ) {
+            for (k, word) in chunk.chunks_exact(8).enumerate() {
+                let c = u64::from_le_bytes(word.try_into().unwrap());
+                if c >= **qi {
+                    return Err(Error::Serialization(format!(
+                        "Invalid SEAL coefficient data: {c} is not reduced modulo {}",
+                        **qi
+                    )));
+                }
+                if p.representation == Representation::PowerBasis {
+                    v[k] = c
+                } else {
+                    v[ctx.bitrev[k]] = c
+                }
+            }
+        }
+        Ok(p)
+    }
+
+    /// Deserializes a polynomial like [`Poly::from_bytes`], and allows
+    /// variable time computations on it when `variable_time` is set, or when
+    /// the serialized polynomial allowed them.
+    ///
+    /// Setting `variable_time` should be reserved to public material, such
+    /// as evaluation keys.
+    pub fn from_bytes_with_variable_time(
+        bytes: &[u8],
+        ctx: &Arc<Context>,
+        variable_time: bool,
+    ) -> Result<Self, Error> {
         let rq: Rq = Message::decode(bytes).map_err(|e| Error::Serialization(e.to_string()))?;
-        Poly::try_convert_from(&rq, ctx, false, None)
+        Poly::try_convert_from(&rq, ctx, variable_time, None)
+    }
+
+    /// Encodes the polynomial as a base64 string (with the standard alphabet
+    /// and padding) of its serialization.
+    pub fn to_base64(&self) -> String {
+        encode_base64(&self.to_bytes())
+    }
+
+    /// Decodes a polynomial from a base64 string produced by `to_base64`.
+    ///
+    /// Returns an error if the string is not valid padded base64, or if the
+    /// decoded bytes are not a valid serialization of a polynomial in `ctx`.
+    pub fn from_base64(s: &str, ctx: &Arc<Context>) -> Result<Self, Error> {
+        Poly::from_bytes(&decode_base64(s)?, ctx)
+    }
+
+    /// Encodes the polynomial as a lowercase hexadecimal string of its
+    /// serialization.
+    pub fn to_hex(&self) -> String {
+        self.to_bytes().iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// Decodes a polynomial from a hexadecimal string produced by `to_hex`;
+    /// both lowercase and uppercase digits are accepted.
+    ///
+    /// Returns an error if the string is not valid hexadecimal, or if the
+    /// decoded bytes are not a valid serialization of a polynomial in `ctx`.
+    pub fn from_hex(s: &str, ctx: &Arc<Context>) -> Result<Self, Error> {
+        if s.len() % 2 != 0 {
+            return Err(Error::Serialization(
+                "Invalid hexadecimal string: odd length".to_string(),
+            ));
+        }
+        let bytes = s
+            .as_bytes()
+            .chunks(2)
+            .map(|c| {
+                std::str::from_utf8(c)
+                    .ok()
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+                    .filter(|_| c.iter().all(u8::is_ascii_hexdigit))
+                    .ok_or_else(|| {
+                        Error::Serialization(
+                            "Invalid hexadecimal string: invalid character".to_string(),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<u8>, Error>>()?;
+        Poly::from_bytes(&bytes, ctx)
+    }
+
+    /// Encodes a polynomial with small coefficients, such as a secret or an
+    /// error polynomial, using 4 bits per coefficient. The centered
+    /// coefficients must be in [-7, 7].
+    ///
+    /// Returns an error if a coefficient does not fit in this range.
+    pub fn to_small_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut p = Zeroizing::new(self.clone());
+        if p.representation != Representation::PowerBasis {
+            p.change_representation(Representation::PowerBasis);
+        }
+
+        let q0 = &self.ctx.q[0];
+        let coefficients = Zeroizing::new(q0.center_vec(p.coefficients.row(0).as_slice().unwrap()));
+        // The range check does not short-circuit, so that its running time does
+        // not depend on the (secret) coefficients.
+        let width = (2 * SMALL_BYTES_BOUND + 1) as u64;
+        let mut fits = coefficients.iter().fold(Choice::from(1), |acc, c| {
+            acc & ((c + SMALL_BYTES_BOUND) as u64).ct_lt(&width)
+        });
+        for (qi, pi) in izip!(self.ctx.q.iter(), p.coefficients.outer_iter()).skip(1) {
+            fits = izip!(coefficients.iter(), pi.iter())
+                .fold(fits, |acc, (c, pij)| acc & qi.reduce_i64(*c).ct_eq(pij));
+        }
+        if !bool::from(fits) {
+            return Err(Error::CoefficientOutOfRange {
+                bound: SMALL_BYTES_BOUND as u64,
+            });
+        }
+
+        Ok(coefficients
+            .chunks(2)
+            .map(|c| ((c[0] as u8) & 0xf) | ((c[1] as u8) << 4))
+            .collect())
+    }
+
+    /// Decodes a polynomial encoded with `to_small_bytes` in the context `ctx`,
+    /// and outputs it in the given representation.
+    ///
+    /// Returns an error if the number of bytes does not match the degree of the
+    /// context, or if a coefficient is out of range.
+    pub fn from_small_bytes(
+        bytes: &[u8],
+        ctx: &Arc<Context>,
+        representation: Representation,
+    ) -> Result<Self, Error> {
+        if 2 * bytes.len() != ctx.degree {
+            return Err(Error::Serialization(format!(
+                "Invalid small encoding: expected {} bytes, got {}",
+                ctx.degree / 2,
+                bytes.len()
+            )));
+        }
+
+        // Sign-extend the 4-bit values.
+        let coefficients = Zeroizing::new(
+            bytes
+                .iter()
+                .flat_map(|b| [(((b << 4) as i8) >> 4) as i64, ((*b as i8) >> 4) as i64])
+                .collect::<Vec<i64>>(),
+        );
+        if coefficients.iter().any(|c| *c < -SMALL_BYTES_BOUND) {
+            return Err(Error::Serialization(
+                "Invalid small encoding: coefficient out of range".to_string(),
+            ));
+        }
+
+        let mut p = Poly::try_convert_from(
+            coefficients.as_ref() as &[i64],
+            ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        if representation != Representation::PowerBasis {
+            p.change_representation(representation);
+        }
+        Ok(p)
     }
 }
 
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes in base64 with the standard alphabet and padding.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | (b[2] as u32);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes a base64 string with the standard alphabet and padding. The
+/// padding is mandatory, and the unused bits of the last character must be 0.
+fn decode_base64(s: &str) -> Result<Vec<u8>, Error> {
+    let invalid = |reason: &str| Error::Serialization(format!("Invalid base64 string: {reason}"));
+
+    let s = s.as_bytes();
+    if s.len() % 4 != 0 {
+        return Err(invalid("the length is not a multiple of 4"));
+    }
+
+    let padding = s.iter().rev().take_while(|c| **c == b'=').count();
+    if padding > 2 {
+        return Err(invalid("too much padding"));
+    }
+
+    let mut out = Vec::with_capacity(s.len() / 4 * 3);
+    for (i, chunk) in s.chunks(4).enumerate() {
+        let chunk_padding = if (i + 1) * 4 == s.len() { padding } else { 0 };
+        let mut n = 0u32;
+        for c in &chunk[..4 - chunk_padding] {
+            let v = BASE64_ALPHABET
+                .iter()
+                .position(|a| a == c)
+                .ok_or_else(|| invalid("invalid character"))?;
+            n = (n << 6) | v as u32;
+        }
+        n <<= 6 * chunk_padding;
+        if n & ((1 << (8 * chunk_padding)) - 1) != 0 {
+            return Err(invalid("non-zero trailing bits"));
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - chunk_padding]);
+    }
+    Ok(out)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{error::Error, sync::Arc};
 
     use fhe_traits::{DeserializeWithContext, Serialize};
+    use prost::Message;
     use rand::thread_rng;
 
-    use crate::rq::{Context, Poly, Representation};
+    use crate::proto::rq::Rq;
+    use crate::rq::{traits::TryConvertFrom, Context, Poly, Representation};
+    use crate::test_allocator::allocations;
 
     const Q: &[u64; 3] = &[
         4611686018282684417,
@@ -38,6 +411,195 @@ mod tests {
         4611686018309947393,
     ];
 
+    #[test]
+    fn from_bytes_with_variable_time() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(Q, 16)?);
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+            let p_public = unsafe { p.clone_public() };
+            for (q, serialized_flag) in [(&p, false), (&p_public, true)] {
+                let bytes = q.to_bytes();
+
+                // The flag of the serialization is kept by default.
+                let r = Poly::from_bytes(&bytes, &ctx)?;
+                assert_eq!(r.allows_variable_time(), serialized_flag);
+                let r = Poly::try_convert_from(&Rq::decode(bytes.as_slice())?, &ctx, false, None)?;
+                assert_eq!(r.allows_variable_time(), serialized_flag);
+
+                // Public material can be deserialized with the flag set.
+                for variable_time in [false, true] {
+                    let r = Poly::from_bytes_with_variable_time(&bytes, &ctx, variable_time)?;
+                    assert_eq!(r.allows_variable_time(), serialized_flag || variable_time);
+                    assert_eq!(r.coefficients(), q.coefficients());
+                    assert_eq!(r.representation(), &representation);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn seal_bytes() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&Q[..2], 8)?);
+
+        // TODO: Check in golden vectors generated with SEAL 4.1. The expected
+        // bytes below are derived from SEAL's documented layout, and were not
+        // produced by SEAL itself.
+
+        // In PowerBasis representation, the words are the coefficients of
+        // each residue in increasing degree, modulus-major.
+        let p = Poly::try_convert_from(
+            &[0i64, 1, 2, 3, 4, 5, 6, -1] as &[i64],
+            &ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        let bytes = p.to_seal_bytes();
+        assert_eq!(bytes.len(), 8 * 8 * 2);
+        assert_eq!(
+            bytes[..16],
+            [0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        let expected = Q[..2]
+            .iter()
+            .flat_map(|qi| [0, 1, 2, 3, 4, 5, 6, qi - 1])
+            .flat_map(u64::to_le_bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, expected);
+
+        // In Ntt representation, the evaluations of x are the odd powers of a
+        // primitive root of unity in natural order.
+        let mut x = Poly::try_convert_from(
+            &[0i64, 1] as &[i64],
+            &ctx,
+            false,
+            Representation::PowerBasis,
+        )?;
+        x.change_representation(Representation::Ntt);
+        let bytes = x.to_seal_bytes();
+        for (words, qi) in bytes.chunks_exact(8 * 8).zip(ctx.q.iter()) {
+            let w = words
+                .chunks_exact(8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                .collect::<Vec<_>>();
+            let w0_squared = qi.mul(w[0], w[0]);
+            for k in 1..8 {
+                assert_eq!(w[k], qi.mul(w[k - 1], w0_squared));
+            }
+        }
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation.clone(), &mut rng);
+            let bytes = p.to_seal_bytes();
+            let q = Poly::from_seal_bytes(&bytes, &ctx, representation.clone())?;
+            assert_eq!(q, p);
+            assert_eq!(q.representation(), &representation);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn seal_bytes_invalid() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(&Q[..2], 8)?);
+        let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+        let mut bytes = p.to_seal_bytes();
+
+        for length in [0, bytes.len() - 8, bytes.len() + 8] {
+            let mut b = bytes.clone();
+            b.resize(length, 0);
+            assert!(Poly::from_seal_bytes(&b, &ctx, Representation::PowerBasis).is_err());
+        }
+
+        // A word that is not reduced modulo its modulus is rejected.
+        bytes[8 * 8..8 * 9].copy_from_slice(&Q[1].to_le_bytes());
+        assert!(Poly::from_seal_bytes(&bytes, &ctx, Representation::Ntt).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_into() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(Q, 16)?);
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            for variable_time in [false, true] {
+                let mut p = Poly::random(&ctx, representation.clone(), &mut rng);
+                if variable_time {
+                    unsafe { p.allow_variable_time_computations() }
+                }
+
+                // The bytes are the concatenation of the serializations of the
+                // residues in PowerBasis representation.
+                let mut q = p.clone();
+                q.change_representation(Representation::PowerBasis);
+                let expected = q
+                    .coefficients()
+                    .outer_iter()
+                    .zip(ctx.q.iter())
+                    .flat_map(|(v, qi)| qi.serialize_vec(v.as_slice().unwrap()))
+                    .collect::<Vec<_>>();
+
+                let mut out = vec![1, 2, 3];
+                p.serialize_into(&mut out);
+                assert_eq!(out[..3], [1, 2, 3]);
+                assert_eq!(out[3..], expected);
+                assert_eq!(Rq::from(&p).coefficients, expected);
+                assert_eq!(p, Poly::from_bytes(&p.to_bytes(), &ctx)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_into_allocations() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(Q, 16)?);
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation, &mut rng);
+            p.serialize_into(&mut vec![]);
+
+            // No allocation in a buffer with enough capacity.
+            let mut out = Vec::with_capacity(p.to_bytes().len() * 10);
+            let before = allocations();
+            for _ in 0..10 {
+                p.serialize_into(&mut out);
+            }
+            assert_eq!(allocations(), before);
+
+            // A single allocation for the coefficients of the protobuf message.
+            let before = allocations();
+            let _ = Rq::from(&p);
+            assert_eq!(allocations(), before + 1);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn serialize() -> Result<(), Box<dyn Error>> {
         let mut rng = thread_rng();
@@ -62,4 +624,167 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn to_bytes_with_packing() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        // Moduli of 62, 17 and 40 bits.
+        let ctx = Arc::new(Context::new(&[Q[0], 65537, 1099511627297], 16)?);
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation, &mut rng);
+            let packed = p.to_bytes_with_packing(true);
+            let aligned = p.to_bytes_with_packing(false);
+            assert_eq!(packed, p.to_bytes());
+            assert_eq!(p, Poly::from_bytes(&packed, &ctx)?);
+            assert_eq!(p, Poly::from_bytes(&aligned, &ctx)?);
+
+            let packed = Rq::decode(packed.as_slice())?;
+            let aligned = Rq::decode(aligned.as_slice())?;
+            assert!(!packed.byte_aligned);
+            assert!(aligned.byte_aligned);
+            assert_eq!(packed.coefficients.len(), (62 + 17 + 40) * 16 / 8);
+            assert_eq!(aligned.coefficients.len(), (64 + 24 + 40) * 16 / 8);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn base64_hex() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(Q, 16)?);
+
+        for representation in [
+            Representation::PowerBasis,
+            Representation::Ntt,
+            Representation::NttShoup,
+        ] {
+            let p = Poly::random(&ctx, representation, &mut rng);
+            let b64 = p.to_base64();
+            assert_eq!(b64.len() % 4, 0);
+            assert_eq!(p, Poly::from_base64(&b64, &ctx)?);
+
+            let hex = p.to_hex();
+            assert_eq!(hex.len(), 2 * p.to_bytes().len());
+            assert_eq!(p, Poly::from_hex(&hex, &ctx)?);
+            assert_eq!(p, Poly::from_hex(&hex.to_uppercase(), &ctx)?);
+        }
+
+        // Known encodings of the bytes with all the possible paddings.
+        assert_eq!(super::encode_base64(b""), "");
+        assert_eq!(super::encode_base64(b"f"), "Zg==");
+        assert_eq!(super::encode_base64(b"fo"), "Zm8=");
+        assert_eq!(super::encode_base64(b"foo"), "Zm9v");
+        assert_eq!(super::encode_base64(b"foob"), "Zm9vYg==");
+        for s in ["", "f", "fo", "foo", "foob", "fooba", "foobar"] {
+            let encoded = super::encode_base64(s.as_bytes());
+            assert_eq!(super::decode_base64(&encoded)?, s.as_bytes());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn base64_hex_invalid() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        let ctx = Arc::new(Context::new(Q, 16)?);
+        let p = Poly::random(&ctx, Representation::Ntt, &mut rng);
+
+        // Corrupted base64 strings.
+        let b64 = p.to_base64();
+        for corrupted in [
+            format!("{b64}="),
+            b64[1..].to_string(),
+            b64.replacen(&b64[..1], "*", 1),
+            format!("{b64}Zg="),
+            format!("{b64}Z==="),
+            format!("Zg=={b64}"),
+            format!("{b64}Zh=="),
+        ] {
+            assert!(Poly::from_base64(&corrupted, &ctx).is_err());
+        }
+        assert!(super::decode_base64("Zh==").is_err());
+        assert!(super::decode_base64("Z===").is_err());
+        assert!(super::decode_base64("Zm9").is_err());
+        assert!(super::decode_base64("Zm 9").is_err());
+
+        // Corrupted hexadecimal strings.
+        let hex = p.to_hex();
+        assert!(Poly::from_hex(&hex[1..], &ctx).is_err());
+        assert!(Poly::from_hex(&format!("{hex}zz"), &ctx).is_err());
+        assert!(Poly::from_hex(&format!("{hex}+1"), &ctx).is_err());
+        assert!(Poly::from_hex(&hex[2..], &ctx).is_err());
+
+        // Valid encodings of an invalid serialization.
+        assert!(Poly::from_base64("Zm9vYmFy", &ctx).is_err());
+        assert!(Poly::from_hex("00ff", &ctx).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn small_bytes() -> Result<(), Box<dyn Error>> {
+        let mut rng = thread_rng();
+        for moduli in [&Q[..1], &Q[..]] {
+            let ctx = Arc::new(Context::new(moduli, 16)?);
+            for representation in [
+                Representation::PowerBasis,
+                Representation::Ntt,
+                Representation::NttShoup,
+            ] {
+                for variance in 1..=3 {
+                    let p = Poly::small(&ctx, representation.clone(), variance, &mut rng)?;
+                    let bytes = p.to_small_bytes()?;
+                    assert_eq!(bytes.len(), 8);
+                    assert_eq!(
+                        *p,
+                        Poly::from_small_bytes(&bytes, &ctx, representation.clone())?
+                    );
+                }
+            }
+
+            // The extreme values of the range.
+            let coefficients = (-7..9).collect::<Vec<i64>>();
+            let p = Poly::try_convert_from(
+                &coefficients[..15],
+                &ctx,
+                false,
+                Representation::PowerBasis,
+            )?;
+            let q = Poly::from_small_bytes(&p.to_small_bytes()?, &ctx, Representation::PowerBasis)?;
+            assert_eq!(p, q);
+
+            // Coefficients out of range.
+            let p = Poly::try_convert_from(&coefficients, &ctx, false, Representation::PowerBasis)?;
+            assert_eq!(
+                p.to_small_bytes(),
+                Err(crate::Error::CoefficientOutOfRange { bound: 7 })
+            );
+            let mut p = Poly::try_convert_from(&[-8i64], &ctx, false, Representation::PowerBasis)?;
+            p.change_representation(Representation::Ntt);
+            assert_eq!(
+                p.to_small_bytes(),
+                Err(crate::Error::CoefficientOutOfRange { bound: 7 })
+            );
+            let p = Poly::random(&ctx, Representation::PowerBasis, &mut rng);
+            assert_eq!(
+                p.to_small_bytes(),
+                Err(crate::Error::CoefficientOutOfRange { bound: 7 })
+            );
+
+            // Invalid encodings.
+            assert!(Poly::from_small_bytes(&[0; 7], &ctx, Representation::PowerBasis).is_err());
+            assert!(Poly::from_small_bytes(&[0; 9], &ctx, Representation::PowerBasis).is_err());
+            let mut bytes = vec![0u8; 8];
+            bytes[3] = 0x80;
+            assert!(Poly::from_small_bytes(&bytes, &ctx, Representation::PowerBasis).is_err());
+        }
+
+        Ok(())
+    }
 }
@@ -24,4 +24,10 @@ impl Switcher {
     pub(crate) fn switch(&self, p: &Poly) -> Result<Poly> {
         self.scaler.scale(p)
     }
+
+    /// Switch a batch of polynomials, such as the components of a ciphertext;
+    /// see [`Scaler::scale_batch`].
+    pub fn switch_batch(&self, polys: &[Poly]) -> Result<Vec<Poly>> {
+        self.scaler.scale_batch(polys)
+    }
 }
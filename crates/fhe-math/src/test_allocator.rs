@@ -0,0 +1,53 @@
+//! Global allocator used in the tests, which records the allocations of the
+//! current thread, and detects deallocated memory that still contains given
+//! canary values.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+struct TestAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static CANARIES: Cell<Option<[u64; 2]>> = const { Cell::new(None) };
+    static LEAKS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for TestAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Ok(Some(canaries)) = CANARIES.try_with(|c| c.get()) {
+            if layout.align() >= 8 {
+                let words = std::slice::from_raw_parts(ptr as *const u64, layout.size() / 8);
+                if words.iter().any(|w| canaries.contains(w)) {
+                    let _ = LEAKS.try_with(|l| l.set(l.get() + 1));
+                }
+            }
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TestAllocator = TestAllocator;
+
+/// Number of allocations made by the current thread.
+pub(crate) fn allocations() -> usize {
+    ALLOCATIONS.with(|a| a.get())
+}
+
+/// Runs `f` and returns the number of memory blocks deallocated by the current
+/// thread while they still contained one of the `canaries`.
+pub(crate) fn leaked_canaries(canaries: [u64; 2], f: impl FnOnce()) -> usize {
+    LEAKS.with(|l| l.set(0));
+    CANARIES.with(|c| c.set(Some(canaries)));
+    f();
+    CANARIES.with(|c| c.set(None));
+    LEAKS.with(|l| l.get())
+}
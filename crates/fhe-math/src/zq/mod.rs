@@ -3,13 +3,15 @@
 //! Ring operations for moduli up to 62 bits.
 
 pub mod primes;
+pub(crate) mod simd;
 
-use std::ops::Deref;
+use alloc::{vec, vec::Vec};
+use core::ops::Deref;
 
 use crate::errors::{Error, Result};
-use derivative::Derivative;
-use fhe_util::{is_prime, transcode_from_bytes, transcode_to_bytes};
+use fhe_util::{is_prime, transcode_from_bytes, transcode_to_bytes, transcode_to_bytes_into};
 use itertools::{izip, Itertools};
+use ndarray::{ArrayView1, ArrayViewMut1};
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
 use pulp::Arch;
@@ -23,8 +25,6 @@ const fn const_time_cond_select(on_true: u64, on_false: u64, cond: bool) -> u64
 }
 
 /// Structure encapsulating an integer modulus up to 62 bits.
-#[derive(Derivative)]
-#[derivative(PartialEq)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Modulus {
     pub(crate) p: u64,
@@ -32,13 +32,26 @@ pub struct Modulus {
     barrett_hi: u64,
     barrett_lo: u64,
     leading_zeros: u32,
+    mont_p_inv_neg: u64,
+    mont_r2: u64,
     pub(crate) supports_opt: bool,
-    distribution: Uniform<u64>,
-    #[derivative(PartialEq = "ignore")]
     arch: Arch,
 }
 
-// We need to declare Eq manually because of the `Uniform` member.
+// The architecture is ignored in the comparison.
+impl PartialEq for Modulus {
+    fn eq(&self, other: &Self) -> bool {
+        self.p == other.p
+            && self.nbits == other.nbits
+            && self.barrett_hi == other.barrett_hi
+            && self.barrett_lo == other.barrett_lo
+            && self.leading_zeros == other.leading_zeros
+            && self.mont_p_inv_neg == other.mont_p_inv_neg
+            && self.mont_r2 == other.mont_r2
+            && self.supports_opt == other.supports_opt
+    }
+}
+
 impl Eq for Modulus {}
 
 // Override the dereference to return the underlying modulus.
@@ -50,6 +63,10 @@ impl Deref for Modulus {
     }
 }
 
+/// Number of square-and-multiply iterations of the constant time modular
+/// exponentiation, covering all the bits of a u64 exponent.
+const POW_NUM_ITERATIONS: u32 = u64::BITS;
+
 impl Modulus {
     /// Create a modulus from an integer of at most 62 bits.
     pub fn new(p: u64) -> Result<Self> {
@@ -57,14 +74,24 @@ impl Modulus {
             Err(Error::InvalidModulus(p))
         } else {
             let barrett = ((BigUint::from(1u64) << 128usize) / p).to_u128().unwrap(); // 2^128 / p
+
+            // -p^(-1) mod 2^64 using Newton's iteration, which doubles the number of
+            // correct bits at each step; p * p = 1 mod 8 so p is correct on 3 bits.
+            let mut p_inv = p;
+            for _ in 0..5 {
+                p_inv = p_inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(p_inv)));
+            }
+            let mont_r2 = ((BigUint::from(1u64) << 128usize) % p).to_u64().unwrap(); // 2^128 mod p
+
             Ok(Self {
                 p,
                 nbits: 64 - p.leading_zeros() as usize,
                 barrett_hi: (barrett >> 64) as u64,
                 barrett_lo: barrett as u64,
                 leading_zeros: p.leading_zeros(),
+                mont_p_inv_neg: if p & 1 == 1 { p_inv.wrapping_neg() } else { 0 },
+                mont_r2,
                 supports_opt: primes::supports_opt(p),
-                distribution: Uniform::from(0..p),
                 arch: Arch::new(),
             })
         }
@@ -214,8 +241,24 @@ impl Modulus {
     /// debug mode.
     pub fn add_vec(&self, a: &mut [u64], b: &[u64]) {
         debug_assert_eq!(a.len(), b.len());
+        let n = simd::add_vec(self.p, a, b);
+        self.arch.dispatch(|| {
+            izip!(a[n..].iter_mut(), b[n..].iter()).for_each(|(ai, bi)| *ai = self.add(*ai, *bi))
+        })
+    }
+
+    /// Lazy modular addition of vectors in place in constant time.
+    /// The input and output coefficients are in the interval [0, 2 * p).
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= 2 * p
+    /// in debug mode.
+    pub fn lazy_add_vec(&self, a: &mut [u64], b: &[u64]) {
+        debug_assert_eq!(a.len(), b.len());
+        let p_twice = 2 * self.p;
+        let n = simd::add_vec(p_twice, a, b);
         self.arch.dispatch(|| {
-            izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.add(*ai, *bi))
+            izip!(a[n..].iter_mut(), b[n..].iter())
+                .for_each(|(ai, bi)| *ai = Self::reduce1(*ai + *bi, p_twice))
         })
     }
 
@@ -272,8 +315,9 @@ impl Modulus {
     /// debug mode.
     pub fn sub_vec(&self, a: &mut [u64], b: &[u64]) {
         debug_assert_eq!(a.len(), b.len());
+        let n = simd::sub_vec(self.p, a, b);
         self.arch.dispatch(|| {
-            izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.sub(*ai, *bi))
+            izip!(a[n..].iter_mut(), b[n..].iter()).for_each(|(ai, bi)| *ai = self.sub(*ai, *bi))
         })
     }
 
@@ -326,11 +370,26 @@ impl Modulus {
 
     /// Modular multiplication of vectors in place in constant time.
     ///
+    /// The values of a and b must be reduced, i.e., < p: since p < 2^62, each
+    /// product is then < p^2 < 2^124, which is within the bounds of the
+    /// (optimized) Barrett reduction of u128 values.
+    ///
+    /// Unlike [`Modulus::add_vec`], [`Modulus::sub_vec`] and
+    /// [`Modulus::mul_shoup_vec`], this has no AVX2 or NEON kernel and always
+    /// runs the scalar code. When `b` is reused, [`Modulus::mul_shoup_vec`] is
+    /// vectorized.
+    ///
     /// Aborts if a and b differ in size, and if any of their values is >= p in
     /// debug mode.
     pub fn mul_vec(&self, a: &mut [u64], b: &[u64]) {
         debug_assert_eq!(a.len(), b.len());
+        debug_assert!(a.iter().chain(b.iter()).all(|x| *x < self.p));
 
+        // There is no kernel of the `simd` module for this operation. An AVX2
+        // kernel emulating the Barrett reduction with
+        // 32-bit multiplications computes the same values, but 2^14 products
+        // modulo a 62-bit prime took about 80µs, against about 70µs for the
+        // scalar code below and about 60µs with `mul_opt`.
         if self.supports_opt {
             self.arch.dispatch(|| {
                 izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.mul_opt(*ai, *bi))
@@ -342,6 +401,82 @@ impl Modulus {
         }
     }
 
+    /// Returns whether the modulus supports the Montgomery representation, i.e.,
+    /// whether p is odd.
+    pub const fn supports_montgomery(&self) -> bool {
+        self.p & 1 == 1
+    }
+
+    /// Montgomery reduction of a u128 in constant time: returns a * 2^(-64) mod
+    /// p. Aborts if a >= p * 2^64 in debug mode.
+    const fn mont_reduce(&self, a: u128) -> u64 {
+        debug_assert!(a < (self.p as u128) << 64);
+
+        let m = (a as u64).wrapping_mul(self.mont_p_inv_neg);
+        let r = ((a + (m as u128) * (self.p as u128)) >> 64) as u64;
+        Self::reduce1(r, self.p)
+    }
+
+    /// Converts a value in [0, p) in Montgomery representation, i.e., returns
+    /// a * 2^64 mod p, in constant time.
+    /// Aborts if a >= p or if p is even in debug mode.
+    pub const fn to_montgomery(&self, a: u64) -> u64 {
+        self.mont_mul(a, self.mont_r2)
+    }
+
+    /// Converts a value from the Montgomery representation, i.e., returns
+    /// a * 2^(-64) mod p, in constant time.
+    /// Aborts if a >= p or if p is even in debug mode.
+    pub const fn from_montgomery(&self, a: u64) -> u64 {
+        debug_assert!(self.supports_montgomery() && a < self.p);
+        self.mont_reduce(a as u128)
+    }
+
+    /// Performs the Montgomery multiplication of a and b in constant time,
+    /// i.e., returns a * b * 2^(-64) mod p. When a and b are in Montgomery
+    /// representation, so is the result.
+    /// Aborts if a >= p, b >= p, or if p is even in debug mode.
+    pub const fn mont_mul(&self, a: u64, b: u64) -> u64 {
+        debug_assert!(self.supports_montgomery() && a < self.p && b < self.p);
+        self.mont_reduce((a as u128) * (b as u128))
+    }
+
+    /// Converts a vector in Montgomery representation in place in constant time.
+    ///
+    /// Aborts if p is even, and if any of the values in the vector is >= p in
+    /// debug mode.
+    pub fn to_montgomery_vec(&self, a: &mut [u64]) {
+        assert!(self.supports_montgomery());
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.to_montgomery(*ai)))
+    }
+
+    /// Converts a vector from Montgomery representation in place in constant
+    /// time.
+    ///
+    /// Aborts if p is even, and if any of the values in the vector is >= p in
+    /// debug mode.
+    pub fn from_montgomery_vec(&self, a: &mut [u64]) {
+        assert!(self.supports_montgomery());
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.from_montgomery(*ai)))
+    }
+
+    /// Montgomery multiplication of vectors in place in constant time. Unlike
+    /// the Shoup multiplication, it does not require any precomputation on b,
+    /// and unlike `mul_vec`, it only needs two multiplications per element.
+    ///
+    /// Aborts if p is even, and if a and b differ in size or if any of their
+    /// values is >= p in debug mode.
+    pub fn mont_mul_vec(&self, a: &mut [u64], b: &[u64]) {
+        assert!(self.supports_montgomery());
+        debug_assert_eq!(a.len(), b.len());
+
+        self.arch.dispatch(|| {
+            izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.mont_mul(*ai, *bi))
+        })
+    }
+
     /// Modular scalar multiplication of vectors in place in constant time.
     ///
     /// Aborts if any of the values in a is >= p in debug mode.
@@ -367,13 +502,87 @@ impl Modulus {
         })
     }
 
+    /// Modular scalar multiplication of vectors in place in constant time, with
+    /// the Shoup representation of the scalar precomputed.
+    ///
+    /// When multiplying several vectors by the same scalar, b_shoup can be
+    /// computed once using [`Modulus::shoup`].
+    ///
+    /// Aborts if any of the values in a is >= p, or if b >= p or b_shoup !=
+    /// shoup(b) in debug mode.
+    pub fn scalar_mul_shoup_vec(&self, a: &mut [u64], b: u64, b_shoup: u64) {
+        self.arch.dispatch(|| {
+            a.iter_mut()
+                .for_each(|ai| *ai = self.mul_shoup(*ai, b, b_shoup))
+        })
+    }
+
+    /// Modular scalar multiplication of vectors in place in variable time, with
+    /// the Shoup representation of the scalar precomputed.
+    /// Aborts if any of the values in a is >= p, or if b >= p or b_shoup !=
+    /// shoup(b) in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being multiplied.
+    pub unsafe fn scalar_mul_shoup_vec_vt(&self, a: &mut [u64], b: u64, b_shoup: u64) {
+        self.arch.dispatch(|| {
+            a.iter_mut()
+                .for_each(|ai| *ai = self.mul_shoup_vt(*ai, b, b_shoup))
+        })
+    }
+
+    /// Modular scalar addition of vectors in place in constant time.
+    ///
+    /// Aborts if any of the values in a is >= p, or if b >= p in debug mode.
+    pub fn scalar_add_vec(&self, a: &mut [u64], b: u64) {
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.add(*ai, b)))
+    }
+
+    /// Modular scalar addition of vectors in place in variable time.
+    /// Aborts if any of the values in a is >= p, or if b >= p in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being added.
+    pub unsafe fn scalar_add_vec_vt(&self, a: &mut [u64], b: u64) {
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.add_vt(*ai, b)))
+    }
+
+    /// Modular scalar subtraction of vectors in place in constant time: each
+    /// value of a is replaced by a - b.
+    ///
+    /// Aborts if any of the values in a is >= p, or if b >= p in debug mode.
+    pub fn scalar_sub_vec(&self, a: &mut [u64], b: u64) {
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.sub(*ai, b)))
+    }
+
+    /// Modular scalar subtraction of vectors in place in variable time: each
+    /// value of a is replaced by a - b.
+    /// Aborts if any of the values in a is >= p, or if b >= p in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being subtracted.
+    pub unsafe fn scalar_sub_vec_vt(&self, a: &mut [u64], b: u64) {
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.sub_vt(*ai, b)))
+    }
+
     /// Modular multiplication of vectors in place in variable time.
+    ///
+    /// The values of a and b must be reduced, i.e., < p, so that each product
+    /// is < p^2 < 2^124; see [`Modulus::mul_vec`].
+    ///
     /// Aborts if a and b differ in size, and if any of their values is >= p in
     /// debug mode.
     ///
     /// # Safety
     /// This function is not constant time and its timing may reveal information
-    /// about the values being subtracted.
+    /// about the values being multiplied.
     pub unsafe fn mul_vec_vt(&self, a: &mut [u64], b: &[u64]) {
         debug_assert_eq!(a.len(), b.len());
 
@@ -403,14 +612,32 @@ impl Modulus {
     pub fn mul_shoup_vec(&self, a: &mut [u64], b: &[u64], b_shoup: &[u64]) {
         debug_assert_eq!(a.len(), b.len());
         debug_assert_eq!(a.len(), b_shoup.len());
-        debug_assert_eq!(&b_shoup, &self.shoup_vec(b));
+        debug_assert!(izip!(b, b_shoup).all(|(bi, bi_shoup)| *bi_shoup == self.shoup(*bi)));
 
+        let n = simd::mul_shoup_vec(self.p, a, b, b_shoup);
         self.arch.dispatch(|| {
-            izip!(a.iter_mut(), b.iter(), b_shoup.iter())
+            izip!(a[n..].iter_mut(), b[n..].iter(), b_shoup[n..].iter())
                 .for_each(|(ai, bi, bi_shoup)| *ai = self.mul_shoup(*ai, *bi, *bi_shoup))
         })
     }
 
+    /// Lazy Shoup modular multiplication of vectors in place in constant time.
+    /// The output coefficients are in the interval [0, 2 * p).
+    ///
+    /// Aborts if a and b differ in size, and if any of the values of b is >= p
+    /// in debug mode.
+    pub fn lazy_mul_shoup_vec(&self, a: &mut [u64], b: &[u64], b_shoup: &[u64]) {
+        debug_assert_eq!(a.len(), b.len());
+        debug_assert_eq!(a.len(), b_shoup.len());
+        debug_assert!(izip!(b, b_shoup).all(|(bi, bi_shoup)| *bi_shoup == self.shoup(*bi)));
+
+        let n = simd::lazy_mul_shoup_vec(self.p, a, b, b_shoup);
+        self.arch.dispatch(|| {
+            izip!(a[n..].iter_mut(), b[n..].iter(), b_shoup[n..].iter())
+                .for_each(|(ai, bi, bi_shoup)| *ai = self.lazy_mul_shoup(*ai, *bi, *bi_shoup))
+        })
+    }
+
     /// Shoup modular multiplication of vectors in place in variable time.
     /// Aborts if a and b differ in size, and if any of their values is >= p in
     /// debug mode.
@@ -421,7 +648,7 @@ impl Modulus {
     pub unsafe fn mul_shoup_vec_vt(&self, a: &mut [u64], b: &[u64], b_shoup: &[u64]) {
         debug_assert_eq!(a.len(), b.len());
         debug_assert_eq!(a.len(), b_shoup.len());
-        debug_assert_eq!(&b_shoup, &self.shoup_vec(b));
+        debug_assert!(izip!(b, b_shoup).all(|(bi, bi_shoup)| *bi_shoup == self.shoup(*bi)));
 
         self.arch.dispatch(|| {
             izip!(a.iter_mut(), b.iter(), b_shoup.iter())
@@ -435,8 +662,40 @@ impl Modulus {
             .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.reduce(*ai)))
     }
 
-    /// Center a value modulo p as i64 in variable time.
-    /// TODO: To test and to make constant time?
+    /// Center a value modulo p as i64 in constant time, i.e., returns the
+    /// representative of `a` in the interval (-p/2, p/2].
+    ///
+    /// Aborts if a >= p in debug mode.
+    pub const fn center(&self, a: u64) -> i64 {
+        debug_assert!(a < self.p);
+
+        // The mask is all ones when a > p / 2, since both values are < 2^63.
+        let mask = ((self.p >> 1).wrapping_sub(a) >> 63).wrapping_neg();
+        (a as i64).wrapping_sub((self.p & mask) as i64)
+    }
+
+    /// Center a vector in constant time.
+    ///
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    pub fn center_vec(&self, a: &[u64]) -> Vec<i64> {
+        self.arch
+            .dispatch(|| a.iter().map(|ai| self.center(*ai)).collect_vec())
+    }
+
+    /// Center a vector in constant time, writing the result in `out`.
+    ///
+    /// Aborts if a and out differ in size, and if any of the values in the
+    /// vector is >= p in debug mode.
+    pub fn center_vec_into(&self, a: &[u64], out: &mut [i64]) {
+        assert_eq!(a.len(), out.len());
+
+        self.arch.dispatch(|| {
+            izip!(out.iter_mut(), a.iter()).for_each(|(oi, ai)| *oi = self.center(*ai))
+        })
+    }
+
+    /// Center a value modulo p as i64 in variable time, i.e., returns the
+    /// representative of `a` in the interval (-p/2, p/2] as [`Self::center`].
     ///
     /// # Safety
     /// This function is not constant time and its timing may reveal information
@@ -444,7 +703,7 @@ impl Modulus {
     const unsafe fn center_vt(&self, a: u64) -> i64 {
         debug_assert!(a < self.p);
 
-        if a >= self.p >> 1 {
+        if a > self.p >> 1 {
             (a as i64) - (self.p as i64)
         } else {
             a as i64
@@ -472,7 +731,7 @@ impl Modulus {
     }
 
     /// Modular reduction of a i64 in constant time.
-    const fn reduce_i64(&self, a: i64) -> u64 {
+    pub(crate) const fn reduce_i64(&self, a: i64) -> u64 {
         self.reduce_u128((((self.p as i128) << 64) + (a as i128)) as u128)
     }
 
@@ -536,11 +795,205 @@ impl Modulus {
             .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.neg_vt(*ai)))
     }
 
-    /// Modular exponentiation in variable time.
+    /// Modular addition of vectors in place in constant time, where the
+    /// vectors may be non-contiguous views, e.g., reversed or stepped views, or
+    /// columns of a two-dimensional array.
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= p in
+    /// debug mode.
+    pub fn add_vec_strided(&self, mut a: ArrayViewMut1<u64>, b: ArrayView1<u64>) {
+        debug_assert_eq!(a.len(), b.len());
+
+        if a.is_standard_layout() && b.is_standard_layout() {
+            self.add_vec(a.as_slice_mut().unwrap(), b.as_slice().unwrap())
+        } else {
+            self.arch.dispatch(|| {
+                izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.add(*ai, *bi))
+            })
+        }
+    }
+
+    /// Modular addition of vectors in place in variable time, where the
+    /// vectors may be non-contiguous views.
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= p in
+    /// debug mode.
     ///
-    /// Aborts if a >= p or n >= p in debug mode.
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being added.
+    pub unsafe fn add_vec_strided_vt(&self, mut a: ArrayViewMut1<u64>, b: ArrayView1<u64>) {
+        debug_assert_eq!(a.len(), b.len());
+
+        if a.is_standard_layout() && b.is_standard_layout() {
+            self.add_vec_vt(a.as_slice_mut().unwrap(), b.as_slice().unwrap())
+        } else {
+            self.arch.dispatch(|| {
+                izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.add_vt(*ai, *bi))
+            })
+        }
+    }
+
+    /// Modular subtraction of vectors in place in constant time, where the
+    /// vectors may be non-contiguous views, e.g., reversed or stepped views, or
+    /// columns of a two-dimensional array.
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= p in
+    /// debug mode.
+    pub fn sub_vec_strided(&self, mut a: ArrayViewMut1<u64>, b: ArrayView1<u64>) {
+        debug_assert_eq!(a.len(), b.len());
+
+        if a.is_standard_layout() && b.is_standard_layout() {
+            self.sub_vec(a.as_slice_mut().unwrap(), b.as_slice().unwrap())
+        } else {
+            self.arch.dispatch(|| {
+                izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.sub(*ai, *bi))
+            })
+        }
+    }
+
+    /// Modular subtraction of vectors in place in variable time, where the
+    /// vectors may be non-contiguous views.
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= p in
+    /// debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being subtracted.
+    pub unsafe fn sub_vec_strided_vt(&self, mut a: ArrayViewMut1<u64>, b: ArrayView1<u64>) {
+        debug_assert_eq!(a.len(), b.len());
+
+        if a.is_standard_layout() && b.is_standard_layout() {
+            self.sub_vec_vt(a.as_slice_mut().unwrap(), b.as_slice().unwrap())
+        } else {
+            self.arch.dispatch(|| {
+                izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.sub_vt(*ai, *bi))
+            })
+        }
+    }
+
+    /// Modular multiplication of vectors in place in constant time, where the
+    /// vectors may be non-contiguous views, e.g., reversed or stepped views, or
+    /// columns of a two-dimensional array.
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= p in
+    /// debug mode.
+    pub fn mul_vec_strided(&self, mut a: ArrayViewMut1<u64>, b: ArrayView1<u64>) {
+        debug_assert_eq!(a.len(), b.len());
+
+        if a.is_standard_layout() && b.is_standard_layout() {
+            self.mul_vec(a.as_slice_mut().unwrap(), b.as_slice().unwrap())
+        } else {
+            self.arch.dispatch(|| {
+                izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.mul(*ai, *bi))
+            })
+        }
+    }
+
+    /// Modular multiplication of vectors in place in variable time, where the
+    /// vectors may be non-contiguous views.
+    ///
+    /// Aborts if a and b differ in size, and if any of their values is >= p in
+    /// debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being multiplied.
+    pub unsafe fn mul_vec_strided_vt(&self, mut a: ArrayViewMut1<u64>, b: ArrayView1<u64>) {
+        debug_assert_eq!(a.len(), b.len());
+
+        if a.is_standard_layout() && b.is_standard_layout() {
+            self.mul_vec_vt(a.as_slice_mut().unwrap(), b.as_slice().unwrap())
+        } else {
+            self.arch.dispatch(|| {
+                izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| *ai = self.mul_vt(*ai, *bi))
+            })
+        }
+    }
+
+    /// Modular negation of a vector in place in constant time, where the vector may be a
+    /// non-contiguous view.
+    ///
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    pub fn neg_vec_strided(&self, mut a: ArrayViewMut1<u64>) {
+        if let Some(a) = a.as_slice_mut() {
+            self.neg_vec(a)
+        } else {
+            self.arch
+                .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.neg(*ai)))
+        }
+    }
+
+    /// Modular negation of a vector in place in variable time, where the vector may be a
+    /// non-contiguous view.
+    ///
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being negated.
+    pub unsafe fn neg_vec_strided_vt(&self, mut a: ArrayViewMut1<u64>) {
+        if let Some(a) = a.as_slice_mut() {
+            self.neg_vec_vt(a)
+        } else {
+            self.arch
+                .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.neg_vt(*ai)))
+        }
+    }
+
+    /// Reduction of a vector in place in constant time, where the vector may be a
+    /// non-contiguous view.
+    pub fn reduce_vec_strided(&self, mut a: ArrayViewMut1<u64>) {
+        if let Some(a) = a.as_slice_mut() {
+            self.reduce_vec(a)
+        } else {
+            self.arch
+                .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.reduce(*ai)))
+        }
+    }
+
+    /// Reduction of a vector in place in variable time, where the vector may be a
+    /// non-contiguous view.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being reduced.
+    pub unsafe fn reduce_vec_strided_vt(&self, mut a: ArrayViewMut1<u64>) {
+        if let Some(a) = a.as_slice_mut() {
+            self.reduce_vec_vt(a)
+        } else {
+            self.arch
+                .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.reduce_vt(*ai)))
+        }
+    }
+
+    /// Modular exponentiation in constant time.
+    ///
+    /// The running time only depends on the modulus, and not on a nor n; in
+    /// particular, a^0 = 1 for any a, including 0.
+    /// Aborts if a >= p in debug mode.
     pub fn pow(&self, a: u64, n: u64) -> u64 {
-        debug_assert!(a < self.p && n < self.p);
+        debug_assert!(a < self.p);
+
+        let mut r = 1;
+        for i in (0..POW_NUM_ITERATIONS).rev() {
+            r = self.mul(r, r);
+            r = const_time_cond_select(self.mul(r, a), r, (n >> i) & 1 == 1);
+        }
+        r
+    }
+
+    /// Modular exponentiation in variable time.
+    ///
+    /// a^0 = 1 for any a, including 0.
+    /// Aborts if a >= p in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being exponentiated.
+    pub unsafe fn pow_vt(&self, a: u64, n: u64) -> u64 {
+        debug_assert!(a < self.p);
 
         if n == 0 {
             1
@@ -550,9 +1003,9 @@ impl Modulus {
             let mut r = a;
             let mut i = (62 - n.leading_zeros()) as isize;
             while i >= 0 {
-                r = self.mul(r, r);
+                r = self.mul_vt(r, r);
                 if (n >> i) & 1 == 1 {
-                    r = self.mul(r, a);
+                    r = self.mul_vt(r, a);
                 }
                 i -= 1;
             }
@@ -560,21 +1013,235 @@ impl Modulus {
         }
     }
 
+    /// Modular exponentiation of a vector in place in constant time.
+    ///
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    pub fn pow_vec(&self, a: &mut [u64], n: u64) {
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.pow(*ai, n)))
+    }
+
+    /// Modular exponentiation of a vector in place in variable time.
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being exponentiated.
+    pub unsafe fn pow_vec_vt(&self, a: &mut [u64], n: u64) {
+        self.arch
+            .dispatch(|| a.iter_mut().for_each(|ai| *ai = self.pow_vt(*ai, n)))
+    }
+
+    /// Modular inversion in constant time, computed as a^(p - 2) using Fermat's
+    /// little theorem.
+    ///
+    /// Returns None if p is not prime or a = 0; the running time does not
+    /// depend on a otherwise.
+    /// Aborts if a >= p in debug mode.
+    pub fn inv(&self, a: u64) -> Option<u64> {
+        if !is_prime(self.p) || a == 0 {
+            None
+        } else {
+            let r = self.pow(a, self.p - 2);
+            debug_assert_eq!(self.mul(a, r), 1);
+            Some(r)
+        }
+    }
+
     /// Modular inversion in variable time.
     ///
     /// Returns None if p is not prime or a = 0.
     /// Aborts if a >= p in debug mode.
-    pub fn inv(&self, a: u64) -> std::option::Option<u64> {
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the value being inverted.
+    pub unsafe fn inv_vt(&self, a: u64) -> Option<u64> {
         if !is_prime(self.p) || a == 0 {
             None
         } else {
-            let r = self.pow(a, self.p - 2);
+            let r = self.pow_vt(a, self.p - 2);
             debug_assert_eq!(self.mul(a, r), 1);
             Some(r)
         }
     }
 
+    /// Modular inversion of a vector in constant time, using Montgomery's
+    /// simultaneous inversion trick: a single inversion is computed, together
+    /// with 3 multiplications per element.
+    ///
+    /// Returns None if p is not prime or if any of the values is 0; the
+    /// running time does not depend on the values otherwise.
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    pub fn inv_vec(&self, a: &[u64]) -> Option<Vec<u64>> {
+        if a.is_empty() {
+            return Some(vec![]);
+        } else if !is_prime(self.p) {
+            return None;
+        }
+        debug_assert!(a.iter().all(|ai| *ai < self.p));
+
+        let prefix_products = a
+            .iter()
+            .scan(1, |acc, ai| {
+                *acc = self.mul(*acc, *ai);
+                Some(*acc)
+            })
+            .collect_vec();
+        let product = prefix_products.last().copied().unwrap_or(1);
+        if product == 0 {
+            return None;
+        }
+
+        let mut inv = self.pow(product, self.p - 2);
+        let mut out = vec![0; a.len()];
+        for i in (1..a.len()).rev() {
+            out[i] = self.mul(inv, prefix_products[i - 1]);
+            inv = self.mul(inv, a[i]);
+        }
+        if let Some(out_0) = out.first_mut() {
+            *out_0 = inv;
+        }
+        Some(out)
+    }
+
+    /// Modular inversion of a vector in variable time, using Montgomery's
+    /// simultaneous inversion trick.
+    ///
+    /// Returns None if p is not prime or if any of the values is 0.
+    /// Aborts if any of the values in the vector is >= p in debug mode.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being inverted.
+    pub unsafe fn inv_vec_vt(&self, a: &[u64]) -> Option<Vec<u64>> {
+        if a.is_empty() {
+            return Some(vec![]);
+        }
+        debug_assert!(a.iter().all(|ai| *ai < self.p));
+
+        let prefix_products = a
+            .iter()
+            .scan(1, |acc, ai| {
+                *acc = self.mul_vt(*acc, *ai);
+                Some(*acc)
+            })
+            .collect_vec();
+        let product = prefix_products.last().copied().unwrap_or(1);
+
+        let mut inv = self.inv_vt(product)?;
+        let mut out = vec![0; a.len()];
+        for i in (1..a.len()).rev() {
+            out[i] = self.mul_vt(inv, prefix_products[i - 1]);
+            inv = self.mul_vt(inv, a[i]);
+        }
+        if let Some(out_0) = out.first_mut() {
+            *out_0 = inv;
+        }
+        Some(out)
+    }
+
+    /// Maximum number of products of two values in [0, p) that can be
+    /// accumulated in a u128 starting from 0, i.e., 2^(128 - 2 * nbits) where
+    /// nbits is the number of bits of p. For a 62-bit modulus, 16 products can
+    /// be accumulated.
+    pub const fn fma_max_terms(&self) -> u128 {
+        1u128 << (2 * self.leading_zeros)
+    }
+
+    /// Fused multiply-add of vectors in constant time: the products a[i] * b[i]
+    /// are added to acc[i] without modular reduction.
+    ///
+    /// The accumulator does not overflow if it holds at most
+    /// [`Modulus::fma_max_terms`] products in total (a reduced value counts as
+    /// one product); use [`Modulus::reduce_vec_u128`] to reduce it.
+    /// Aborts if acc, a and b differ in size, and if any of the values of a and
+    /// b is >= p in debug mode.
+    pub fn fma_vec(&self, acc: &mut [u128], a: &[u64], b: &[u64]) {
+        assert_eq!(a.len(), acc.len());
+        assert_eq!(b.len(), acc.len());
+        debug_assert!(a.iter().all(|ai| *ai < self.p));
+        debug_assert!(b.iter().all(|bi| *bi < self.p));
+
+        self.arch.dispatch(|| {
+            izip!(acc.iter_mut(), a.iter(), b.iter())
+                .for_each(|(acci, ai, bi)| *acci += (*ai as u128) * (*bi as u128))
+        })
+    }
+
+    /// Fused multiply-add of vectors in variable time: the products a[i] * b[i]
+    /// are added to acc[i] without modular reduction.
+    ///
+    /// The accumulator does not overflow if it holds at most
+    /// [`Modulus::fma_max_terms`] products in total (a reduced value counts as
+    /// one product); use [`Modulus::reduce_vec_u128_vt`] to reduce it.
+    /// Aborts if acc, a and b differ in size.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being multiplied.
+    pub unsafe fn fma_vec_vt(&self, acc: &mut [u128], a: &[u64], b: &[u64]) {
+        let n = acc.len();
+        assert_eq!(a.len(), n);
+        assert_eq!(b.len(), n);
+
+        macro_rules! fma_at {
+            ($idx:expr) => {
+                *acc.get_unchecked_mut($idx) +=
+                    (*a.get_unchecked($idx) as u128) * (*b.get_unchecked($idx) as u128);
+            };
+        }
+
+        self.arch.dispatch(|| {
+            let r = n / 16;
+            for i in 0..r {
+                fma_at!(16 * i);
+                fma_at!(16 * i + 1);
+                fma_at!(16 * i + 2);
+                fma_at!(16 * i + 3);
+                fma_at!(16 * i + 4);
+                fma_at!(16 * i + 5);
+                fma_at!(16 * i + 6);
+                fma_at!(16 * i + 7);
+                fma_at!(16 * i + 8);
+                fma_at!(16 * i + 9);
+                fma_at!(16 * i + 10);
+                fma_at!(16 * i + 11);
+                fma_at!(16 * i + 12);
+                fma_at!(16 * i + 13);
+                fma_at!(16 * i + 14);
+                fma_at!(16 * i + 15);
+            }
+
+            for i in 0..n % 16 {
+                fma_at!(16 * r + i);
+            }
+        })
+    }
+
+    /// Modular reduction of a vector of u128 in constant time.
+    ///
+    /// As for [`Modulus::reduce_u128`], the values can be any u128, such as
+    /// wide intermediate results of an exact polynomial multiplication.
+    pub fn reduce_vec_u128(&self, a: &[u128]) -> Vec<u64> {
+        self.arch
+            .dispatch(|| a.iter().map(|ai| self.reduce_u128(*ai)).collect_vec())
+    }
+
+    /// Modular reduction of a vector of u128 in variable time.
+    ///
+    /// # Safety
+    /// This function is not constant time and its timing may reveal information
+    /// about the values being reduced.
+    pub unsafe fn reduce_vec_u128_vt(&self, a: &[u128]) -> Vec<u64> {
+        self.arch
+            .dispatch(|| a.iter().map(|ai| self.reduce_u128_vt(*ai)).collect_vec())
+    }
+
     /// Modular reduction of a u128 in constant time.
+    ///
+    /// The Barrett reduction is computed on the full 128-bit input, so any
+    /// u128 is accepted, and not only products of two values modulo p.
     pub const fn reduce_u128(&self, a: u128) -> u64 {
         Self::reduce1(self.lazy_reduce_u128(a), self.p)
     }
@@ -741,11 +1408,31 @@ impl Modulus {
     }
 
     /// Returns a random vector.
+    ///
+    /// This is equivalent to `random_vec_from_rng`.
     pub fn random_vec<R: RngCore + CryptoRng>(&self, size: usize, rng: &mut R) -> Vec<u64> {
-        rng.sample_iter(self.distribution).take(size).collect_vec()
+        self.random_vec_from_rng(size, rng)
     }
 
-    /// Length of the serialization of a vector of size `size`.
+    /// Returns a vector of `size` values sampled uniformly in [0, p) from the
+    /// random number generator `rng`.
+    ///
+    /// The values are sampled with the [`Uniform`] distribution of `rand`,
+    /// which rejects some of the random words so that the output is exactly
+    /// uniform, unlike when reducing random words modulo p. The output is
+    /// deterministic given the state of `rng`.
+    pub fn random_vec_from_rng<R: RngCore + CryptoRng>(
+        &self,
+        size: usize,
+        rng: &mut R,
+    ) -> Vec<u64> {
+        rng.sample_iter(Uniform::from(0..self.p))
+            .take(size)
+            .collect_vec()
+    }
+
+    /// Length of the serialization of a vector of size `size`, i.e., `size`
+    /// times the number of bits of p - 1, in bytes.
     ///
     /// Panics if the size is not a multiple of 8.
     pub const fn serialization_length(&self, size: usize) -> usize {
@@ -756,26 +1443,86 @@ impl Modulus {
 
     /// Serialize a vector of elements of length a multiple of 8.
     ///
+    /// The elements are bit-packed using exactly the number of bits of p - 1
+    /// each, i.e., ceil(log2(p)) bits, so that the serialization of a vector
+    /// modulo a 30-bit prime uses 30 bits per element rather than 32.
+    ///
     /// Panics if the length of the vector is not a multiple of 8.
     pub fn serialize_vec(&self, a: &[u64]) -> Vec<u8> {
         let p_nbits = 64 - (self.p - 1).leading_zeros() as usize;
         transcode_to_bytes(a, p_nbits)
     }
 
+    /// Serialize a vector of elements into `out` without allocating; the
+    /// bytes are the same as the ones of [`Modulus::serialize_vec`].
+    ///
+    /// Panics if `out` is not exactly as long as the serialization of `a`,
+    /// i.e., [`Modulus::serialization_length`] bytes when the length of `a`
+    /// is a multiple of 8.
+    pub fn serialize_vec_into(&self, a: &[u64], out: &mut [u8]) {
+        let p_nbits = 64 - (self.p - 1).leading_zeros() as usize;
+        transcode_to_bytes_into(a, p_nbits, out)
+    }
+
     /// Deserialize a vector of bytes into a vector of elements mod p.
     pub fn deserialize_vec(&self, b: &[u8]) -> Vec<u64> {
         let p_nbits = 64 - (self.p - 1).leading_zeros() as usize;
         transcode_from_bytes(b, p_nbits)
     }
+
+    /// Length of the serialization of a vector of size `size` with
+    /// [`Modulus::serialize_vec_packed`], i.e., `size` times the number of
+    /// bits of p - 1, rounded up to a whole number of bytes.
+    pub const fn serialization_length_packed(&self, size: usize) -> usize {
+        let p_nbits = 64 - (self.p - 1).leading_zeros() as usize;
+        (p_nbits * size).div_ceil(8)
+    }
+
+    /// Serialize a vector of elements of any length, bit-packed using exactly
+    /// the number of bits of p - 1 each, i.e., ceil(log2(p)) bits; the last
+    /// byte is padded with zero bits. For a length multiple of 8, the bytes are
+    /// the same as the ones of [`Modulus::serialize_vec`].
+    pub fn serialize_vec_packed(&self, a: &[u64]) -> Vec<u8> {
+        let p_nbits = 64 - (self.p - 1).leading_zeros() as usize;
+        transcode_to_bytes(a, p_nbits)
+    }
+
+    /// Deserialize a vector of `size` elements mod p serialized with
+    /// [`Modulus::serialize_vec_packed`].
+    ///
+    /// Returns an error if the number of bytes is not
+    /// [`Modulus::serialization_length_packed`], if the padding bits are not
+    /// zero, or if an element is not reduced modulo p.
+    pub fn deserialize_vec_packed(&self, b: &[u8], size: usize) -> Result<Vec<u64>> {
+        if b.len() != self.serialization_length_packed(size) {
+            return Err(Error::Serialization(
+                "Invalid packed serialization: unexpected number of bytes".into(),
+            ));
+        }
+        let p_nbits = 64 - (self.p - 1).leading_zeros() as usize;
+        let mut a = transcode_from_bytes(b, p_nbits);
+        // The check does not short-circuit on the (possibly secret) elements.
+        let padding_is_zero = a.drain(size..).fold(true, |acc, ai| acc & (ai == 0));
+        let is_reduced = a.iter().fold(true, |acc, ai| acc & (*ai < self.p));
+        if !padding_is_zero || !is_reduced {
+            return Err(Error::Serialization(
+                "Invalid packed serialization: invalid element".into(),
+            ));
+        }
+        Ok(a)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{primes, Modulus};
+    use super::{primes, Modulus, POW_NUM_ITERATIONS};
     use itertools::{izip, Itertools};
+    use ndarray::{s, Array1, ArrayView1};
+    use num_bigint::BigUint;
     use proptest::collection::vec as prop_vec;
     use proptest::prelude::{any, BoxedStrategy, Just, Strategy};
-    use rand::{thread_rng, RngCore};
+    use rand::{thread_rng, RngCore, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     // Utility functions for the proptests.
 
@@ -921,6 +1668,49 @@ mod tests {
             unsafe { prop_assert_eq!(p.reduce_i64_vt(a), b) }
         }
 
+        #[test]
+        fn center(p in valid_moduli(), a: i64) {
+            // Map a in the interval (-p/2, p/2].
+            let half = (*p >> 1) as i64;
+            let a = a.rem_euclid(*p as i64);
+            let a = if a > half { a - *p as i64 } else { a };
+
+            prop_assert_eq!(p.center(p.reduce_i64(a)), a);
+            prop_assert_eq!(p.center(0), 0);
+            prop_assert_eq!(p.center(*p >> 1), half);
+            prop_assert_eq!(p.center((*p >> 1) + 1), half + 1 - *p as i64);
+            prop_assert_eq!(p.center(*p - 1), -1);
+        }
+
+        #[test]
+        fn center_vec(p in valid_moduli(), a: Vec<i64>) {
+            let b = p.reduce_vec_i64(&a);
+            let c = p.center_vec(&b);
+            prop_assert_eq!(&c, &b.iter().map(|bi| p.center(*bi)).collect_vec());
+            prop_assert_eq!(p.reduce_vec_i64(&c), b.clone());
+
+            let mut d = vec![0i64; b.len()];
+            p.center_vec_into(&b, &mut d);
+            prop_assert_eq!(&c, &d);
+
+            prop_assert_eq!(unsafe { p.center_vec_vt(&b) }, c);
+            let half = [*p >> 1, (*p >> 1) + 1];
+            prop_assert_eq!(unsafe { p.center_vec_vt(&half) }, p.center_vec(&half));
+        }
+
+        #[test]
+        fn reduce_u128_edge_cases(p in valid_moduli(), a: u64) {
+            let q = *p as u128;
+            let a = a as u128;
+            let values = [a, (q << 64).wrapping_sub(a), (q << 64).wrapping_add(a), u128::MAX - a];
+            for x in values {
+                let expected = BigUint::from(x) % *p;
+                prop_assert_eq!(BigUint::from(p.reduce_u128(x)), expected.clone());
+                unsafe { prop_assert_eq!(BigUint::from(p.reduce_u128_vt(x)), expected) }
+            }
+            prop_assert_eq!(p.reduce_vec_u128(&values), values.iter().map(|x| p.reduce_u128(*x)).collect_vec());
+        }
+
         #[test]
         fn reduce_u128(p in valid_moduli(), mut a: u128) {
             prop_assert_eq!(p.reduce_u128(a) as u128, a % (*p as u128));
@@ -969,6 +1759,121 @@ mod tests {
             prop_assert_eq!(a, izip!(b.iter(), c.iter()).map(|(bi, ci)| p.mul(*ci, *bi)).collect_vec());
         }
 
+        #[test]
+        fn mul_vec_wide_modulus(a in prop_vec(0..1u64 << 20, 1..100), b in prop_vec(0..1u64 << 20, 1..100)) {
+            // The largest test modulus, with residues close to p.
+            let p = Modulus::new(4611686018326724609)?;
+            let n = a.len().min(b.len());
+            let mut a = a[..n].iter().map(|ai| *p - 1 - ai).collect_vec();
+            let b = b[..n].iter().map(|bi| *p - 1 - bi).collect_vec();
+            let expected = izip!(a.iter(), b.iter())
+                .map(|(ai, bi)| (((*ai as u128) * (*bi as u128)) % (*p as u128)) as u64)
+                .collect_vec();
+
+            let c = a.clone();
+            p.mul_vec(&mut a, &b);
+            prop_assert_eq!(&a, &expected);
+            a.clone_from(&c);
+            unsafe { p.mul_vec_vt(&mut a, &b) }
+            prop_assert_eq!(&a, &expected);
+
+            // The product of the largest residues.
+            let mut x = vec![*p - 1; 2];
+            p.mul_vec(&mut x, &[*p - 1, 1]);
+            prop_assert_eq!(x, vec![1, *p - 1]);
+        }
+
+        #[test]
+        fn montgomery(p in valid_moduli(), (mut a, mut b) in vecs()) {
+            prop_assume!(p.supports_montgomery());
+            p.reduce_vec(&mut a);
+            p.reduce_vec(&mut b);
+
+            // Conversions to and from the Montgomery representation.
+            let mut a_mont = a.clone();
+            p.to_montgomery_vec(&mut a_mont);
+            let r = p.reduce_u128(1 << 64);
+            prop_assert_eq!(&a_mont, &a.iter().map(|ai| p.mul(*ai, r)).collect_vec());
+            let mut c = a_mont.clone();
+            p.from_montgomery_vec(&mut c);
+            prop_assert_eq!(&c, &a);
+
+            // The Montgomery multiplication matches the Barrett multiplication.
+            let mut b_mont = b.clone();
+            p.to_montgomery_vec(&mut b_mont);
+            p.mont_mul_vec(&mut a_mont, &b_mont);
+            p.from_montgomery_vec(&mut a_mont);
+            p.mul_vec(&mut a, &b);
+            prop_assert_eq!(a_mont, a);
+
+            prop_assert_eq!(p.from_montgomery(p.to_montgomery(*p - 1)), *p - 1);
+            prop_assert_eq!(p.mont_mul(p.to_montgomery(1), 1), 1);
+        }
+
+        #[test]
+        fn vec_strided(p in valid_moduli(), (mut a, mut b) in vecs()) {
+            p.reduce_vec(&mut a);
+            p.reduce_vec(&mut b);
+            let a_rev = Array1::from_iter(a.iter().rev().copied());
+
+            // Views of a reversed and stepped by 2, and the contiguous vectors
+            // they correspond to.
+            let a_step = a.iter().step_by(2).copied().collect_vec();
+            let b_step = b.iter().step_by(2).copied().collect_vec();
+            let b_rev = b.iter().rev().copied().collect_vec();
+
+            macro_rules! check_binary {
+                ($f:ident, $f_vt:ident, $f_vec:ident) => {{
+                    let mut expected = a.clone();
+                    p.$f_vec(&mut expected, &b);
+                    let mut c = a_rev.clone();
+                    p.$f(c.slice_mut(s![..;-1]), ArrayView1::from(&b));
+                    prop_assert_eq!(c.iter().rev().copied().collect_vec(), expected.clone());
+                    let mut c = a_rev.clone();
+                    unsafe { p.$f_vt(c.slice_mut(s![..;-1]), ArrayView1::from(&b)) };
+                    prop_assert_eq!(c.iter().rev().copied().collect_vec(), expected);
+
+                    let mut expected = a_step.clone();
+                    p.$f_vec(&mut expected, &b_step);
+                    let mut c = Array1::from(a.clone());
+                    p.$f(c.slice_mut(s![..;2]), ArrayView1::from(&b).slice_move(s![..;2]));
+                    prop_assert_eq!(c.iter().step_by(2).copied().collect_vec(), expected.clone());
+                    prop_assert_eq!(c.iter().skip(1).step_by(2).collect_vec(), a.iter().skip(1).step_by(2).collect_vec());
+                    let mut c = Array1::from(a.clone());
+                    unsafe { p.$f_vt(c.slice_mut(s![..;2]), ArrayView1::from(&b).slice_move(s![..;2])) };
+                    prop_assert_eq!(c.iter().step_by(2).copied().collect_vec(), expected);
+
+                    // Contiguous views.
+                    let mut expected = a.clone();
+                    p.$f_vec(&mut expected, &b);
+                    let mut c = Array1::from(a.clone());
+                    p.$f(c.view_mut(), ArrayView1::from(&b));
+                    prop_assert_eq!(c.to_vec(), expected);
+                }};
+            }
+            check_binary!(add_vec_strided, add_vec_strided_vt, add_vec);
+            check_binary!(sub_vec_strided, sub_vec_strided_vt, sub_vec);
+            check_binary!(mul_vec_strided, mul_vec_strided_vt, mul_vec);
+
+            let mut expected = a_step.clone();
+            p.neg_vec(&mut expected);
+            let mut c = Array1::from(a.clone());
+            p.neg_vec_strided(c.slice_mut(s![..;2]));
+            prop_assert_eq!(c.iter().step_by(2).copied().collect_vec(), expected.clone());
+            let mut c = Array1::from(a.clone());
+            unsafe { p.neg_vec_strided_vt(c.slice_mut(s![..;2])) };
+            prop_assert_eq!(c.iter().step_by(2).copied().collect_vec(), expected);
+
+            let mut expected = b_rev.clone();
+            p.reduce_vec(&mut expected);
+            let mut c = Array1::from(b.clone());
+            p.reduce_vec_strided(c.slice_mut(s![..;-1]));
+            prop_assert_eq!(c.iter().rev().copied().collect_vec(), expected.clone());
+            let mut c = Array1::from(b.clone());
+            unsafe { p.reduce_vec_strided_vt(c.slice_mut(s![..;-1])) };
+            prop_assert_eq!(c.iter().rev().copied().collect_vec(), expected);
+        }
+
         #[test]
         fn scalar_mul_vec(p in valid_moduli(), mut a: Vec<u64>, mut b: u64) {
             p.reduce_vec(&mut a);
@@ -983,6 +1888,58 @@ mod tests {
             prop_assert_eq!(a, c.iter().map(|ci| p.mul(*ci, b)).collect_vec());
         }
 
+        #[test]
+        fn scalar_mul_shoup_vec(p in valid_moduli(), mut a: Vec<u64>, mut b: u64) {
+            p.reduce_vec(&mut a);
+            b = p.reduce(b);
+            let b_shoup = p.shoup(b);
+            let c = a.clone();
+            let mut expected = a.clone();
+            p.mul_shoup_vec(&mut expected, &vec![b; a.len()], &vec![b_shoup; a.len()]);
+            let mut expected_mul = a.clone();
+            p.mul_vec(&mut expected_mul, &vec![b; a.len()]);
+            prop_assert_eq!(&expected, &expected_mul);
+
+            p.scalar_mul_shoup_vec(&mut a, b, b_shoup);
+            prop_assert_eq!(&a, &expected);
+
+            a.clone_from(&c);
+            unsafe { p.scalar_mul_shoup_vec_vt(&mut a, b, b_shoup) }
+            prop_assert_eq!(&a, &expected);
+        }
+
+        #[test]
+        fn scalar_add_vec(p in valid_moduli(), mut a: Vec<u64>, mut b: u64) {
+            p.reduce_vec(&mut a);
+            b = p.reduce(b);
+            let c = a.clone();
+            let mut expected = a.clone();
+            p.add_vec(&mut expected, &vec![b; a.len()]);
+
+            p.scalar_add_vec(&mut a, b);
+            prop_assert_eq!(&a, &expected);
+
+            a.clone_from(&c);
+            unsafe { p.scalar_add_vec_vt(&mut a, b) }
+            prop_assert_eq!(&a, &expected);
+        }
+
+        #[test]
+        fn scalar_sub_vec(p in valid_moduli(), mut a: Vec<u64>, mut b: u64) {
+            p.reduce_vec(&mut a);
+            b = p.reduce(b);
+            let c = a.clone();
+            let mut expected = a.clone();
+            p.sub_vec(&mut expected, &vec![b; a.len()]);
+
+            p.scalar_sub_vec(&mut a, b);
+            prop_assert_eq!(&a, &expected);
+
+            a.clone_from(&c);
+            unsafe { p.scalar_sub_vec_vt(&mut a, b) }
+            prop_assert_eq!(&a, &expected);
+        }
+
         #[test]
         fn mul_shoup_vec(p in valid_moduli(), (mut a, mut b) in vecs()) {
             p.reduce_vec(&mut a);
@@ -996,6 +1953,58 @@ mod tests {
             prop_assert_eq!(a, izip!(b.iter(), c.iter()).map(|(bi, ci)| p.mul(*ci, *bi)).collect_vec());
         }
 
+        #[test]
+        fn lazy_mul_shoup_vec(p in valid_moduli(), (mut a, mut b) in vecs()) {
+            p.reduce_vec(&mut b);
+            let b_shoup = p.shoup_vec(&b);
+            let c = a.clone();
+            p.lazy_mul_shoup_vec(&mut a, &b, &b_shoup);
+            prop_assert!(a.iter().all(|ai| *ai < 2 * *p));
+            p.reduce_vec(&mut a);
+            prop_assert_eq!(a, izip!(b.iter(), c.iter()).map(|(bi, ci)| p.mul(p.reduce(*ci), *bi)).collect_vec());
+        }
+
+        #[test]
+        fn lazy_add_vec(p in valid_moduli(), (mut a, mut b) in vecs()) {
+            a.iter_mut().for_each(|ai| *ai %= 2 * *p);
+            b.iter_mut().for_each(|bi| *bi %= 2 * *p);
+            let c = a.clone();
+            p.lazy_add_vec(&mut a, &b);
+            prop_assert!(a.iter().all(|ai| *ai < 2 * *p));
+            p.reduce_vec(&mut a);
+            prop_assert_eq!(a, izip!(b.iter(), c.iter()).map(|(bi, ci)| p.add(p.reduce(*bi), p.reduce(*ci))).collect_vec());
+        }
+
+        #[test]
+        fn fma_vec(p in valid_moduli(), (mut a, mut b) in vecs(), nterms in 1..20usize) {
+            p.reduce_vec(&mut a);
+            p.reduce_vec(&mut b);
+            let nterms = nterms.min(p.fma_max_terms() as usize);
+
+            let mut acc = vec![0u128; a.len()];
+            let mut acc_vt = vec![0u128; a.len()];
+            let mut expected = vec![0u64; a.len()];
+            for _ in 0..nterms {
+                p.fma_vec(&mut acc, &a, &b);
+                unsafe { p.fma_vec_vt(&mut acc_vt, &a, &b) }
+                izip!(expected.iter_mut(), &a, &b).for_each(|(e, ai, bi)| *e = p.add(*e, p.mul(*ai, *bi)));
+            }
+            prop_assert_eq!(&acc, &acc_vt);
+            prop_assert_eq!(p.reduce_vec_u128(&acc), expected.clone());
+            unsafe { prop_assert_eq!(p.reduce_vec_u128_vt(&acc), expected) }
+
+            // Vectors of different sizes are rejected, also in release mode.
+            let mut acc = vec![0u128; a.len() + 1];
+            prop_assert!(std::panic::catch_unwind(move || p.fma_vec(&mut acc, &a, &b)).is_err());
+        }
+
+        #[test]
+        fn reduce_vec_u128(p in valid_moduli(), a: Vec<u128>) {
+            let expected = a.iter().map(|ai| (*ai % (*p as u128)) as u64).collect_vec();
+            prop_assert_eq!(p.reduce_vec_u128(&a), expected.clone());
+            unsafe { prop_assert_eq!(p.reduce_vec_u128_vt(&a), expected) }
+        }
+
         #[test]
         fn reduce_vec(p in valid_moduli(), a: Vec<u64>) {
             let mut b = a.clone();
@@ -1030,6 +2039,35 @@ mod tests {
             prop_assert_eq!(b, a.iter().map(|ai| p.reduce_i64(*ai)).collect_vec());
         }
 
+        #[test]
+        fn pow_biguint(p in valid_moduli(), mut a: u64, n: u64) {
+            a = p.reduce(a);
+            let expected = BigUint::from(a).modpow(&BigUint::from(n), &BigUint::from(*p));
+            prop_assert_eq!(BigUint::from(p.pow(a, n)), expected.clone());
+            unsafe { prop_assert_eq!(BigUint::from(p.pow_vt(a, n)), expected) }
+            prop_assert_eq!(p.pow(a, 0), 1);
+            prop_assert_eq!(p.pow(0, 0), 1);
+        }
+
+        #[test]
+        fn pow_vec(p in valid_moduli(), mut a: Vec<u64>, n: u64) {
+            p.reduce_vec(&mut a);
+            let mut b = a.clone();
+            p.pow_vec(&mut b, n);
+            prop_assert_eq!(b.clone(), a.iter().map(|ai| p.pow(*ai, n)).collect_vec());
+            b.clone_from(&a);
+            unsafe { p.pow_vec_vt(&mut b, n); }
+            prop_assert_eq!(b, a.iter().map(|ai| p.pow(*ai, n)).collect_vec());
+        }
+
+        #[test]
+        fn inv_vec_elementwise(p in valid_moduli(), mut a: Vec<u64>) {
+            p.reduce_vec(&mut a);
+            let expected = a.iter().map(|ai| p.inv(*ai)).collect::<Option<Vec<_>>>();
+            prop_assert_eq!(p.inv_vec(&a), expected.clone());
+            unsafe { prop_assert_eq!(p.inv_vec_vt(&a), expected) }
+        }
+
         #[test]
         fn neg_vec(p in valid_moduli(), mut a: Vec<u64>) {
             p.reduce_vec(&mut a);
@@ -1056,6 +2094,14 @@ mod tests {
             }
         }
 
+        #[test]
+        fn random_vec_from_rng(p in valid_moduli(), size in 1..1000usize, seed: u64) {
+            let v = p.random_vec_from_rng(size, &mut ChaCha8Rng::seed_from_u64(seed));
+            prop_assert_eq!(v.len(), size);
+            prop_assert!(v.iter().all(|vi| *vi < *p));
+            prop_assert_eq!(&v, &p.random_vec(size, &mut ChaCha8Rng::seed_from_u64(seed)));
+        }
+
         #[test]
         fn serialize(p in valid_moduli(), mut a in prop_vec(any::<u64>(), 8)) {
             p.reduce_vec(&mut a);
@@ -1065,6 +2111,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn serialization_is_bit_packed() -> Result<(), Box<dyn std::error::Error>> {
+        let mut rng = rand::thread_rng();
+        for nbits in [10, 17, 30, 33, 50, 62] {
+            let p = Modulus::new(primes::generate_prime(nbits, 16, 1 << nbits).unwrap())?;
+            let a = p.random_vec(64, &mut rng);
+            let b = p.serialize_vec(&a);
+            assert_eq!(b.len(), p.serialization_length(64));
+            assert_eq!(b.len(), nbits * 64 / 8);
+            // Smaller than when each element uses whole bytes.
+            assert!(b.len() < nbits.div_ceil(8) * 64);
+            assert_eq!(p.deserialize_vec(&b), a);
+
+            let mut c = vec![0xffu8; b.len()];
+            p.serialize_vec_into(&a, &mut c);
+            assert_eq!(c, b);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn serialization_packed() -> Result<(), Box<dyn std::error::Error>> {
+        let mut rng = rand::thread_rng();
+        for nbits in [10, 17, 30, 33, 50, 62] {
+            let p = Modulus::new(primes::generate_prime(nbits, 16, 1 << nbits).unwrap())?;
+            for size in [1, 7, 8, 63, 64] {
+                let a = p.random_vec(size, &mut rng);
+                let b = p.serialize_vec_packed(&a);
+                assert_eq!(b.len(), p.serialization_length_packed(size));
+                assert_eq!(b.len(), (nbits * size).div_ceil(8));
+                // Smaller than when each element uses whole bytes.
+                if size >= 8 {
+                    assert!(b.len() < nbits.div_ceil(8) * size);
+                }
+                if size % 8 == 0 {
+                    assert_eq!(b, p.serialize_vec(&a));
+                }
+                assert_eq!(p.deserialize_vec_packed(&b, size)?, a);
+
+                assert!(p.deserialize_vec_packed(&b[1..], size).is_err());
+                assert!(p.deserialize_vec_packed(&b, size + 8).is_err());
+            }
+
+            // Unreduced elements and non-zero padding bits are rejected.
+            let b = p.serialize_vec_packed(&[*p]);
+            assert!(p.deserialize_vec_packed(&b, 1).is_err());
+            let mut b = p.serialize_vec_packed(&[0]);
+            *b.last_mut().unwrap() |= 0x80;
+            assert!(p.deserialize_vec_packed(&b, 1).is_err());
+        }
+        Ok(())
+    }
+
     // TODO: Make a proptest.
     #[test]
     fn mul_opt() {
@@ -1120,10 +2219,13 @@ mod tests {
             {
                 assert!(std::panic::catch_unwind(|| q.pow(p, 1)).is_err());
                 assert!(std::panic::catch_unwind(|| q.pow(p << 1, 1)).is_err());
-                assert!(std::panic::catch_unwind(|| q.pow(0, p)).is_err());
-                assert!(std::panic::catch_unwind(|| q.pow(0, p << 1)).is_err());
             }
 
+            assert_eq!(q.pow(0, 0), 1);
+            assert_eq!(q.pow(0, p), 0);
+            assert_eq!(q.pow(0, p << 1), 0);
+            assert_eq!(q.pow(1, u64::MAX), 1);
+
             for _ in 0..ntests {
                 let a = rng.next_u64() % p;
                 let b = (rng.next_u64() % p) % 1000;
@@ -1134,6 +2236,7 @@ mod tests {
                     c -= 1;
                 }
                 assert_eq!(q.pow(a, b), r);
+                assert_eq!(unsafe { q.pow_vt(a, b) }, r);
             }
         }
     }
@@ -1148,6 +2251,7 @@ mod tests {
             let q = Modulus::new(p).unwrap();
 
             assert!(q.inv(0).is_none());
+            assert!(unsafe { q.inv_vt(0) }.is_none());
             assert_eq!(q.inv(1).unwrap(), 1);
             assert_eq!(q.inv(p - 1).unwrap(), p - 1);
 
@@ -1160,6 +2264,7 @@ mod tests {
             for _ in 0..ntests {
                 let a = rng.next_u64() % p;
                 let b = q.inv(a);
+                assert_eq!(b, unsafe { q.inv_vt(a) });
 
                 if a == 0 {
                     assert!(b.is_none())
@@ -1169,5 +2274,59 @@ mod tests {
                 }
             }
         }
+
+        // The modulus must be prime.
+        let q = Modulus::new(4).unwrap();
+        assert!(q.inv(3).is_none());
+        assert!(unsafe { q.inv_vt(3) }.is_none());
+
+        // The constant time exponentiation, and therefore inversion, iterates over
+        // all the bits of the exponent regardless of its value.
+        assert_eq!(POW_NUM_ITERATIONS, u64::BITS);
+    }
+
+    #[test]
+    fn random_vec_uniformity() {
+        // Chi-square test with 16 degrees of freedom: the statistic exceeds 39.25
+        // with probability 0.001 for uniform samples.
+        let p = Modulus::new(17).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let samples = p.random_vec_from_rng(17 * 1000, &mut rng);
+
+        let mut counts = [0usize; 17];
+        samples.iter().for_each(|s| counts[*s as usize] += 1);
+        let chi_square = counts
+            .iter()
+            .map(|c| ((*c as f64) - 1000.0).powi(2) / 1000.0)
+            .sum::<f64>();
+        assert!(chi_square < 39.25, "chi_square = {chi_square}");
+    }
+
+    #[test]
+    fn inv_vec() {
+        let mut rng = rand::thread_rng();
+
+        for p in [2u64, 3, 17, 1987, 4611686018326724609] {
+            let q = Modulus::new(p).unwrap();
+
+            assert_eq!(q.inv_vec(&[]), Some(vec![]));
+            assert_eq!(q.inv_vec(&[0]), None);
+            assert_eq!(q.inv_vec(&[1]), Some(vec![1]));
+            assert_eq!(q.inv_vec(&[p - 1]), Some(vec![p - 1]));
+
+            let mut a = (0..100).map(|_| 1 + rng.next_u64() % (p - 1)).collect_vec();
+            let b = q.inv_vec(&a).unwrap();
+            assert_eq!(b, unsafe { q.inv_vec_vt(&a) }.unwrap());
+            izip!(&a, &b).for_each(|(ai, bi)| assert_eq!(q.mul(*ai, *bi), 1));
+
+            a[42] = 0;
+            assert!(q.inv_vec(&a).is_none());
+            assert!(unsafe { q.inv_vec_vt(&a) }.is_none());
+        }
+
+        // The modulus must be prime.
+        let q = Modulus::new(4).unwrap();
+        assert!(q.inv_vec(&[1, 3]).is_none());
+        assert!(unsafe { q.inv_vec_vt(&[1, 3]) }.is_none());
     }
 }
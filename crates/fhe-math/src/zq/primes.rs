@@ -1,5 +1,9 @@
 //! Optimized primes generated as in the NFLlib library.
 
+use super::Modulus;
+use crate::ntt::supports_ntt;
+use alloc::vec::Vec;
+use core::iter::successors;
 use fhe_util::is_prime;
 use num_bigint::BigUint;
 
@@ -22,6 +26,31 @@ pub fn supports_opt(p: u64) -> bool {
     left_side < middle
 }
 
+/// Returns the largest prime strictly smaller than `below` that supports the
+/// NTT of size `degree`, i.e., which is congruent to 1 modulo `2 * degree`, or
+/// `None` if there is no such prime.
+pub fn previous_ntt_prime(below: u64, degree: usize) -> Option<u64> {
+    let modulo = (degree as u64).checked_mul(2).filter(|m| *m > 0)?;
+    let start = below.checked_sub(2)? / modulo * modulo + 1;
+    successors(Some(start), |p| p.checked_sub(modulo)).find(|p| is_prime(*p))
+}
+
+/// Generates `count` distinct `bit_size`-bit primes supporting the NTT of size
+/// `degree`, in descending order. Returns `None` if `bit_size` does not belong
+/// to (10..=62), if `degree` is not a power of 2 that is >= 8, or if there are
+/// not enough such primes.
+pub fn generate_ntt_primes(bit_size: usize, degree: usize, count: usize) -> Option<Vec<u64>> {
+    if !(10..=62).contains(&bit_size) || degree < 8 || !degree.is_power_of_two() {
+        return None;
+    }
+
+    let primes = primes_supporting_ntt(bit_size, degree)
+        .take_while(|p| p.leading_zeros() == (64 - bit_size) as u32)
+        .take(count)
+        .collect::<Vec<_>>();
+    (primes.len() == count).then_some(primes)
+}
+
 /// Generate a `num_bits`-bit prime, congruent to 1 mod `modulo`, strictly
 /// smaller than `upper_bound`. Note that `num_bits` must belong to (10..=62),
 /// and upper_bound must be <= 1 << num_bits.
@@ -56,9 +85,30 @@ pub fn generate_prime(num_bits: usize, modulo: u64, upper_bound: u64) -> Option<
     }
 }
 
+/// Returns an iterator over the primes strictly smaller than `2^bit_size` that
+/// support the NTT of size `degree`, i.e., which are congruent to 1 modulo
+/// `2 * degree`, in descending order.
+///
+/// Aborts if `bit_size` is larger than 62, or if `degree` is not a power of 2
+/// that is >= 8.
+pub fn primes_supporting_ntt(bit_size: usize, degree: usize) -> impl Iterator<Item = u64> {
+    assert!(bit_size <= 62, "The bit size must be at most 62");
+    assert!(degree >= 8 && degree.is_power_of_two());
+
+    let modulo = 2 * degree as u64;
+    // Largest integer smaller than 2^bit_size congruent to 1 modulo `modulo`.
+    let start = ((1u64 << bit_size) - 1) / modulo * modulo + 1;
+    successors(Some(start), move |p| p.checked_sub(modulo))
+        .filter(move |p| supports_ntt(*p, degree) && Modulus::new(*p).is_ok())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::generate_prime;
+    use super::{
+        generate_ntt_primes, generate_prime, is_prime, previous_ntt_prime, primes_supporting_ntt,
+    };
+    use crate::ntt::supports_ntt;
+    use itertools::Itertools;
 
     // Verifies that the same moduli as in the NFLlib library are generated.
     // <https://github.com/quarkslab/NFLlib/blob/master/include/nfl/params.hpp>
@@ -118,4 +168,74 @@ mod tests {
         // smaller one should fail.
         assert!(generate_prime(11, 16, 1033).is_none());
     }
+
+    #[test]
+    fn primes_supporting_ntt_1024() {
+        let primes = primes_supporting_ntt(62, 1024).take(10).collect_vec();
+        assert_eq!(primes.len(), 10);
+        assert!(primes.windows(2).all(|w| w[0] > w[1]));
+        for p in &primes {
+            assert!(*p < 1 << 62);
+            assert_eq!(p % 2048, 1);
+            assert!(is_prime(*p));
+            assert!(supports_ntt(*p, 1024));
+        }
+
+        // The primes are the same as the ones obtained with `generate_prime`.
+        let mut upper_bound = 1 << 62;
+        for p in &primes {
+            upper_bound = generate_prime(62, 2048, upper_bound).unwrap();
+            assert_eq!(*p, upper_bound);
+        }
+    }
+
+    #[test]
+    fn primes_supporting_ntt_exhaustive() {
+        // All the primes smaller than 2^12 congruent to 1 modulo 16.
+        assert_eq!(
+            primes_supporting_ntt(12, 8).collect_vec(),
+            (2..1 << 12)
+                .rev()
+                .filter(|p| p % 16 == 1 && is_prime(*p))
+                .collect_vec()
+        );
+        assert_eq!(primes_supporting_ntt(4, 8).count(), 0);
+    }
+
+    #[test]
+    fn previous_ntt_prime_matches() {
+        let mut below = 1 << 62;
+        for p in primes_supporting_ntt(62, 1024).take(5) {
+            assert_eq!(previous_ntt_prime(below, 1024), Some(p));
+            below = p;
+        }
+
+        assert_eq!(previous_ntt_prime(1009, 8), Some(977));
+        assert_eq!(previous_ntt_prime(1010, 8), Some(1009));
+        assert_eq!(previous_ntt_prime(17, 8), None);
+        assert_eq!(previous_ntt_prime(18, 8), Some(17));
+        assert_eq!(previous_ntt_prime(0, 8), None);
+        assert_eq!(previous_ntt_prime(1 << 20, 0), None);
+    }
+
+    #[test]
+    fn generate_ntt_primes_distinct() {
+        for (bit_size, degree) in [(62, 1 << 15), (50, 4096), (20, 8)] {
+            let primes = generate_ntt_primes(bit_size, degree, 10).unwrap();
+            assert_eq!(primes.len(), 10);
+            assert!(primes.windows(2).all(|w| w[0] > w[1]));
+            for p in primes {
+                assert_eq!(64 - p.leading_zeros() as usize, bit_size);
+                assert!(supports_ntt(p, degree));
+            }
+        }
+
+        // There are only two 11-bit primes congruent to 1 modulo 128.
+        assert_eq!(generate_ntt_primes(11, 64, 2), Some(vec![1409, 1153]));
+        assert!(generate_ntt_primes(11, 64, 3).is_none());
+        assert!(generate_ntt_primes(63, 1024, 1).is_none());
+        assert!(generate_ntt_primes(9, 8, 1).is_none());
+        assert!(generate_ntt_primes(62, 1000, 1).is_none());
+        assert_eq!(generate_ntt_primes(62, 1024, 0), Some(vec![]));
+    }
 }
@@ -0,0 +1,617 @@
+//! Vectorized kernels for vectors of integers modulo a modulus up to 62 bits.
+//!
+//! The kernels are written once on top of the lane operations of the [`Lanes`]
+//! trait, which is implemented with AVX2 intrinsics on x86_64 (when the CPU
+//! supports it, as detected at runtime) and with NEON intrinsics on aarch64.
+//! All the kernels are constant time and compute exactly the same values as
+//! the scalar functions of [`Modulus`](super::Modulus).
+//!
+//! Each kernel returns how many elements it processed, so that the caller can
+//! finish the computation (or do all of it) with the scalar code.
+//!
+//! There is no kernel for the multiplication of two vectors: without a 64-bit
+//! multiplier in the vector units, the Barrett reduction takes 22 products of
+//! 32-bit lanes for 4 values with AVX2, and is slower than the scalar code;
+//! see [`Modulus::mul_vec`](super::Modulus::mul_vec).
+
+/// Operations on vectors of u64 lanes.
+///
+/// The comparisons return masks where a lane is either all ones or all zeros.
+trait Lanes: Copy {
+    /// Vector of u64.
+    type V: Copy;
+
+    /// Number of u64 in a vector.
+    const LANES: usize;
+
+    /// Load a vector from `ptr`, which must point to `LANES` elements.
+    unsafe fn load(self, ptr: *const u64) -> Self::V;
+
+    /// Store a vector to `ptr`, which must point to `LANES` elements.
+    unsafe fn store(self, ptr: *mut u64, a: Self::V);
+
+    /// Load `LANES` u32 from `ptr` and zero-extend them to u64.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    unsafe fn load_u32(self, ptr: *const u32) -> Self::V;
+
+    /// Broadcast a value to all the lanes.
+    fn splat(self, a: u64) -> Self::V;
+
+    /// Wrapping addition.
+    fn add(self, a: Self::V, b: Self::V) -> Self::V;
+
+    /// Wrapping subtraction.
+    fn sub(self, a: Self::V, b: Self::V) -> Self::V;
+
+    /// Product of the 32 least significant bits of a and b.
+    fn mul32(self, a: Self::V, b: Self::V) -> Self::V;
+
+    /// Shift right by 32 bits.
+    fn shr32(self, a: Self::V) -> Self::V;
+
+    /// Shift left by 32 bits.
+    fn shl32(self, a: Self::V) -> Self::V;
+
+    /// Keep the 32 least significant bits.
+    fn lo32(self, a: Self::V) -> Self::V;
+
+    /// Mask of the lanes where a < b.
+    fn lt(self, a: Self::V, b: Self::V) -> Self::V;
+
+    /// Select a in the lanes where the mask is set, and b otherwise.
+    fn select(self, mask: Self::V, a: Self::V, b: Self::V) -> Self::V;
+}
+
+/// Kernels written generically over the lane operations.
+///
+/// All the functions are inlined so that they get compiled with the target
+/// features of the function calling them.
+mod kernels {
+    use super::Lanes;
+
+    /// Return x mod p, assuming x < 2 * p.
+    #[inline(always)]
+    fn reduce1<S: Lanes>(s: S, x: S::V, p: S::V) -> S::V {
+        s.select(s.lt(x, p), x, s.sub(x, p))
+    }
+
+    /// Returns the (high, low) 64-bit words of a * b.
+    #[inline(always)]
+    fn mul_wide<S: Lanes>(s: S, a: S::V, b: S::V) -> (S::V, S::V) {
+        let a_hi = s.shr32(a);
+        let b_hi = s.shr32(b);
+        let lo_lo = s.mul32(a, b);
+        let lo_hi = s.mul32(a, b_hi);
+        let hi_lo = s.mul32(a_hi, b);
+        let hi_hi = s.mul32(a_hi, b_hi);
+
+        let mid = s.add(s.add(s.shr32(lo_lo), s.lo32(lo_hi)), s.lo32(hi_lo));
+        let hi = s.add(
+            s.add(s.add(hi_hi, s.shr32(lo_hi)), s.shr32(hi_lo)),
+            s.shr32(mid),
+        );
+        let lo = s.add(s.lo32(lo_lo), s.shl32(mid));
+        (hi, lo)
+    }
+
+    /// Returns the high 64-bit word of a * b.
+    #[inline(always)]
+    fn mul_hi<S: Lanes>(s: S, a: S::V, b: S::V) -> S::V {
+        mul_wide(s, a, b).0
+    }
+
+    /// Returns the low 64-bit word of a * b.
+    #[inline(always)]
+    fn mul_lo<S: Lanes>(s: S, a: S::V, b: S::V) -> S::V {
+        let cross = s.add(s.mul32(s.shr32(a), b), s.mul32(a, s.shr32(b)));
+        s.add(s.mul32(a, b), s.shl32(cross))
+    }
+
+    /// Lazy Shoup multiplication; the output is in [0, 2 * p).
+    #[inline(always)]
+    fn lazy_mul_shoup<S: Lanes>(s: S, a: S::V, b: S::V, b_shoup: S::V, p: S::V) -> S::V {
+        let q = mul_hi(s, a, b_shoup);
+        s.sub(mul_lo(s, a, b), mul_lo(s, q, p))
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn add_vec<S: Lanes>(s: S, p: u64, a: &mut [u64], b: &[u64]) -> usize {
+        let n = a.len().min(b.len());
+        let n = n - n % S::LANES;
+        let p = s.splat(p);
+        for i in (0..n).step_by(S::LANES) {
+            let ai = s.load(a.as_ptr().add(i));
+            let bi = s.load(b.as_ptr().add(i));
+            s.store(a.as_mut_ptr().add(i), reduce1(s, s.add(ai, bi), p));
+        }
+        n
+    }
+
+    #[inline(always)]
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(super) unsafe fn widen_u32<S: Lanes>(s: S, a: &mut [u64], b: &[u32]) -> usize {
+        let n = a.len().min(b.len());
+        let n = n - n % S::LANES;
+        for i in (0..n).step_by(S::LANES) {
+            s.store(a.as_mut_ptr().add(i), s.load_u32(b.as_ptr().add(i)));
+        }
+        n
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn sub_vec<S: Lanes>(s: S, p: u64, a: &mut [u64], b: &[u64]) -> usize {
+        let n = a.len().min(b.len());
+        let n = n - n % S::LANES;
+        let p = s.splat(p);
+        for i in (0..n).step_by(S::LANES) {
+            let ai = s.load(a.as_ptr().add(i));
+            let bi = s.load(b.as_ptr().add(i));
+            s.store(
+                a.as_mut_ptr().add(i),
+                reduce1(s, s.sub(s.add(ai, p), bi), p),
+            );
+        }
+        n
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn mul_shoup_vec<S: Lanes>(
+        s: S,
+        p: u64,
+        a: &mut [u64],
+        b: &[u64],
+        b_shoup: &[u64],
+    ) -> usize {
+        let n = a.len().min(b.len()).min(b_shoup.len());
+        let n = n - n % S::LANES;
+        let p = s.splat(p);
+        for i in (0..n).step_by(S::LANES) {
+            let ai = s.load(a.as_ptr().add(i));
+            let bi = s.load(b.as_ptr().add(i));
+            let bi_shoup = s.load(b_shoup.as_ptr().add(i));
+            s.store(
+                a.as_mut_ptr().add(i),
+                reduce1(s, lazy_mul_shoup(s, ai, bi, bi_shoup, p), p),
+            );
+        }
+        n
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn lazy_mul_shoup_vec<S: Lanes>(
+        s: S,
+        p: u64,
+        a: &mut [u64],
+        b: &[u64],
+        b_shoup: &[u64],
+    ) -> usize {
+        let n = a.len().min(b.len()).min(b_shoup.len());
+        let n = n - n % S::LANES;
+        let p = s.splat(p);
+        for i in (0..n).step_by(S::LANES) {
+            let ai = s.load(a.as_ptr().add(i));
+            let bi = s.load(b.as_ptr().add(i));
+            let bi_shoup = s.load(b_shoup.as_ptr().add(i));
+            s.store(
+                a.as_mut_ptr().add(i),
+                lazy_mul_shoup(s, ai, bi, bi_shoup, p),
+            );
+        }
+        n
+    }
+
+    /// One level of the forward NTT, with blocks of size `2 * l`.
+    #[inline(always)]
+    pub(super) unsafe fn ntt_forward_level<S: Lanes>(
+        s: S,
+        p: u64,
+        a: &mut [u64],
+        l: usize,
+        omegas: &[u64],
+        omegas_shoup: &[u64],
+    ) -> usize {
+        if l < S::LANES || omegas_shoup.len() < omegas.len() || a.len() < 2 * omegas.len() * l {
+            return 0;
+        }
+        let p_twice = s.splat(2 * p);
+        let p = s.splat(p);
+        for (i, (w, w_shoup)) in omegas.iter().zip(omegas_shoup).enumerate() {
+            let w = s.splat(*w);
+            let w_shoup = s.splat(*w_shoup);
+            let x_ptr = a.as_mut_ptr().add(2 * i * l);
+            let y_ptr = x_ptr.add(l);
+            for j in (0..l).step_by(S::LANES) {
+                let x = reduce1(s, s.load(x_ptr.add(j)), p_twice);
+                let t = lazy_mul_shoup(s, s.load(y_ptr.add(j)), w, w_shoup, p);
+                s.store(y_ptr.add(j), s.sub(s.add(x, p_twice), t));
+                s.store(x_ptr.add(j), s.add(x, t));
+            }
+        }
+        2 * omegas.len() * l
+    }
+
+    /// One level of the backward NTT, with blocks of size `2 * l`.
+    #[inline(always)]
+    pub(super) unsafe fn ntt_backward_level<S: Lanes>(
+        s: S,
+        p: u64,
+        a: &mut [u64],
+        l: usize,
+        zetas_inv: &[u64],
+        zetas_inv_shoup: &[u64],
+    ) -> usize {
+        if l < S::LANES
+            || zetas_inv_shoup.len() < zetas_inv.len()
+            || a.len() < 2 * zetas_inv.len() * l
+        {
+            return 0;
+        }
+        let p_twice = s.splat(2 * p);
+        let p = s.splat(p);
+        for (i, (z, z_shoup)) in zetas_inv.iter().zip(zetas_inv_shoup).enumerate() {
+            let z = s.splat(*z);
+            let z_shoup = s.splat(*z_shoup);
+            let x_ptr = a.as_mut_ptr().add(2 * i * l);
+            let y_ptr = x_ptr.add(l);
+            for j in (0..l).step_by(S::LANES) {
+                let x = s.load(x_ptr.add(j));
+                let y = s.load(y_ptr.add(j));
+                s.store(x_ptr.add(j), reduce1(s, s.add(x, y), p_twice));
+                s.store(
+                    y_ptr.add(j),
+                    lazy_mul_shoup(s, s.sub(s.add(x, p_twice), y), z, z_shoup, p),
+                );
+            }
+        }
+        2 * zetas_inv.len() * l
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::Lanes;
+    use core::arch::x86_64::*;
+
+    /// Token proving that the CPU supports AVX2.
+    #[derive(Clone, Copy)]
+    pub(super) struct Avx2(());
+
+    impl Avx2 {
+        #[inline(always)]
+        pub(super) fn detect() -> Option<Self> {
+            // Without `std`, the CPU features cannot be detected at runtime,
+            // so AVX2 is only used when it is enabled at compile time.
+            #[cfg(feature = "std")]
+            let avx2 = is_x86_feature_detected!("avx2");
+            #[cfg(not(feature = "std"))]
+            let avx2 = cfg!(target_feature = "avx2");
+            if avx2 {
+                Some(Self(()))
+            } else {
+                None
+            }
+        }
+    }
+
+    // The intrinsics are only called with an `Avx2` token, which can only be
+    // constructed when the CPU supports AVX2.
+    impl Lanes for Avx2 {
+        type V = __m256i;
+        const LANES: usize = 4;
+
+        #[inline(always)]
+        unsafe fn load(self, ptr: *const u64) -> __m256i {
+            _mm256_loadu_si256(ptr as *const __m256i)
+        }
+
+        #[inline(always)]
+        unsafe fn store(self, ptr: *mut u64, a: __m256i) {
+            _mm256_storeu_si256(ptr as *mut __m256i, a)
+        }
+
+        #[inline(always)]
+        unsafe fn load_u32(self, ptr: *const u32) -> __m256i {
+            _mm256_cvtepu32_epi64(_mm_loadu_si128(ptr as *const __m128i))
+        }
+
+        #[inline(always)]
+        fn splat(self, a: u64) -> __m256i {
+            unsafe { _mm256_set1_epi64x(a as i64) }
+        }
+
+        #[inline(always)]
+        fn add(self, a: __m256i, b: __m256i) -> __m256i {
+            unsafe { _mm256_add_epi64(a, b) }
+        }
+
+        #[inline(always)]
+        fn sub(self, a: __m256i, b: __m256i) -> __m256i {
+            unsafe { _mm256_sub_epi64(a, b) }
+        }
+
+        #[inline(always)]
+        fn mul32(self, a: __m256i, b: __m256i) -> __m256i {
+            unsafe { _mm256_mul_epu32(a, b) }
+        }
+
+        #[inline(always)]
+        fn shr32(self, a: __m256i) -> __m256i {
+            unsafe { _mm256_srli_epi64::<32>(a) }
+        }
+
+        #[inline(always)]
+        fn shl32(self, a: __m256i) -> __m256i {
+            unsafe { _mm256_slli_epi64::<32>(a) }
+        }
+
+        #[inline(always)]
+        fn lo32(self, a: __m256i) -> __m256i {
+            unsafe { _mm256_blend_epi32::<0b10101010>(a, _mm256_setzero_si256()) }
+        }
+
+        #[inline(always)]
+        fn lt(self, a: __m256i, b: __m256i) -> __m256i {
+            // AVX2 only has a signed comparison, so we flip the sign bits.
+            unsafe {
+                let sign = _mm256_set1_epi64x(i64::MIN);
+                _mm256_cmpgt_epi64(_mm256_xor_si256(b, sign), _mm256_xor_si256(a, sign))
+            }
+        }
+
+        #[inline(always)]
+        fn select(self, mask: __m256i, a: __m256i, b: __m256i) -> __m256i {
+            unsafe { _mm256_blendv_epi8(b, a, mask) }
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use super::Lanes;
+    use core::arch::aarch64::*;
+
+    /// NEON is always available on aarch64.
+    #[derive(Clone, Copy)]
+    pub(super) struct Neon;
+
+    impl Lanes for Neon {
+        type V = uint64x2_t;
+        const LANES: usize = 2;
+
+        #[inline(always)]
+        unsafe fn load(self, ptr: *const u64) -> uint64x2_t {
+            vld1q_u64(ptr)
+        }
+
+        #[inline(always)]
+        unsafe fn store(self, ptr: *mut u64, a: uint64x2_t) {
+            vst1q_u64(ptr, a)
+        }
+
+        #[inline(always)]
+        unsafe fn load_u32(self, ptr: *const u32) -> uint64x2_t {
+            vmovl_u32(vld1_u32(ptr))
+        }
+
+        #[inline(always)]
+        fn splat(self, a: u64) -> uint64x2_t {
+            unsafe { vdupq_n_u64(a) }
+        }
+
+        #[inline(always)]
+        fn add(self, a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+            unsafe { vaddq_u64(a, b) }
+        }
+
+        #[inline(always)]
+        fn sub(self, a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+            unsafe { vsubq_u64(a, b) }
+        }
+
+        #[inline(always)]
+        fn mul32(self, a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+            unsafe { vmull_u32(vmovn_u64(a), vmovn_u64(b)) }
+        }
+
+        #[inline(always)]
+        fn shr32(self, a: uint64x2_t) -> uint64x2_t {
+            unsafe { vshrq_n_u64::<32>(a) }
+        }
+
+        #[inline(always)]
+        fn shl32(self, a: uint64x2_t) -> uint64x2_t {
+            unsafe { vshlq_n_u64::<32>(a) }
+        }
+
+        #[inline(always)]
+        fn lo32(self, a: uint64x2_t) -> uint64x2_t {
+            unsafe { vandq_u64(a, vdupq_n_u64(u32::MAX as u64)) }
+        }
+
+        #[inline(always)]
+        fn lt(self, a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+            unsafe { vcltq_u64(a, b) }
+        }
+
+        #[inline(always)]
+        fn select(self, mask: uint64x2_t, a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+            unsafe { vbslq_u64(mask, a, b) }
+        }
+    }
+}
+
+/// Defines, for each kernel, a function that runs it with the best lane
+/// implementation available, and returns 0 if there is none.
+macro_rules! dispatch {
+    ($($(#[$doc:meta])* fn $name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            $(#[$doc])*
+            #[allow(unreachable_code, unused_variables)]
+            pub(crate) fn $name($($arg: $ty),*) -> usize {
+                #[cfg(target_arch = "x86_64")]
+                {
+                    #[target_feature(enable = "avx2")]
+                    unsafe fn avx2(s: x86::Avx2, $($arg: $ty),*) -> usize {
+                        kernels::$name(s, $($arg),*)
+                    }
+
+                    if let Some(s) = x86::Avx2::detect() {
+                        return unsafe { avx2(s, $($arg),*) };
+                    }
+                }
+                #[cfg(target_arch = "aarch64")]
+                {
+                    return unsafe { kernels::$name(aarch64::Neon, $($arg),*) };
+                }
+                0
+            }
+        )*
+    };
+}
+
+dispatch! {
+    /// Modular addition of the first elements of a and b in place.
+    fn add_vec(p: u64, a: &mut [u64], b: &[u64]);
+
+    /// Modular subtraction of the first elements of a and b in place.
+    fn sub_vec(p: u64, a: &mut [u64], b: &[u64]);
+
+    /// Zero extension of the first elements of b into a.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    fn widen_u32(a: &mut [u64], b: &[u32]);
+
+    /// Shoup multiplication of the first elements of a and b in place.
+    fn mul_shoup_vec(p: u64, a: &mut [u64], b: &[u64], b_shoup: &[u64]);
+
+    /// Lazy Shoup multiplication of the first elements of a and b in place.
+    fn lazy_mul_shoup_vec(p: u64, a: &mut [u64], b: &[u64], b_shoup: &[u64]);
+
+    /// Level of the forward NTT with `omegas.len()` blocks of size `2 * l`.
+    /// Returns 0 when l is smaller than the number of lanes.
+    fn ntt_forward_level(p: u64, a: &mut [u64], l: usize, omegas: &[u64], omegas_shoup: &[u64]);
+
+    /// Level of the backward NTT with `zetas_inv.len()` blocks of size `2 * l`.
+    /// Returns 0 when l is smaller than the number of lanes.
+    fn ntt_backward_level(
+        p: u64,
+        a: &mut [u64],
+        l: usize,
+        zetas_inv: &[u64],
+        zetas_inv_shoup: &[u64]
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zq::Modulus;
+    use itertools::izip;
+    use proptest::collection::vec as prop_vec;
+    use proptest::prelude::{any, Just, Strategy};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn valid_moduli() -> impl Strategy<Value = Modulus> {
+        any::<u64>().prop_filter_map("filter invalid moduli", |p| Modulus::new(p).ok())
+    }
+
+    fn moduli_and_vecs() -> impl Strategy<Value = (Modulus, Vec<u64>, Vec<u64>)> {
+        (valid_moduli(), 1..100usize).prop_flat_map(|(q, n)| {
+            let p = q.p;
+            (Just(q), prop_vec(0..p, n), prop_vec(0..p, n))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn add_vec((q, mut a, b) in moduli_and_vecs()) {
+            let expected = izip!(&a, &b).map(|(ai, bi)| q.add(*ai, *bi)).collect::<Vec<_>>();
+            let n = super::add_vec(q.p, &mut a, &b);
+            prop_assert_eq!(&a[..n], &expected[..n]);
+        }
+
+        #[test]
+        fn widen_u32(b in prop_vec(any::<u32>(), 0..100)) {
+            let mut a = vec![0u64; b.len()];
+            let n = super::widen_u32(&mut a, &b);
+            prop_assert_eq!(&a[..n], &b[..n].iter().map(|bi| *bi as u64).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn sub_vec((q, mut a, b) in moduli_and_vecs()) {
+            let expected = izip!(&a, &b).map(|(ai, bi)| q.sub(*ai, *bi)).collect::<Vec<_>>();
+            let n = super::sub_vec(q.p, &mut a, &b);
+            prop_assert_eq!(&a[..n], &expected[..n]);
+        }
+
+        #[test]
+        fn mul_shoup_vec((q, mut a, b) in moduli_and_vecs()) {
+            let b_shoup = q.shoup_vec(&b);
+            let expected = izip!(&a, &b, &b_shoup)
+                .map(|(ai, bi, bi_shoup)| q.mul_shoup(*ai, *bi, *bi_shoup))
+                .collect::<Vec<_>>();
+            let n = super::mul_shoup_vec(q.p, &mut a, &b, &b_shoup);
+            prop_assert_eq!(&a[..n], &expected[..n]);
+        }
+
+        #[test]
+        fn lazy_mul_shoup_vec((q, mut a, b) in moduli_and_vecs()) {
+            let b_shoup = q.shoup_vec(&b);
+            let expected = izip!(&a, &b, &b_shoup)
+                .map(|(ai, bi, bi_shoup)| q.lazy_mul_shoup(*ai, *bi, *bi_shoup))
+                .collect::<Vec<_>>();
+            let n = super::lazy_mul_shoup_vec(q.p, &mut a, &b, &b_shoup);
+            prop_assert_eq!(&a[..n], &expected[..n]);
+        }
+
+        #[test]
+        fn ntt_forward_level(q in valid_moduli(), log_l in 0..5usize, blocks in 1..4usize, seed: u64) {
+            let l = 1 << log_l;
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            // Inputs of the forward butterflies are in [0, 4 * p).
+            let mut a = (0..2 * l * blocks)
+                .map(|_| rng.gen_range(0..4 * q.p))
+                .collect::<Vec<_>>();
+            let omegas = (0..blocks).map(|_| rng.gen_range(0..q.p)).collect::<Vec<_>>();
+            let omegas_shoup = q.shoup_vec(&omegas);
+
+            let mut expected = a.clone();
+            for (i, (w, w_shoup)) in izip!(&omegas, &omegas_shoup).enumerate() {
+                for j in 2 * i * l..(2 * i + 1) * l {
+                    let x = Modulus::reduce1(expected[j], 2 * q.p);
+                    let t = q.lazy_mul_shoup(expected[j + l], *w, *w_shoup);
+                    expected[j + l] = x + 2 * q.p - t;
+                    expected[j] = x + t;
+                }
+            }
+
+            let n = super::ntt_forward_level(q.p, &mut a, l, &omegas, &omegas_shoup);
+            prop_assert!(n == 0 || n == a.len());
+            prop_assert_eq!(&a[..n], &expected[..n]);
+        }
+
+        #[test]
+        fn ntt_backward_level(q in valid_moduli(), log_l in 0..5usize, blocks in 1..4usize, seed: u64) {
+            let l = 1 << log_l;
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            // Inputs of the backward butterflies are in [0, 2 * p).
+            let mut a = (0..2 * l * blocks)
+                .map(|_| rng.gen_range(0..2 * q.p))
+                .collect::<Vec<_>>();
+            let zetas_inv = (0..blocks).map(|_| rng.gen_range(0..q.p)).collect::<Vec<_>>();
+            let zetas_inv_shoup = q.shoup_vec(&zetas_inv);
+
+            let mut expected = a.clone();
+            for (i, (z, z_shoup)) in izip!(&zetas_inv, &zetas_inv_shoup).enumerate() {
+                for j in 2 * i * l..(2 * i + 1) * l {
+                    let (x, y) = (expected[j], expected[j + l]);
+                    expected[j] = Modulus::reduce1(x + y, 2 * q.p);
+                    expected[j + l] = q.lazy_mul_shoup(2 * q.p + x - y, *z, *z_shoup);
+                }
+            }
+
+            let n = super::ntt_backward_level(q.p, &mut a, l, &zetas_inv, &zetas_inv_shoup);
+            prop_assert!(n == 0 || n == a.len());
+            prop_assert_eq!(&a[..n], &expected[..n]);
+        }
+    }
+}
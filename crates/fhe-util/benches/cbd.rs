@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fhe_util::sample_vec_cbd;
+use rand::thread_rng;
+
+pub fn cbd_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cbd");
+    group.sample_size(50);
+
+    let mut rng = thread_rng();
+    let vector_size = 16384;
+    group.throughput(Throughput::Elements(vector_size as u64));
+
+    for variance in [1usize, 10, 16] {
+        group.bench_function(
+            BenchmarkId::new("sample_vec_cbd", format!("{vector_size}/{variance}")),
+            |b| {
+                b.iter(|| sample_vec_cbd(vector_size, variance, &mut rng));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(cbd, cbd_benchmark);
+criterion_main!(cbd);
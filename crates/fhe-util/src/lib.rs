@@ -1,13 +1,21 @@
 #![crate_name = "fhe_util"]
 #![crate_type = "lib"]
 #![warn(missing_docs, unused_imports)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 //! Utilities for the fhe.rs library.
+//!
+//! The crate supports `no_std` environments with `alloc` when its default
+//! `std` feature is disabled.
+
+extern crate alloc;
 
 #[cfg(test)]
 extern crate proptest;
 
-use rand::{CryptoRng, RngCore};
+use alloc::{vec, vec::Vec};
+use rand::{CryptoRng, Rng, RngCore};
+use zeroize::Zeroizing;
 
 use num_bigint_dig::{prime::probably_prime, BigUint, ModInverse};
 use num_traits::{cast::ToPrimitive, PrimInt};
@@ -28,43 +36,65 @@ pub fn sample_vec_cbd<R: RngCore + CryptoRng>(
         return Err("The variance should be between 1 and 16");
     }
 
-    let mut out = Vec::with_capacity(vector_size);
-
+    // Each sample is derived from a window of 4 * variance consecutive bits of
+    // a buffer of random words, which is filled at once. The buffer is padded
+    // with one word so that two consecutive words can be read at the start of
+    // every window.
     let number_bits = 4 * variance;
-    let mask_add = ((u64::MAX >> (64 - number_bits)) >> (2 * variance)) as u128;
-    let mask_sub = mask_add << (2 * variance);
+    let number_words = (vector_size * number_bits).div_ceil(64);
+    let mut words = Zeroizing::new(vec![0u64; number_words + 1]);
+    rng.fill(&mut words[..number_words]);
 
-    let mut current_pool = 0u128;
-    let mut current_pool_nbits = 0;
+    let mask_add = u64::MAX >> (64 - 2 * variance);
+    let mask_sub = mask_add << (2 * variance);
 
-    for _ in 0..vector_size {
-        if current_pool_nbits < number_bits {
-            current_pool |= (rng.next_u64() as u128) << current_pool_nbits;
-            current_pool_nbits += 64;
-        }
-        debug_assert!(current_pool_nbits >= number_bits);
-        out.push(
-            ((current_pool & mask_add).count_ones() as i64)
-                - ((current_pool & mask_sub).count_ones() as i64),
-        );
-        current_pool >>= number_bits;
-        current_pool_nbits -= number_bits;
-    }
+    let out = (0..vector_size)
+        .map(|i| {
+            let offset = i * number_bits;
+            let (index, shift) = (offset / 64, offset % 64);
+            let window =
+                ((((words[index + 1] as u128) << 64) | (words[index] as u128)) >> shift) as u64;
+            ((window & mask_add).count_ones() as i64) - ((window & mask_sub).count_ones() as i64)
+        })
+        .collect();
 
     Ok(out)
 }
 
+/// Sample a vector of independent centered binomial distributions of a given
+/// variance using the provided random number generator, as in
+/// [`sample_vec_cbd`], and return it in a buffer which is zeroized on drop.
+/// Returns an error if the variance is strictly larger than 16.
+pub fn sample_vec_cbd_from_rng<R: RngCore + CryptoRng>(
+    vector_size: usize,
+    variance: usize,
+    rng: &mut R,
+) -> Result<Zeroizing<Vec<i64>>, &'static str> {
+    sample_vec_cbd(vector_size, variance, rng).map(Zeroizing::new)
+}
+
 /// Transcodes a vector of u64 of `nbits`-bit numbers into a vector of bytes.
 pub fn transcode_to_bytes(a: &[u64], nbits: usize) -> Vec<u8> {
+    let mut out = vec![0u8; (a.len() * nbits).div_ceil(8)];
+    transcode_to_bytes_into(a, nbits, &mut out);
+    out
+}
+
+/// Transcodes a vector of u64 of `nbits`-bit numbers into the bytes `out`,
+/// as in [`transcode_to_bytes`] but without allocating.
+///
+/// Panics if `out` is not exactly `ceil(a.len() * nbits / 8)` bytes long.
+pub fn transcode_to_bytes_into(a: &[u64], nbits: usize, out: &mut [u8]) {
     assert!(0 < nbits && nbits <= 64);
 
     let mask = (u64::MAX >> (64 - nbits)) as u128;
     let nbytes = (a.len() * nbits).div_ceil(8);
-    let mut out = Vec::with_capacity(nbytes);
+    assert_eq!(out.len(), nbytes);
 
     let mut current_index = 0;
     let mut current_value = 0u128;
     let mut current_value_nbits = 0;
+    let mut out_index = 0;
     while current_index < a.len() {
         if current_value_nbits < 8 {
             debug_assert!(64 - a[current_index].leading_zeros() <= nbits as u32);
@@ -73,20 +103,20 @@ pub fn transcode_to_bytes(a: &[u64], nbits: usize) -> Vec<u8> {
             current_index += 1;
         }
         while current_value_nbits >= 8 {
-            out.push(current_value as u8);
+            out[out_index] = current_value as u8;
+            out_index += 1;
             current_value >>= 8;
             current_value_nbits -= 8;
         }
     }
     if current_value_nbits > 0 {
         assert!(current_value_nbits < 8);
-        assert_eq!(out.len(), nbytes - 1);
-        out.push(current_value as u8)
+        assert_eq!(out_index, nbytes - 1);
+        out[out_index] = current_value as u8
     } else {
-        assert_eq!(out.len(), nbytes);
+        assert_eq!(out_index, nbytes);
         assert_eq!(current_value, 0);
     }
-    out
 }
 
 /// Transcodes a vector of u8 into a vector of u64 of `nbits`-bit numbers.
@@ -181,13 +211,14 @@ pub fn variance<T: PrimInt>(values: &[T]) -> f64 {
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
-    use rand::{thread_rng, RngCore};
+    use rand::{thread_rng, RngCore, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     use crate::variance;
 
     use super::{
-        inverse, is_prime, sample_vec_cbd, transcode_bidirectional, transcode_from_bytes,
-        transcode_to_bytes,
+        inverse, is_prime, sample_vec_cbd, sample_vec_cbd_from_rng, transcode_bidirectional,
+        transcode_from_bytes, transcode_to_bytes, transcode_to_bytes_into,
     };
 
     #[test]
@@ -229,6 +260,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sample_cbd_from_rng() {
+        assert!(sample_vec_cbd_from_rng(10, 0, &mut thread_rng()).is_err());
+        assert!(sample_vec_cbd_from_rng(10, 17, &mut thread_rng()).is_err());
+
+        for var in 1..=16 {
+            // The distribution is the same as the one of `sample_vec_cbd`.
+            let mut rng = ChaCha8Rng::seed_from_u64(var as u64);
+            let mut rng_copy = rng.clone();
+            let v = sample_vec_cbd_from_rng(100000, var, &mut rng).unwrap();
+            assert_eq!(*v, sample_vec_cbd(100000, var, &mut rng_copy).unwrap());
+
+            // Verifies that the mean is close to 0, and that the variance is correct.
+            let mean = v.iter().sum::<i64>() as f64 / v.len() as f64;
+            assert!(mean.abs() < 0.1);
+            assert!(variance(&v).round() == (var as f64));
+        }
+
+        // Golden vector for a fixed seed.
+        let mut rng = ChaCha8Rng::from_seed([0u8; 32]);
+        let v = sample_vec_cbd_from_rng(16, 10, &mut rng).unwrap();
+        assert_eq!(
+            *v,
+            [-2, -6, 5, -1, 3, -3, -1, 1, 0, 4, -1, -7, -2, 5, -1, -7]
+        );
+    }
+
     #[test]
     fn transcode_self_consistency() {
         let mut rng = thread_rng();
@@ -241,6 +299,9 @@ mod tests {
                     .map(|i| (*i) & (u64::MAX >> (64 - input_nbits)))
                     .collect_vec();
                 let bytes = transcode_to_bytes(&masked_input, input_nbits);
+                let mut bytes_into = vec![0xffu8; bytes.len()];
+                transcode_to_bytes_into(&masked_input, input_nbits, &mut bytes_into);
+                assert_eq!(bytes, bytes_into);
                 let bytes_as_u64 = transcode_bidirectional(&masked_input, input_nbits, 8);
                 assert_eq!(bytes, bytes_as_u64.iter().map(|e| *e as u8).collect_vec());
 
@@ -9,7 +9,7 @@ use fhe_math::{
     rns::RnsContext,
     rq::{Poly, Representation},
 };
-use fhe_traits::{DeserializeWithContext, Serialize};
+use fhe_traits::Serialize;
 use itertools::{izip, Itertools};
 use num_bigint::BigUint;
 use rand::{CryptoRng, Rng, RngCore, SeedableRng};
@@ -158,9 +158,8 @@ impl KeySwitchingKey {
                 *a_s.as_mut() *= s.as_ref();
                 a_s.change_representation(Representation::PowerBasis);
 
-                let mut b =
-                    Poly::small(a_s.ctx(), Representation::PowerBasis, sk.par.variance, rng)?;
-                b -= &a_s;
+                let e = Poly::small(a_s.ctx(), Representation::PowerBasis, sk.par.variance, rng)?;
+                let mut b = e.as_ref() - a_s.as_ref();
 
                 let gi = rns.get_garner(i).unwrap();
                 let g_i_from = Zeroizing::new(gi * from);
@@ -212,9 +211,8 @@ impl KeySwitchingKey {
                 *a_s.as_mut() *= s.as_ref();
                 a_s.change_representation(Representation::PowerBasis);
 
-                let mut b =
-                    Poly::small(a_s.ctx(), Representation::PowerBasis, sk.par.variance, rng)?;
-                b -= &a_s;
+                let e = Poly::small(a_s.ctx(), Representation::PowerBasis, sk.par.variance, rng)?;
+                let mut b = e.as_ref() - a_s.as_ref();
 
                 let power = BigUint::from(1u64 << (i * log_base));
                 b += &(from * &power);
@@ -374,20 +372,26 @@ impl BfvTryConvertFrom<&KeySwitchingKeyProto> for KeySwitchingKey {
             Some(unwrapped.unwrap())
         };
 
+        // The key switching key is public, as when it is generated.
         let c1 = if let Some(seed) = seed {
             Self::generate_c1(ctx_ksk, seed, value.c0.len())
         } else {
             value
                 .c1
                 .iter()
-                .map(|c1i| Poly::from_bytes(c1i, ctx_ksk).map_err(Error::MathError))
+                .map(|c1i| {
+                    Poly::from_bytes_with_variable_time(c1i, ctx_ksk, true)
+                        .map_err(Error::MathError)
+                })
                 .collect::<Result<Vec<Poly>>>()?
         };
 
         let c0 = value
             .c0
             .iter()
-            .map(|c0i| Poly::from_bytes(c0i, ctx_ksk).map_err(Error::MathError))
+            .map(|c0i| {
+                Poly::from_bytes_with_variable_time(c0i, ctx_ksk, true).map_err(Error::MathError)
+            })
             .collect::<Result<Vec<Poly>>>()?;
 
         Ok(Self {
@@ -477,7 +481,7 @@ mod tests {
 
                 input.change_representation(Representation::Ntt);
                 p.change_representation(Representation::Ntt);
-                let mut c3 = &input * &p;
+                let mut c3 = &input * p.as_ref();
                 c3.change_representation(Representation::PowerBasis);
 
                 let rns = RnsContext::new(&params.moduli)?;
@@ -515,7 +519,7 @@ mod tests {
 
                 input.change_representation(Representation::Ntt);
                 p.change_representation(Representation::Ntt);
-                let mut c3 = &input * &p;
+                let mut c3 = &input * p.as_ref();
                 c3.change_representation(Representation::PowerBasis);
 
                 let rns = RnsContext::new(ctx.moduli())?;
@@ -542,7 +546,13 @@ mod tests {
             let p = Poly::small(ctx, Representation::PowerBasis, 10, &mut rng)?;
             let ksk = KeySwitchingKey::new(&sk, &p, 0, 0, &mut rng)?;
             let ksk_proto = KeySwitchingKeyProto::from(&ksk);
-            assert_eq!(ksk, KeySwitchingKey::try_convert_from(&ksk_proto, &params)?);
+            let ksk_deserialized = KeySwitchingKey::try_convert_from(&ksk_proto, &params)?;
+            assert_eq!(ksk, ksk_deserialized);
+            assert!(ksk_deserialized
+                .c0
+                .iter()
+                .chain(ksk_deserialized.c1.iter())
+                .all(|c| c.allows_variable_time()));
         }
         Ok(())
     }
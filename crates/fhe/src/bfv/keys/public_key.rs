@@ -54,24 +54,9 @@ impl FheEncrypter<Plaintext, Ciphertext> for PublicKey {
         }
 
         let ctx = self.par.ctx_at_level(ct.level)?;
-        let u = Zeroizing::new(Poly::small(
-            ctx,
-            Representation::Ntt,
-            self.par.variance,
-            rng,
-        )?);
-        let e1 = Zeroizing::new(Poly::small(
-            ctx,
-            Representation::Ntt,
-            self.par.variance,
-            rng,
-        )?);
-        let e2 = Zeroizing::new(Poly::small(
-            ctx,
-            Representation::Ntt,
-            self.par.variance,
-            rng,
-        )?);
+        let u = Poly::small(ctx, Representation::Ntt, self.par.variance, rng)?;
+        let e1 = Poly::small(ctx, Representation::Ntt, self.par.variance, rng)?;
+        let e2 = Poly::small(ctx, Representation::Ntt, self.par.variance, rng)?;
 
         let m = Zeroizing::new(pt.to_poly());
         let mut c0 = u.as_ref() * &ct[0];
@@ -185,10 +185,8 @@ mod tests {
                 // c1, c2) encrypting 0.
                 let mut c2 = Poly::random(ctx, Representation::Ntt, &mut rng);
                 let c1 = Poly::random(ctx, Representation::Ntt, &mut rng);
-                let mut c0 = Poly::small(ctx, Representation::PowerBasis, 16, &mut rng)?;
-                c0.change_representation(Representation::Ntt);
-                c0 -= &(&c1 * &s);
-                c0 -= &(&c2 * &s2);
+                let e = Poly::small(ctx, Representation::Ntt, 16, &mut rng)?;
+                let c0 = &(e.as_ref() - &(&c1 * &s)) - &(&c2 * &s2);
                 let mut ct = Ciphertext::new(vec![c0.clone(), c1.clone(), c2.clone()], &params)?;
 
                 // Relinearize the extended ciphertext!
@@ -245,10 +243,8 @@ mod tests {
                         // s^2, c1, c2) encrypting 0.
                         let mut c2 = Poly::random(ctx, Representation::Ntt, &mut rng);
                         let c1 = Poly::random(ctx, Representation::Ntt, &mut rng);
-                        let mut c0 = Poly::small(ctx, Representation::PowerBasis, 16, &mut rng)?;
-                        c0.change_representation(Representation::Ntt);
-                        c0 -= &(&c1 * &s);
-                        c0 -= &(&c2 * &s2);
+                        let e = Poly::small(ctx, Representation::Ntt, 16, &mut rng)?;
+                        let c0 = &(e.as_ref() - &(&c1 * &s)) - &(&c2 * &s2);
                         let mut ct =
                             Ciphertext::new(vec![c0.clone(), c1.clone(), c2.clone()], &params)?;
 
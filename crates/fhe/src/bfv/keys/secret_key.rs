@@ -109,9 +109,9 @@ impl SecretKey {
         let mut a = Poly::random_from_seed(p.ctx(), Representation::Ntt, seed);
         let a_s = Zeroizing::new(&a * s.as_ref());
 
-        let mut b = Poly::small(p.ctx(), Representation::Ntt, self.par.variance, rng)
+        let e = Poly::small(p.ctx(), Representation::Ntt, self.par.variance, rng)
             .map_err(Error::MathError)?;
-        b -= &a_s;
+        let mut b = e.as_ref() - a_s.as_ref();
         b += p;
 
         // It is now safe to enable variable time computations.
@@ -46,8 +46,7 @@ impl Plaintext {
         let ctx = self.par.ctx_at_level(self.level).unwrap();
         let mut m =
             Poly::try_convert_from(m_v.as_ref(), ctx, false, Representation::PowerBasis).unwrap();
-        m.change_representation(Representation::Ntt);
-        m *= &self.par.delta[self.level];
+        m.change_representation_and_mul(&self.par.delta[self.level]);
         m
     }
 
@@ -104,9 +103,7 @@ impl TryConvertFrom<&Plaintext> for Poly {
                 .ctx_at_level(pt.level())
                 .map_err(|e| fhe_math::Error::Default(e.to_string()))?
         {
-            Err(fhe_math::Error::Default(
-                "Incompatible contexts".to_string(),
-            ))
+            Err(fhe_math::Error::InvalidContext)
         } else {
             Poly::try_convert_from(
                 pt.value.as_ref(),
@@ -48,7 +48,7 @@ impl PublicKeyShare {
         s.change_representation(Representation::Ntt);
 
         // Sample error
-        let e = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+        let e = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
         // Create p0_i share
         let mut p0_share = -crp.poly.clone();
         p0_share.disallow_variable_time_computations();
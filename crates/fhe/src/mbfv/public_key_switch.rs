@@ -59,10 +59,10 @@ impl PublicKeySwitchShare {
         s.change_representation(Representation::Ntt);
         s.disallow_variable_time_computations();
 
-        let u = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+        let u = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
         // TODO this should be exponential in ciphertext noise!
-        let e0 = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
-        let e1 = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+        let e0 = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
+        let e1 = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
 
         let mut h0 = pk_ct[0].clone();
         h0.disallow_variable_time_computations();
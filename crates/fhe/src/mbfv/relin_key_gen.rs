@@ -91,7 +91,7 @@ impl<'a, 'b> RelinKeyGenerator<'a, 'b> {
                     .to_string(),
             ))
         } else {
-            let u = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+            let u = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
             Ok(Self { sk_share, crp, u })
         }
     }
@@ -162,7 +162,7 @@ impl RelinKeyShare<R1> {
                 let mut w_s = Zeroizing::new(w * s.as_ref());
                 w_s.change_representation(Representation::Ntt);
 
-                let e = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+                let e = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
 
                 let mut h = -a.poly.clone();
                 h.disallow_variable_time_computations();
@@ -197,7 +197,7 @@ impl RelinKeyShare<R1> {
                 let mut h = a.poly.clone();
                 h.disallow_variable_time_computations();
                 h.change_representation(Representation::Ntt);
-                let e = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+                let e = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
                 h *= s.as_ref();
                 h += e.as_ref();
                 Ok(h)
@@ -268,7 +268,7 @@ impl RelinKeyShare<R2> {
         let h0 = r1_h0
             .iter()
             .map(|h| {
-                let e = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+                let e = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
 
                 let mut h_prime = h.clone();
                 h_prime.disallow_variable_time_computations();
@@ -306,7 +306,7 @@ impl RelinKeyShare<R2> {
                 let mut h_prime = h.clone();
                 h_prime.disallow_variable_time_computations();
                 h_prime.change_representation(Representation::Ntt);
-                let e = Zeroizing::new(Poly::small(ctx, Representation::Ntt, par.variance, rng)?);
+                let e = Poly::small(ctx, Representation::Ntt, par.variance, rng)?;
                 h_prime *= u_s.as_ref();
                 h_prime += e.as_ref();
                 Ok(h_prime)
@@ -72,12 +72,7 @@ impl SecretKeySwitchShare {
 
         // Sample error
         // TODO this should be exponential in ciphertext noise!
-        let e = Zeroizing::new(Poly::small(
-            ct[0].ctx(),
-            Representation::Ntt,
-            par.variance,
-            rng,
-        )?);
+        let e = Poly::small(ct[0].ctx(), Representation::Ntt, par.variance, rng)?;
 
         // Create h_i share
         let mut h_share = s_in.as_ref() - s_out.as_ref();